@@ -9,7 +9,7 @@ pub mod ping_pong {
         serve_to: Option<Id>,
     }
 
-    #[derive(Eq, Ord, PartialEq, PartialOrd, Clone, Debug, Hash)]
+    #[derive(Eq, Ord, PartialEq, PartialOrd, Clone, Debug, Hash, serde::Serialize)]
     pub enum PingPongMsg {
         Ping(u32),
         Pong(u32),
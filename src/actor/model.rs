@@ -4,7 +4,7 @@ use crate::actor::{
     is_no_op, is_no_op_with_timer, Actor, ActorModelState, Command, Envelope, Id, Network, Out,
     RandomChoices,
 };
-use crate::{Expectation, Model, Path, Property};
+use crate::{Expectation, Ltl, Model, Path, Property, Query, Rewrite};
 use std::borrow::Cow;
 use std::collections::HashMap;
 use std::fmt::{Debug, Display, Formatter};
@@ -36,9 +36,17 @@ where
     /// Maximum number of actors that can be contemporarily crashed
     pub max_crashes: usize,
     pub properties: Vec<Property<ActorModel<A, C, H>>>,
-    pub record_msg_in: fn(cfg: &C, history: &H, envelope: Envelope<&A::Msg>) -> Option<H>,
-    pub record_msg_out: fn(cfg: &C, history: &H, envelope: Envelope<&A::Msg>) -> Option<H>,
-    pub within_boundary: fn(cfg: &C, state: &ActorModelState<A, H>) -> bool,
+    #[allow(clippy::type_complexity)]
+    pub record_msg_in: Arc<dyn Fn(&C, &H, Envelope<&A::Msg>) -> Option<H> + Send + Sync>,
+    #[allow(clippy::type_complexity)]
+    pub record_msg_out: Arc<dyn Fn(&C, &H, Envelope<&A::Msg>) -> Option<H> + Send + Sync>,
+    #[allow(clippy::type_complexity)]
+    pub within_boundary: Arc<dyn Fn(&C, &ActorModelState<A, H>) -> bool + Send + Sync>,
+    /// Groups of interchangeable actors for symmetry reduction. See
+    /// [`ActorModel::symmetric_actors`].
+    symmetric_groups: Arc<Vec<Vec<Id>>>,
+    /// Named state queries for Explorer. See [`ActorModel::query`].
+    queries: Vec<Query<ActorModel<A, C, H>>>,
 }
 
 /// Indicates possible steps that an actor system can take as it evolves.
@@ -62,6 +70,39 @@ pub enum ActorModelAction<Msg, Timer, Random> {
     },
 }
 
+impl<Msg, Timer, Random> ActorModelAction<Msg, Timer, Random> {
+    /// Indicates whether this is an [`ActorModelAction::Deliver`], such as for declaring
+    /// [fairness](crate::CheckerBuilder::weak_fairness).
+    pub fn is_deliver(&self) -> bool {
+        matches!(self, ActorModelAction::Deliver { .. })
+    }
+
+    /// Indicates whether this is an [`ActorModelAction::Drop`].
+    pub fn is_drop(&self) -> bool {
+        matches!(self, ActorModelAction::Drop(_))
+    }
+
+    /// Indicates whether this is an [`ActorModelAction::Timeout`].
+    pub fn is_timeout(&self) -> bool {
+        matches!(self, ActorModelAction::Timeout(_, _))
+    }
+
+    /// Indicates whether this is an [`ActorModelAction::Crash`].
+    pub fn is_crash(&self) -> bool {
+        matches!(self, ActorModelAction::Crash(_))
+    }
+
+    /// Indicates whether this is an [`ActorModelAction::Recover`].
+    pub fn is_recover(&self) -> bool {
+        matches!(self, ActorModelAction::Recover(_))
+    }
+
+    /// Indicates whether this is an [`ActorModelAction::SelectRandom`].
+    pub fn is_select_random(&self) -> bool {
+        matches!(self, ActorModelAction::SelectRandom { .. })
+    }
+}
+
 /// Indicates whether the network loses messages. Note that as long as invariants do not check
 /// the network state, losing a message is indistinguishable from an unlimited delay, so in
 /// many cases you can improve model checking performance by not modeling message loss.
@@ -104,9 +145,11 @@ where
             lossy_network: LossyNetwork::No,
             max_crashes: 0,
             properties: Default::default(),
-            record_msg_in: |_, _, _| None,
-            record_msg_out: |_, _, _| None,
-            within_boundary: |_, _| true,
+            record_msg_in: Arc::new(|_, _, _| None),
+            record_msg_out: Arc::new(|_, _, _| None),
+            within_boundary: Arc::new(|_, _| true),
+            symmetric_groups: Default::default(),
+            queries: Default::default(),
         }
     }
 
@@ -143,32 +186,55 @@ where
     }
 
     /// Adds a [`Property`] to this model.
-    #[allow(clippy::type_complexity)]
     pub fn property(
         mut self,
         expectation: Expectation,
         name: &'static str,
-        condition: fn(&ActorModel<A, C, H>, &ActorModelState<A, H>) -> bool,
+        condition: impl Fn(&ActorModel<A, C, H>, &ActorModelState<A, H>) -> bool + Send + Sync + 'static,
     ) -> Self {
         assert!(
             !self.properties.iter().any(|p| p.name == name),
             "Property with name '{name}' already exists"
         );
-        self.properties.push(Property {
-            expectation,
-            name,
-            condition,
+        self.properties.push(match expectation {
+            Expectation::Always => Property::always(name, condition),
+            Expectation::Eventually => Property::eventually(name, condition),
+            Expectation::Sometimes => Property::sometimes(name, condition),
         });
         self
     }
 
+    /// Adds a temporal logic [`Property`] to this model. See [`Property::ltl`].
+    pub fn ltl_property(mut self, name: &'static str, formula: Ltl<ActorModel<A, C, H>>) -> Self {
+        assert!(
+            !self.properties.iter().any(|p| p.name == name),
+            "Property with name '{name}' already exists"
+        );
+        self.properties.push(Property::ltl(name, formula));
+        self
+    }
+
+    /// Adds a [`Query`] to this model, for which Explorer can search.
+    pub fn query(
+        mut self,
+        name: &'static str,
+        condition: impl Fn(&ActorModel<A, C, H>, &ActorModelState<A, H>) -> bool + Send + Sync + 'static,
+    ) -> Self {
+        assert!(
+            !self.queries.iter().any(|q| q.name == name),
+            "Query with name '{name}' already exists"
+        );
+        self.queries.push(Query::new(name, condition));
+        self
+    }
+
     /// Defines whether/how an incoming message contributes to relevant history. Returning
     /// `Some(new_history)` updates the relevant history, while `None` does not.
     pub fn record_msg_in(
         mut self,
-        record_msg_in: fn(cfg: &C, history: &H, Envelope<&A::Msg>) -> Option<H>,
+        record_msg_in: impl Fn(&C, &H, Envelope<&A::Msg>) -> Option<H> + Send + Sync + 'static,
     ) -> Self {
-        self.record_msg_in = record_msg_in;
+        self.record_msg_in = Arc::new(record_msg_in);
         self
     }
 
@@ -176,21 +242,81 @@ where
     /// `Some(new_history)` updates the relevant history, while `None` does not.
     pub fn record_msg_out(
         mut self,
-        record_msg_out: fn(cfg: &C, history: &H, Envelope<&A::Msg>) -> Option<H>,
+        record_msg_out: impl Fn(&C, &H, Envelope<&A::Msg>) -> Option<H> + Send + Sync + 'static,
     ) -> Self {
-        self.record_msg_out = record_msg_out;
+        self.record_msg_out = Arc::new(record_msg_out);
         self
     }
 
     /// Indicates whether a state is within the state space that should be model checked.
     pub fn within_boundary(
         mut self,
-        within_boundary: fn(cfg: &C, state: &ActorModelState<A, H>) -> bool,
+        within_boundary: impl Fn(&C, &ActorModelState<A, H>) -> bool + Send + Sync + 'static,
     ) -> Self {
-        self.within_boundary = within_boundary;
+        self.within_boundary = Arc::new(within_boundary);
+        self
+    }
+
+    /// Marks a group of actors as interchangeable for symmetry reduction via
+    /// [`ActorModel::representative`], which then only permutes actors within each marked group
+    /// (e.g. among clients and among servers), leaving the other actors in place. All actors are
+    /// interchangeable if no group is marked.
+    ///
+    /// Actors in a group must behave identically up to renaming of [`Id`]s. Their states are
+    /// ordered to choose a representative, so they must implement [`Ord`], and they (along with
+    /// messages, random choices, storage, and history) must implement [`Rewrite<Id>`] so that
+    /// references to permuted actors are updated.
+    ///
+    /// [`Rewrite<Id>`]: crate::Rewrite
+    pub fn symmetric_actors(mut self, ids: impl IntoIterator<Item = Id>) -> Self {
+        let group: Vec<Id> = ids.into_iter().collect();
+        assert!(
+            !group.iter().any(|id| self
+                .symmetric_groups
+                .iter()
+                .flatten()
+                .any(|other| other == id)),
+            "Actors can only be in one symmetric group"
+        );
+        Arc::make_mut(&mut self.symmetric_groups).push(group);
         self
     }
 
+    /// Returns a function for [`CheckerBuilder::symmetry_fn`] that maps each state to a
+    /// representative, only permuting actors within the groups marked by
+    /// [`ActorModel::symmetric_actors`], or every actor if there are none.
+    ///
+    /// ```
+    /// # use stateright::actor::*; use stateright::*; use std::borrow::Cow;
+    /// # #[derive(Clone)] struct A;
+    /// # impl Actor for A {
+    /// #     type Msg = (); type State = (); type Timer = (); type Random = (); type Storage = ();
+    /// #     fn on_start(&self, _: Id, _: &Option<()>, _: &mut Out<Self>) {}
+    /// # }
+    /// let model = ActorModel::new((), ())
+    ///     .actors([A, A, A])
+    ///     .symmetric_actors([Id::from(1), Id::from(2)]);
+    /// let representative = model.representative();
+    /// model.checker().symmetry_fn(representative).spawn_dfs().join();
+    /// ```
+    ///
+    /// [`CheckerBuilder::symmetry_fn`]: crate::CheckerBuilder::symmetry_fn
+    pub fn representative(
+        &self,
+    ) -> impl Fn(&ActorModelState<A, H>) -> ActorModelState<A, H> + Send + Sync + 'static
+    where
+        A: 'static,
+        A::Msg: Rewrite<Id>,
+        A::State: Ord + Rewrite<Id>,
+        A::Random: Rewrite<Id>,
+        A::Storage: Rewrite<Id>,
+        C: 'static,
+        H: Rewrite<Id> + 'static,
+    {
+        let groups = Arc::clone(&self.symmetric_groups);
+        move |state| state.representative_within(&groups)
+    }
+
     /// Updates the actor state, sends messages, and configures the timers.
     fn process_commands(&self, id: Id, commands: Out<A>, state: &mut ActorModelState<A, H>) {
         let index = usize::from(id);
@@ -515,6 +641,33 @@ where
         }
     }
 
+    /// Steps of distinct actors commute unless they update state shared by every actor: the
+    /// history, or the last delivered message that an unordered duplicating network remembers.
+    /// Crashes also do not commute, as they are limited in number.
+    fn step_process(
+        &self,
+        last_state: &Self::State,
+        action: &Self::Action,
+        next_state: &Self::State,
+    ) -> Option<usize> {
+        if crate::fingerprint(&last_state.history) != crate::fingerprint(&next_state.history) {
+            return None;
+        }
+        match action {
+            ActorModelAction::Deliver { .. }
+                if matches!(self.init_network, Network::UnorderedDuplicating(..)) =>
+            {
+                None
+            }
+            ActorModelAction::Deliver { dst: id, .. }
+            | ActorModelAction::Drop(Envelope { dst: id, .. })
+            | ActorModelAction::Timeout(id, _)
+            | ActorModelAction::Recover(id)
+            | ActorModelAction::SelectRandom { actor: id, .. } => Some(usize::from(*id)),
+            ActorModelAction::Crash(_) => None,
+        }
+    }
+
     fn format_action(&self, action: &Self::Action) -> String {
         match action {
             ActorModelAction::Deliver { src, dst, msg } => {
@@ -871,6 +1024,10 @@ where
         self.properties.clone()
     }
 
+    fn queries(&self) -> Vec<Query<Self>> {
+        self.queries.clone()
+    }
+
     fn within_boundary(&self, state: &Self::State) -> bool {
         (self.within_boundary)(&self.cfg, state)
     }
@@ -1165,6 +1322,33 @@ mod test {
         );
     }
 
+    #[test]
+    fn accepts_closures_capturing_runtime_values() {
+        let (target, bound) = (3, 4);
+        let checker = PingPongCfg {
+            max_nat: 5,
+            maintains_history: false,
+        }
+        .into_model()
+        .lossy_network(LossyNetwork::No)
+        .property(
+            Expectation::Sometimes,
+            "can reach target",
+            move |_, state| state.actor_states.iter().any(|s| **s == target),
+        )
+        .within_boundary(move |_, state| state.actor_states.iter().all(|s| **s <= bound))
+        .checker()
+        .spawn_bfs()
+        .join();
+        assert_eq!(checker.unique_state_count(), 9);
+        assert!(checker
+            .discovery("can reach target")
+            .unwrap()
+            .last_state()
+            .actor_states
+            .contains(&Arc::new(target)));
+    }
+
     #[test]
     fn might_never_reach_beyond_max() {
         // ^ and in fact will never. This is a subtle distinction: we're exercising a
@@ -3,7 +3,7 @@
 use serde::Serialize;
 
 use crate::actor::{Actor, Id, Network};
-use crate::util::HashableHashMap;
+use crate::util::{DenseNatMap, HashableHashMap};
 use crate::{Representative, Rewrite, RewritePlan};
 use std::fmt::Debug;
 use std::hash::{Hash, Hasher};
@@ -183,7 +183,43 @@ where
     H: Rewrite<Id>,
 {
     fn representative(&self) -> Self {
-        let plan = RewritePlan::from_values_to_sort(&self.actor_states);
+        self.representative_within(&[])
+    }
+}
+
+impl<A, H> ActorModelState<A, H>
+where
+    A: Actor,
+    A::Msg: Rewrite<Id>,
+    A::State: Ord + Rewrite<Id>,
+    A::Random: Rewrite<Id>,
+    A::Storage: Rewrite<Id>,
+    H: Rewrite<Id>,
+{
+    /// Like [`Representative::representative`], but only permutes actors within each group, or
+    /// every actor if there are no groups. See [`ActorModel::representative`].
+    ///
+    /// [`ActorModel::representative`]: crate::actor::ActorModel::representative
+    pub(crate) fn representative_within(&self, groups: &[Vec<Id>]) -> Self {
+        let plan = if groups.is_empty() {
+            RewritePlan::from_values_to_sort(&self.actor_states)
+        } else {
+            // Sorts the actors of each group into the indices occupied by that group, leaving
+            // other actors in place.
+            let mut map: Vec<Id> = (0..self.actor_states.len()).map(Id::from).collect();
+            for group in groups {
+                let mut indices = group.clone();
+                indices.sort();
+                let mut members = indices.clone();
+                members.sort_by(|a, b| {
+                    self.actor_states[usize::from(*a)].cmp(&self.actor_states[usize::from(*b)])
+                });
+                for (index, member) in indices.into_iter().zip(members) {
+                    map[usize::from(member)] = index;
+                }
+            }
+            RewritePlan::new(DenseNatMap::from(map), |&id, map| *map.get(id).unwrap())
+        };
         Self {
             actor_states: plan.reindex(&self.actor_states),
             network: self.network.rewrite(&plan),
@@ -199,8 +235,11 @@ where
 #[cfg(test)]
 mod test {
     use crate::actor::timers::Timers;
-    use crate::actor::{Actor, ActorModelState, Envelope, Id, Network, Out, RandomChoices};
-    use crate::{Representative, Rewrite, RewritePlan};
+    use crate::actor::{
+        Actor, ActorModel, ActorModelState, Envelope, Id, Network, Out, RandomChoices,
+    };
+    use crate::{Checker, Expectation, Model, Representative, Rewrite, RewritePlan};
+    use std::borrow::Cow;
     use std::sync::Arc;
 
     #[test]
@@ -285,6 +324,134 @@ mod test {
         actor_storages: vec![None; 3],});
     }
 
+    #[test]
+    fn only_permutes_actors_within_symmetric_groups() {
+        let empty_timers = Timers::new();
+        let mut non_empty_timers = Timers::new();
+        non_empty_timers.set(());
+        #[rustfmt::skip]
+        let state = ActorModelState::<A, History> {
+            actor_states: vec![
+                Arc::new(ActorState { acks: vec![]}),
+                Arc::new(ActorState { acks: vec![Id::from(0), Id::from(3)]}),
+                Arc::new(ActorState { acks: vec![Id::from(1)]}),
+                Arc::new(ActorState { acks: vec![]}),
+            ],
+            network: Network::new_unordered_duplicating([
+                Envelope { src: 1.into(), dst: 0.into(), msg: "Ack(X)" },
+            ]),
+            timers_set: vec![empty_timers.clone(), non_empty_timers.clone(), empty_timers.clone(), empty_timers.clone()],
+            random_choices: vec![RandomChoices::default(); 4],
+            crashed: vec![false; 4],
+            history: History {
+                send_sequence: vec![1.into(), 2.into()],
+            },
+            actor_storages: vec![None; 4],
+        };
+        // Only Id(1) and Id(3) are swapped, although sorting every actor state would also move
+        // Id(2).
+        let groups = [vec![Id::from(1), Id::from(3)]];
+        #[rustfmt::skip]
+        assert_eq!(state.representative_within(&groups), ActorModelState {
+            actor_states: vec![
+                Arc::new(ActorState { acks: vec![]}),
+                Arc::new(ActorState { acks: vec![]}),
+                Arc::new(ActorState { acks: vec![Id::from(3)]}),
+                Arc::new(ActorState { acks: vec![Id::from(0), Id::from(1)]}),
+            ],
+            network: Network::new_unordered_duplicating([
+                Envelope { src: 3.into(), dst: 0.into(), msg: "Ack(X)" },
+            ]),
+            timers_set: vec![empty_timers.clone(), empty_timers.clone(), empty_timers, non_empty_timers],
+            random_choices: vec![RandomChoices::default(); 4],
+            crashed: vec![false; 4],
+            history: History {
+                send_sequence: vec![3.into(), 2.into()],
+            },
+            actor_storages: vec![None; 4],
+        });
+    }
+
+    #[test]
+    fn symmetric_actors_reduce_state_space() {
+        #[derive(Clone)]
+        enum PingActor {
+            Server,
+            Client { server: Id },
+        }
+        #[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+        enum PingState {
+            Server { pings: u8 },
+            Client { ponged: bool },
+        }
+        impl Rewrite<Id> for PingState {
+            fn rewrite<S>(&self, _: &RewritePlan<Id, S>) -> Self {
+                self.clone()
+            }
+        }
+        impl Actor for PingActor {
+            type Msg = bool; // Whether the message is a ping (rather than a pong).
+            type State = PingState;
+            type Timer = ();
+            type Random = ();
+            type Storage = ();
+            fn on_start(&self, _: Id, _: &Option<Self::Storage>, o: &mut Out<Self>) -> PingState {
+                match self {
+                    PingActor::Server => PingState::Server { pings: 0 },
+                    PingActor::Client { server } => {
+                        o.send(*server, true);
+                        PingState::Client { ponged: false }
+                    }
+                }
+            }
+            fn on_msg(
+                &self,
+                _: Id,
+                state: &mut Cow<Self::State>,
+                src: Id,
+                is_ping: bool,
+                o: &mut Out<Self>,
+            ) {
+                match state.to_mut() {
+                    PingState::Server { pings } if is_ping => {
+                        *pings += 1;
+                        o.send(src, false);
+                    }
+                    PingState::Client { ponged } if !is_ping => *ponged = true,
+                    _ => {}
+                }
+            }
+        }
+        let model = || {
+            ActorModel::new((), ())
+                .actor(PingActor::Server)
+                .actors((0..3).map(|_| PingActor::Client { server: 0.into() }))
+                .init_network(Network::new_unordered_nonduplicating([]))
+                .property(Expectation::Always, "one ping each", |_, state| {
+                    *state.actor_states[0] <= PingState::Server { pings: 3 }
+                })
+                .property(Expectation::Sometimes, "all ponged", |_, state| {
+                    state.actor_states[1..]
+                        .iter()
+                        .all(|s| **s == PingState::Client { ponged: true })
+                })
+        };
+
+        let checker = model().checker().spawn_dfs().join();
+        assert_eq!(checker.unique_state_count(), 27);
+        checker.assert_any_discovery("all ponged");
+
+        let model = model().symmetric_actors((1..4).map(Id::from));
+        let representative = model.representative();
+        let checker = model
+            .checker()
+            .symmetry_fn(representative)
+            .spawn_dfs()
+            .join();
+        assert_eq!(checker.unique_state_count(), 15);
+        checker.assert_any_discovery("all ponged");
+    }
+
     struct A;
     impl Actor for A {
         type Msg = &'static str;
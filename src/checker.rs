@@ -1,38 +1,70 @@
 //! Private module for selective re-export.
 
 mod bfs;
+mod bitstate;
+mod checkpoint;
+mod chooser;
 mod dfs;
+mod diff;
+mod disk;
+mod distributed;
 mod explorer;
+mod graph;
+mod liveness;
+mod ltl;
 mod on_demand;
 mod path;
+mod replay;
 mod representative;
 mod rewrite;
 mod rewrite_plan;
+mod rng;
 mod simulation;
+mod sleep;
 mod visitor;
 
 use crate::has_discoveries::HasDiscoveries;
 use crate::report::{ReportData, ReportDiscovery, Reporter};
-use crate::{Expectation, Fingerprint, Model};
+use crate::{Fingerprint, Model, Predicate, Property};
+use bitstate::BitstateOptions;
+use checkpoint::CheckpointOptions;
+use dashmap::DashMap;
+use disk::DiskOptions;
+use liveness::Fairness;
 use std::collections::{BTreeMap, HashMap};
 use std::fmt::{Debug, Display};
 use std::hash::Hash;
 use std::num::NonZeroUsize;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::{Arc, Mutex};
 use std::thread::JoinHandle;
-use std::time::{Duration, Instant};
+use std::time::{Duration, Instant, SystemTime};
 
+pub use chooser::{
+    CoverageChooser, CoverageChooserState, SwarmChooser, SwarmChooserState, WeightedChooser,
+    WeightedChooserState,
+};
+pub use dfs::CompletedDepth;
+pub(crate) use disk::Spill;
+pub use explorer::ExplorerOptions;
+pub use ltl::Ltl;
 pub use path::*;
 pub use representative::*;
 pub use rewrite::*;
 pub use rewrite_plan::*;
-pub use simulation::{Chooser, UniformChooser};
+pub use rng::StableRng;
+pub use simulation::{Chooser, RunTermination, SimulationStats, UniformChooser};
+pub(crate) use sleep::{SleepSet, SleepSets};
+#[cfg(feature = "derive")]
+pub use stateright_derive::Rewrite;
 pub use visitor::*;
 
 #[derive(Clone, Copy)]
 pub(crate) enum ControlFlow {
     CheckFingerprint(Fingerprint),
     RunToCompletion,
+    Pause,
 }
 
 /// The classification of a property discovery.
@@ -64,14 +96,25 @@ impl Display for DiscoveryClassification {
               Consider calling spawn_bfs() or spawn_dfs()."]
 pub struct CheckerBuilder<M: Model> {
     model: M,
-    #[allow(clippy::type_complexity)]
-    symmetry: Option<fn(&M::State) -> M::State>,
+    symmetry: Option<Symmetry<M>>,
     target_state_count: Option<NonZeroUsize>,
     target_max_depth: Option<NonZeroUsize>,
     thread_count: usize,
     visitor: Option<Box<dyn CheckerVisitor<M> + Send + Sync>>,
     finish_when: HasDiscoveries,
     timeout: Option<Duration>,
+    fairness: Vec<Fairness<M::Action>>,
+    disk: Option<DiskOptions>,
+    bitstate: Option<BitstateOptions>,
+    checkpoint: Option<CheckpointOptions>,
+    resume_from: Option<PathBuf>,
+    partial_order_reduction: bool,
+    shortest_discoveries: bool,
+    shrink_discoveries: bool,
+    deterministic: bool,
+    iterative_deepening: bool,
+    detect_cycles: bool,
+    explorer: ExplorerOptions<M::State>,
 }
 impl<M: Model> CheckerBuilder<M> {
     pub(crate) fn new(model: M) -> Self {
@@ -84,6 +127,18 @@ impl<M: Model> CheckerBuilder<M> {
             visitor: None,
             finish_when: HasDiscoveries::All,
             timeout: None,
+            fairness: Vec::new(),
+            disk: None,
+            bitstate: None,
+            checkpoint: None,
+            resume_from: None,
+            partial_order_reduction: false,
+            shortest_discoveries: false,
+            shrink_discoveries: false,
+            deterministic: false,
+            iterative_deepening: false,
+            detect_cycles: false,
+            explorer: ExplorerOptions::default(),
         }
     }
 
@@ -140,7 +195,35 @@ impl<M: Model> CheckerBuilder<M> {
     /// - `GET /.states` returns available initial states and their indices.
     /// - `GET /.states/{init_state_index}/{action_index1}/{action_index2}/...` follows the specified
     ///    path of action indices and returns available actions with resulting states and their indices.
+    ///    With [`CheckerBuilder::explorer_diffs`], each resulting state also includes a structural
+    ///    `diff` from the state that the path leads to.
     /// - `GET /.states/.../{invalid-index}` returns 404.
+    /// - `POST /.runtocompletion` starts checking every state rather than only those browsed.
+    /// - `GET /.properties` returns a JSON array with the `name`, `expectation`, and encoded
+    ///   `discovery` path (or `null`) of each property.
+    /// - `GET /.discoveries/{percent-encoded property name}` returns a JSON object with the
+    ///   encoded `path` of the property's discovery and its `steps`, each with a `state`, its
+    ///   `fingerprint`, and the `action` taken from it (`null` for the last state). Also includes
+    ///   `loop_start` if the path ends in a cycle. Returns 404 if the property is unknown or has
+    ///   no discovery.
+    /// - `GET /.graph/{init_state_index}/{action_index1}/...?depth={depth}` returns a JSON object
+    ///   with the `nodes` and `edges` within `depth` steps (2 by default, at most 5) of the state
+    ///   that the path of action indices leads to, or of the initial states if the path is empty.
+    ///   Nodes and edges identify states by fingerprint. Adding `&format=dot` returns the graph in
+    ///   the DOT language instead (see [`Checker::write_dot`]).
+    /// - `GET /.queries` returns a JSON array with the name of each [`Query`](crate::Query) of the
+    ///   model.
+    /// - `GET /.queries/{percent-encoded query name}` searches breadth-first for a state matching
+    ///   the query and returns a JSON object with the encoded `path` to a nearest match (or `null`
+    ///   if none) and the `state_count` of unique states visited. Returns 404 if the query is
+    ///   unknown, or 503 if too many searches are already in progress.
+    /// - `POST /.pause` pauses checking started by `POST /.runtocompletion`, and `POST /.resume`
+    ///   resumes it.
+    /// - `POST /.shutdown` stops the web service, after which this method returns the checker.
+    ///
+    /// Anyone who can reach the addresses can use the API, so prefer a loopback address such as
+    /// `localhost`, or see [`CheckerBuilder::explorer_read_only`] and
+    /// [`CheckerBuilder::explorer_token`].
     pub fn serve(self, addresses: impl std::net::ToSocketAddrs) -> std::sync::Arc<impl Checker<M>>
     where
         M: 'static + Model + Send + Sync,
@@ -150,6 +233,83 @@ impl<M: Model> CheckerBuilder<M> {
         explorer::serve(self, addresses)
     }
 
+    /// Disables the Explorer endpoints that change what the checker or web service is doing:
+    /// `POST /.runtocompletion`, `/.pause`, and `/.resume` respond with 403, as does
+    /// `POST /.shutdown` unless [`CheckerBuilder::explorer_token`] also requires a token. See
+    /// [`CheckerBuilder::serve`].
+    ///
+    /// States are then only checked as they are browsed, which bounds the work that visitors can
+    /// cause, though each request can still explore some states: a query searches at most
+    /// 100,000 states, and a neighbourhood spans at most 5 steps.
+    pub fn explorer_read_only(self) -> Self {
+        Self {
+            explorer: self.explorer.read_only(),
+            ..self
+        }
+    }
+
+    /// Requires each Explorer request to present a shared token, and otherwise responds with
+    /// 401. Requests may present the token as a bearer token in the `Authorization` header or
+    /// as a `token` query parameter, so the UI can be opened via a URL such as
+    /// `http://host:3000/?token=...`, which saves the token in a cookie for later requests. See
+    /// [`CheckerBuilder::serve`].
+    ///
+    /// The token should be hard to guess, such as a long random alphanumeric string. It is sent
+    /// in the clear, as the web service does not support TLS.
+    pub fn explorer_token(self, token: impl Into<String>) -> Self {
+        Self {
+            explorer: self.explorer.token(token),
+            ..self
+        }
+    }
+
+    /// Shows the changes made by each step in Explorer, as a structural diff between the serde
+    /// serializations of the states before and after, in which unchanged actors and envelopes
+    /// are collapsed. See [`CheckerBuilder::serve`].
+    pub fn explorer_diffs(self) -> Self
+    where
+        M::State: serde::Serialize,
+    {
+        Self {
+            explorer: self.explorer.diffs(),
+            ..self
+        }
+    }
+
+    /// Loads the discoveries saved by [`Checker::save_discoveries`], returning a checker that has
+    /// already finished with those discoveries and the saved state counts. Serve it (see
+    /// [`Checker::serve`]) to browse the discoveries in Explorer without checking the model
+    /// again. Other options of the builder are ignored.
+    ///
+    /// The discoveries must have been saved for the same model, and otherwise this returns an
+    /// error of kind [`std::io::ErrorKind::InvalidData`].
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use stateright::*;
+    /// # #[derive(Clone)] struct M;
+    /// # impl Model for M {
+    /// #     type State = (); type Action = ();
+    /// #     fn init_states(&self) -> Vec<()> { vec![()] }
+    /// #     fn actions(&self, _: &(), _: &mut Vec<()>) {}
+    /// #     fn next_state(&self, _: &(), _: ()) -> Option<()> { None }
+    /// # }
+    /// # let model = M;
+    /// // After an overnight run...
+    /// model.clone().checker().spawn_bfs().join().save_discoveries("overnight.json")?;
+    /// // ...browse what it found.
+    /// model.checker().load_discoveries("overnight.json")?.serve("localhost:3000");
+    /// # Ok::<(), std::io::Error>(())
+    /// ```
+    pub fn load_discoveries(self, path: impl Into<PathBuf>) -> std::io::Result<impl Checker<M>>
+    where
+        M::State: Clone + PartialEq,
+        M::Action: Clone + PartialEq,
+    {
+        replay::ReplayChecker::load(self, &path.into())
+    }
+
     /// Spawns a breadth-first search model checker. This traversal strategy uses more memory than
     /// [`CheckerBuilder::spawn_dfs`] but will find the shortest [`Path`] to each discovery if
     /// checking is single threaded (the default behavior, which [`CheckerBuilder::threads`]
@@ -163,11 +323,83 @@ impl<M: Model> CheckerBuilder<M> {
     where
         M: Model + Send + Sync + 'static,
         M::State: Hash + Send + Sync + Clone + PartialEq + 'static,
-        M::Action: Clone + PartialEq,
+        M::Action: Clone + PartialEq + Send + 'static,
     {
         bfs::BfsChecker::spawn(self)
     }
 
+    /// Spawns a breadth-first search model checker that is one of several processes, possibly on
+    /// different machines, which share the work of checking a model. `addresses` lists the address
+    /// at which each process listens for the others, and `index` identifies the address of this
+    /// process. Each process owns the states whose fingerprints map to it, and the processes
+    /// check one depth at a time, sending the states they generate to their owners over TCP. The
+    /// processes must check the same model with the same options.
+    ///
+    /// Every process reports the same discoveries and state counts, which are also those of
+    /// [`CheckerBuilder::spawn_bfs`] when checking [deterministically]. As with that mode,
+    /// symmetry reduction and partial-order reduction do not apply, and neither do
+    /// [`CheckerBuilder::spill_to_disk`] and [`CheckerBuilder::checkpoint`]. States are sent
+    /// serialized, so they must implement [`Serialize`] and [`DeserializeOwned`].
+    ///
+    /// No process has the whole state graph, so paths ending in a cycle are not identified, and
+    /// `eventually` properties are only checked at terminal states.
+    ///
+    /// This call does not block the current thread, and connecting to the other processes happens
+    /// in the background. A process only accepts a connection from another process that presents
+    /// that process's index from the host of that process's address, unless the address is
+    /// unspecified (such as `0.0.0.0`), and ignores other connections. Call [`Checker::join`] to
+    /// block until checking completes, which panics if the processes fail to connect within a
+    /// minute, a connection is lost, or a process connects more than once.
+    ///
+    /// Connections are not otherwise authenticated, so the addresses must not be reachable by
+    /// untrusted hosts.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` does not identify one of the `addresses`, if an address cannot be
+    /// resolved, if [`CheckerBuilder::detect_cycles`] was called, or if the model has a
+    /// [`Property::ltl`].
+    ///
+    /// [deterministically]: CheckerBuilder::deterministic
+    /// [`Serialize`]: serde::Serialize
+    /// [`DeserializeOwned`]: serde::de::DeserializeOwned
+    #[must_use = "Checkers run on background threads. \
+                  Consider calling join() or report(...), for example."]
+    pub fn spawn_distributed_bfs<A: std::net::ToSocketAddrs>(
+        self,
+        index: usize,
+        addresses: impl IntoIterator<Item = A>,
+    ) -> impl Checker<M>
+    where
+        M: Model + Send + Sync + 'static,
+        M::State: Hash
+            + Send
+            + Sync
+            + Clone
+            + PartialEq
+            + serde::Serialize
+            + serde::de::DeserializeOwned
+            + 'static,
+        M::Action: Clone + PartialEq + Send + 'static,
+    {
+        let addresses: Vec<_> = addresses
+            .into_iter()
+            .map(|address| {
+                address
+                    .to_socket_addrs()
+                    .ok()
+                    .and_then(|mut addresses| addresses.next())
+                    .expect("Failed to resolve the address of a process")
+            })
+            .collect();
+        assert!(
+            index < addresses.len(),
+            "Process index {index} is out of range for {} addresses",
+            addresses.len()
+        );
+        distributed::DistributedChecker::spawn(self, index, addresses)
+    }
+
     /// Spawns an on-demand model checker. This traversal strategy doesn't compute any states until
     /// it is asked to, useful for lightweight exploration. Internally the exploration strategy is
     /// very similar to that of [`CheckerBuilder::spawn_bfs`].
@@ -197,7 +429,7 @@ impl<M: Model> CheckerBuilder<M> {
     where
         M: Model + Send + Sync + 'static,
         M::State: Hash + Send + Sync + Clone + PartialEq + 'static,
-        M::Action: Clone + PartialEq,
+        M::Action: Clone + PartialEq + Send + 'static,
     {
         dfs::DfsChecker::spawn(self)
     }
@@ -206,6 +438,15 @@ impl<M: Model> CheckerBuilder<M> {
     /// to a terminal state. This aims to provide faster coverage of deep states for models that
     /// cannot practically be checked exhaustively.
     ///
+    /// The first run of the first thread uses `seed`, and subsequent runs use seeds from a
+    /// [`StableRng`]. The seed of the run that found each discovery is available via
+    /// [`Checker::discovery_seed`] and is included in reports, so passing it here replays that
+    /// run, including after upgrading Stateright as long as the model and `chooser` are unchanged.
+    /// Statistics about the runs, such as how they ended and which actions they took, are
+    /// available via [`Checker::simulation_stats`] and are also included in reports. Actions must
+    /// be [`Send`], as threads share an example of each kind of action to name it in the
+    /// statistics.
+    ///
     /// This call does not block the current thread. Call [`Checker::join`] to block until
     /// checking completes.
     #[must_use = "Checkers run on background threads. \
@@ -214,7 +455,7 @@ impl<M: Model> CheckerBuilder<M> {
     where
         M: Model + Send + Sync + 'static,
         M::State: Hash + Send + Sync + Clone + PartialEq + 'static,
-        M::Action: Clone + PartialEq,
+        M::Action: Clone + PartialEq + Send,
         C: Chooser<M>,
     {
         simulation::SimulationChecker::spawn::<C>(self, seed, chooser)
@@ -222,20 +463,32 @@ impl<M: Model> CheckerBuilder<M> {
 
     /// Enables symmetry reduction. Requires the [model state] to implement [`Representative`].
     ///
+    /// [`ActorModelState`] treats every actor as interchangeable, so for an [`ActorModel`] with
+    /// [groups of interchangeable actors], pass [`ActorModel::representative`] to
+    /// [`CheckerBuilder::symmetry_fn`] instead.
+    ///
     /// [model state]: crate::Model::State
+    /// [`ActorModelState`]: crate::actor::ActorModelState
+    /// [`ActorModel`]: crate::actor::ActorModel
+    /// [groups of interchangeable actors]: crate::actor::ActorModel::symmetric_actors
+    /// [`ActorModel::representative`]: crate::actor::ActorModel::representative
     pub fn symmetry(self) -> Self
     where
-        M::State: Representative,
+        M::State: Representative + 'static,
     {
         self.symmetry_fn(Representative::representative)
     }
 
-    /// Enables symmetry reduction based on a representative function.
+    /// Enables symmetry reduction based on a representative function, such as the one returned by
+    /// [`ActorModel::representative`] for models with groups of interchangeable actors.
     ///
-    /// [model state]: crate::Model::State
-    pub fn symmetry_fn(self, representative: fn(&M::State) -> M::State) -> Self {
+    /// [`ActorModel::representative`]: crate::actor::ActorModel::representative
+    pub fn symmetry_fn(
+        self,
+        representative: impl Fn(&M::State) -> M::State + Send + Sync + 'static,
+    ) -> Self {
         Self {
-            symmetry: Some(representative),
+            symmetry: Some(Arc::new(representative)),
             ..self
         }
     }
@@ -265,6 +518,235 @@ impl<M: Model> CheckerBuilder<M> {
         }
     }
 
+    /// Moves the fingerprints of generated states and the states awaiting exploration to files in
+    /// a directory once more than `memory_limit` of either are held in memory, so that models
+    /// whose state space exceeds the available memory can be checked to completion. The files
+    /// are placed in a new subdirectory of `dir`, which is removed once the checker is dropped.
+    ///
+    /// Applies to the BFS checker, and to the fingerprints of the on-demand checker. Pending
+    /// states are written as the [`Path`] that reaches them, so reading them back replays the
+    /// path from an initial state. Each thread also holds up to `memory_limit` pending states.
+    /// Fingerprints on disk are kept in sorted files that are merged in tiers, each with a filter
+    /// in memory of a byte or two per fingerprint, so most lookups of new states skip the disk.
+    /// The pass that [detects cycles] once exploration completes is not disk-backed.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use stateright::*;
+    /// # struct Counter;
+    /// # impl Model for Counter {
+    /// #     type State = u16;
+    /// #     type Action = u16;
+    /// #     fn init_states(&self) -> Vec<u16> { vec![0] }
+    /// #     fn actions(&self, &n: &u16, actions: &mut Vec<u16>) {
+    /// #         if n < 10_000 { actions.extend([n + 1, n / 2]) }
+    /// #     }
+    /// #     fn next_state(&self, _: &u16, n: u16) -> Option<u16> { Some(n) }
+    /// #     fn properties(&self) -> Vec<Property<Self>> {
+    /// #         vec![Property::always("bounded", |_, &n| n <= 10_000)]
+    /// #     }
+    /// # }
+    /// let checker = Counter.checker()
+    ///     .spill_to_disk(std::env::temp_dir(), 1_000)
+    ///     .spawn_bfs().join();
+    /// assert_eq!(checker.unique_state_count(), 10_001);
+    /// ```
+    ///
+    /// [detects cycles]: CheckerBuilder::detect_cycles
+    pub fn spill_to_disk(self, dir: impl Into<PathBuf>, memory_limit: usize) -> Self {
+        Self {
+            disk: Some(DiskOptions {
+                dir: dir.into(),
+                memory_limit,
+            }),
+            ..self
+        }
+    }
+
+    /// Records generated states in an array of `bits` bits (rounded up to a power of two) rather
+    /// than by fingerprint, as in Holzmann's bitstate hashing, so that models whose fingerprints
+    /// would not fit in memory can still be checked, albeit with probabilistic coverage. Each
+    /// state sets `hash_count` bits, and a state whose bits are already set is treated as
+    /// generated, so a state can be omitted along with the states only reachable through it.
+    ///
+    /// [`Checker::omission_probability`] estimates the probability that a state was omitted,
+    /// which grows as the array fills, so more bits mean fewer omissions. A few hashes per state
+    /// (2 or 3) are typical. [`Checker::unique_state_count`] excludes omitted states.
+    ///
+    /// Only applies to the DFS checker. When [deepening iteratively], a state that is first
+    /// generated at the depth limit is not explored again if it is reached at a shallower depth.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `bits` or `hash_count` is zero. The DFS checker also panics if [cycles are
+    /// detected], as that pass holds every reachable state in memory.
+    ///
+    /// [deepening iteratively]: CheckerBuilder::iterative_deepening
+    /// [cycles are detected]: CheckerBuilder::detect_cycles
+    pub fn bitstate(self, bits: usize, hash_count: usize) -> Self {
+        assert!(bits > 0, "The bit array must have at least one bit");
+        assert!(hash_count > 0, "Each state must set at least one bit");
+        Self {
+            bitstate: Some(BitstateOptions { bits, hash_count }),
+            ..self
+        }
+    }
+
+    /// Periodically writes the progress of the checker to a file, replacing any earlier
+    /// checkpoint, so that an interrupted check can continue via [`CheckerBuilder::resume_from`].
+    /// A checkpoint includes the fingerprints of generated states, the states awaiting
+    /// exploration (as the [`Path`] that reaches each), discoveries, and counters.
+    ///
+    /// Checker threads pause while a checkpoint is written. Only applies to the BFS checker.
+    pub fn checkpoint(self, path: impl Into<PathBuf>, interval: Duration) -> Self {
+        Self {
+            checkpoint: Some(CheckpointOptions {
+                path: path.into(),
+                interval,
+            }),
+            ..self
+        }
+    }
+
+    /// Continues from a checkpoint written by [`CheckerBuilder::checkpoint`] if the file exists,
+    /// and otherwise starts from the initial states. The checkpoint must have been written for
+    /// the same model. A single threaded checker that resumes reaches the same discoveries as
+    /// one that was not interrupted.
+    ///
+    /// Only applies to the BFS checker, which panics if the checkpoint cannot be read. Sleep sets
+    /// and the depth being checked are not checkpointed, so the checker also panics if
+    /// [partial-order reduction], [shortest discoveries], or [deterministic] checking is enabled.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use stateright::{report::WriteReporter, *}; use std::time::Duration;
+    /// # struct M;
+    /// # impl Model for M {
+    /// #     type State = (); type Action = ();
+    /// #     fn init_states(&self) -> Vec<()> { vec![()] }
+    /// #     fn actions(&self, _: &(), _: &mut Vec<()>) {}
+    /// #     fn next_state(&self, _: &(), _: ()) -> Option<()> { None }
+    /// # }
+    /// # let model = M;
+    /// model.checker()
+    ///     .checkpoint("overnight.checkpoint", Duration::from_secs(600))
+    ///     .resume_from("overnight.checkpoint")
+    ///     .spawn_bfs().report(&mut WriteReporter::new(&mut std::io::stdout()));
+    /// ```
+    ///
+    /// [partial-order reduction]: CheckerBuilder::partial_order_reduction
+    /// [shortest discoveries]: CheckerBuilder::shortest_discoveries
+    /// [deterministic]: CheckerBuilder::deterministic
+    pub fn resume_from(self, path: impl Into<PathBuf>) -> Self {
+        Self {
+            resume_from: Some(path.into()),
+            ..self
+        }
+    }
+
+    /// Enables partial-order reduction, which avoids taking steps whose outcome is already known
+    /// because they commute with a step that was already taken, as indicated by
+    /// [`Model::step_process`]. For example, [`ActorModel`] indicates that messages delivered to
+    /// distinct actors commute, so the checker need not deliver them in every order. Every state
+    /// is still visited, so [`Checker::unique_state_count`] and the discoveries for `always` and
+    /// `sometimes` properties are unaffected, while [`Checker::state_count`] decreases.
+    ///
+    /// Applies to the BFS and DFS checkers. Ignored if the model has [transition properties],
+    /// which must see every step, or if symmetry reduction is enabled. Cannot be combined with
+    /// resuming from a [checkpoint].
+    ///
+    /// [`ActorModel`]: crate::actor::ActorModel
+    /// [checkpoint]: CheckerBuilder::resume_from
+    /// [transition properties]: crate::Property::always_transition
+    pub fn partial_order_reduction(self) -> Self {
+        Self {
+            partial_order_reduction: true,
+            ..self
+        }
+    }
+
+    /// Ensures that the BFS checker reports one of the shortest paths for each discovery of an
+    /// `always` or `sometimes` property regardless of the number of [threads], so that the length
+    /// of a discovery is the same across runs. Otherwise threads may check states at different
+    /// depths concurrently, so a longer path can be found first.
+    ///
+    /// Threads instead check every state at one depth before any state at the next, which limits
+    /// parallelism for narrow state spaces. Cannot be combined with resuming from a [checkpoint].
+    ///
+    /// [threads]: CheckerBuilder::threads
+    /// [checkpoint]: CheckerBuilder::resume_from
+    pub fn shortest_discoveries(self) -> Self {
+        Self {
+            shortest_discoveries: true,
+            ..self
+        }
+    }
+
+    /// Shrinks each discovery (see [`Path::shrink`]) before [`Checker::report`] or
+    /// [`Checker::join_and_report`] reports it, which is most useful for the long paths that
+    /// [`CheckerBuilder::spawn_dfs`] and [`CheckerBuilder::spawn_simulation`] tend to find.
+    /// Shrinking replays the model many times, so it can be slow for long paths.
+    ///
+    /// The report notes which discoveries were shrunk (see [`ReportDiscovery::shrunk_from`]), as
+    /// a [simulation seed] reproduces the path that was found rather than the shrunk path.
+    ///
+    /// [simulation seed]: Checker::discovery_seed
+    pub fn shrink_discoveries(self) -> Self {
+        Self {
+            shrink_discoveries: true,
+            ..self
+        }
+    }
+
+    /// Makes the BFS and DFS checkers deterministic, so that discoveries,
+    /// [`Checker::state_count`], [`Checker::unique_state_count`], and [`Checker::max_depth`] are
+    /// the same across runs regardless of the number of [threads] or how they are scheduled.
+    ///
+    /// The BFS checker checks one depth at a time as with [`CheckerBuilder::shortest_discoveries`],
+    /// reports the lexicographically least of the shortest paths for each discovery, and checks
+    /// every state at a depth before stopping. The DFS checker has no such synchronization, so it
+    /// uses a single thread. [Partial-order reduction] does not apply to the BFS checker.
+    ///
+    /// Results still vary if the check stops due to a [timeout], and a [visitor] may visit states
+    /// in a different order. Cannot be combined with resuming from a [checkpoint].
+    ///
+    /// [threads]: CheckerBuilder::threads
+    /// [Partial-order reduction]: CheckerBuilder::partial_order_reduction
+    /// [timeout]: CheckerBuilder::timeout
+    /// [checkpoint]: CheckerBuilder::resume_from
+    /// [visitor]: CheckerBuilder::visitor
+    pub fn deterministic(self) -> Self {
+        Self {
+            deterministic: true,
+            ..self
+        }
+    }
+
+    /// Makes the DFS checker deepen iteratively, checking every state within depth 1, then every
+    /// state within depth 2, and so on, so that shallow discoveries are found early even for
+    /// models that are too large to check exhaustively. Each depth repeats the work of the
+    /// previous one. Like any DFS only the states along the current path are pending at once, but
+    /// the fingerprint of each state generated while checking a depth is kept along with the
+    /// depth at which it was generated, so memory grows with the number of states within the
+    /// depth, at roughly twice the cost per state of a DFS without deepening.
+    ///
+    /// Checking stops once it [finishes], every state has been checked, or the depth reaches the
+    /// [target max depth], where the last depth checks the same states as a DFS with that target.
+    /// Each completed depth is available via [`Checker::completed_depths`] and is reported as it
+    /// completes. [Partial-order reduction] does not apply.
+    ///
+    /// [finishes]: CheckerBuilder::finish_when
+    /// [target max depth]: CheckerBuilder::target_max_depth
+    /// [Partial-order reduction]: CheckerBuilder::partial_order_reduction
+    pub fn iterative_deepening(self) -> Self {
+        Self {
+            iterative_deepening: true,
+            ..self
+        }
+    }
+
     /// Sets the number of threads available for model checking. For maximum performance this
     /// should match the number of cores.
     pub fn threads(self, thread_count: usize) -> Self {
@@ -289,6 +771,81 @@ impl<M: Model> CheckerBuilder<M> {
             ..self
         }
     }
+
+    /// Makes the BFS and DFS checkers identify counterexamples to [`eventually`] and [temporal
+    /// logic] properties that end in a cycle. Otherwise these checkers only report `eventually`
+    /// counterexamples that end in a terminal state, as exploration cannot tell a cycle from a
+    /// path that rejoins a state reached another way, and they panic if the model has temporal
+    /// logic properties.
+    ///
+    /// Cycles are identified by a single threaded pass once exploration completes, which checks
+    /// each such property that exploration did not already falsify. The pass holds every
+    /// reachable state along with the steps between them in memory and explores the model again,
+    /// ignoring symmetry reduction and [`CheckerBuilder::spill_to_disk`], so it limits the size
+    /// of the models that can be checked. It cannot be combined with [`CheckerBuilder::bitstate`].
+    /// It honors the [target max depth], [timeout], and [`finish_when`] condition.
+    ///
+    /// [`eventually`]: crate::Property::eventually
+    /// [temporal logic]: crate::Property::ltl
+    /// [target max depth]: CheckerBuilder::target_max_depth
+    /// [timeout]: CheckerBuilder::timeout
+    /// [`finish_when`]: CheckerBuilder::finish_when
+    pub fn detect_cycles(self) -> Self {
+        Self {
+            detect_cycles: true,
+            ..self
+        }
+    }
+
+    /// Assumes weak fairness for the class of actions satisfying a predicate: if an action in the
+    /// class is continuously enabled, then an action in the class is eventually taken. An
+    /// [`eventually`] counterexample ending in a cycle that violates this assumption is discarded
+    /// (see [`CheckerBuilder::detect_cycles`]).
+    /// The class is treated as a single action, so taking any action in the class satisfies the
+    /// constraint, and an action is enabled if it leads to a state within the [model boundary].
+    ///
+    /// Fairness is assumed by the BFS and DFS checkers and by the simulation checker. The predicate
+    /// can be a closure, so a class can depend on values only known at runtime, such as the
+    /// [`Id`] of a particular actor.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use stateright::*; use stateright::actor::*;
+    /// # struct A;
+    /// # impl Actor for A {
+    /// #     type Msg = (); type State = (); type Timer = (); type Random = (); type Storage = ();
+    /// #     fn on_start(&self, _: Id, _: &Option<()>, _: &mut Out<Self>) {}
+    /// # }
+    /// # let model = ActorModel::new((), ()).actor(A);
+    /// model.checker()
+    ///     .detect_cycles()
+    ///     .weak_fairness(ActorModelAction::is_deliver)
+    ///     .weak_fairness(ActorModelAction::is_timeout)
+    ///     .spawn_bfs().join().assert_properties();
+    /// ```
+    ///
+    /// [`eventually`]: crate::Property::eventually
+    /// [`Id`]: crate::actor::Id
+    /// [model boundary]: crate::Model::within_boundary
+    pub fn weak_fairness(
+        mut self,
+        predicate: impl Fn(&M::Action) -> bool + Send + Sync + 'static,
+    ) -> Self {
+        self.fairness.push(Fairness::Weak(Arc::new(predicate)));
+        self
+    }
+
+    /// Assumes strong fairness for the class of actions satisfying a predicate: if an action in
+    /// the class is enabled infinitely often, then an action in the class is taken infinitely
+    /// often. See [`CheckerBuilder::weak_fairness`] for details.
+    pub fn strong_fairness(
+        mut self,
+        predicate: impl Fn(&M::Action) -> bool + Send + Sync + 'static,
+    ) -> Self {
+        self.fairness.push(Fairness::Strong(Arc::new(predicate)));
+        self
+    }
 }
 
 /// Implementations perform [`Model`] checking.
@@ -309,6 +866,12 @@ pub trait Checker<M: Model> {
         // nothing to do for most cases
     }
 
+    /// Asks a checker that was running to completion to resume waiting for fingerprints, which
+    /// pauses checking until [`Checker::run_to_completion`] is called again.
+    fn pause(&self) {
+        // nothing to do for most cases
+    }
+
     /// Indicate how many states have been generated including repeats. Always greater than or
     /// equal to [`Checker::unique_state_count`].
     fn state_count(&self) -> usize;
@@ -357,6 +920,151 @@ pub trait Checker<M: Model> {
         self.discoveries().remove(name)
     }
 
+    /// Looks up a discovery by property name like [`Checker::discovery`], and then searches for a
+    /// shorter path that is still a discovery via [`Path::shrink`]. This is most useful for the
+    /// long paths that [`CheckerBuilder::spawn_dfs`] and [`CheckerBuilder::spawn_simulation`]
+    /// tend to find. See also [`CheckerBuilder::shrink_discoveries`].
+    fn shrunk_discovery(&self, name: &'static str) -> Option<Path<M::State, M::Action>>
+    where
+        M::State: Clone + PartialEq,
+        M::Action: Clone + PartialEq,
+    {
+        self.discovery(name)
+            .map(|path| path.shrink(self.model(), name))
+    }
+
+    /// Writes the graph of states reachable from the initial states in the
+    /// [DOT](https://graphviz.org/doc/info/lang.html) language of Graphviz, for viewing offline
+    /// (e.g. with `dot -Tsvg`). Nodes are named by fingerprint and labelled by state, edges are
+    /// labelled by [`Model::format_action`], and initial states are drawn bold. `max_depth`
+    /// limits the number of steps from an initial state.
+    ///
+    /// The graph is generated from the model rather than from the states this checker recorded,
+    /// so it does not depend on how far checking has progressed. Like checking, it omits states
+    /// outside [`Model::within_boundary`].
+    fn write_dot(
+        &self,
+        writer: &mut dyn std::io::Write,
+        max_depth: Option<usize>,
+    ) -> std::io::Result<()>
+    where
+        M::State: Clone + Debug + Hash,
+        M::Action: Debug,
+    {
+        let model = self.model();
+        let roots = model
+            .init_states()
+            .into_iter()
+            .enumerate()
+            .map(|(i, s)| (s, vec![i]))
+            .collect();
+        graph::StateGraph::explore(model, roots, max_depth).write_dot(writer)
+    }
+
+    /// Saves the discoveries so far to a JSON file, along with the state counts and maximum depth,
+    /// so that they can be browsed later without checking the model again (see
+    /// [`CheckerBuilder::load_discoveries`]). Each discovery is saved as it would be encoded in
+    /// an Explorer URL (see [`Path::encode`]), which only identifies the same states for the same
+    /// model.
+    fn save_discoveries(&self, path: impl Into<PathBuf>) -> std::io::Result<()>
+    where
+        M::State: Clone + PartialEq,
+        M::Action: Clone + PartialEq,
+        Self: Sized,
+    {
+        replay::SavedDiscoveries::of(self).save(&path.into())
+    }
+
+    /// Starts Explorer (see [`CheckerBuilder::serve`]) for this checker, such as one that has
+    /// already found a discovery via [`CheckerBuilder::spawn_bfs`],
+    /// [`CheckerBuilder::spawn_dfs`], or [`CheckerBuilder::spawn_simulation`], so that its
+    /// discoveries can be browsed without checking the model again. A checker that has not
+    /// finished keeps checking in the background, and `POST /.runtocompletion`, `/.pause`, and
+    /// `/.resume` respond with 404.
+    ///
+    /// Blocks until `POST /.shutdown`, and then returns the checker. The service neither
+    /// requires a token nor is read-only, so prefer a loopback address such as `localhost`, or
+    /// see [`Checker::serve_with`].
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use stateright::*;
+    /// # #[derive(Clone)] struct M;
+    /// # impl Model for M {
+    /// #     type State = (); type Action = ();
+    /// #     fn init_states(&self) -> Vec<()> { vec![()] }
+    /// #     fn actions(&self, _: &(), _: &mut Vec<()>) {}
+    /// #     fn next_state(&self, _: &(), _: ()) -> Option<()> { None }
+    /// # }
+    /// # let model = M;
+    /// let checker = model.checker().spawn_bfs().join();
+    /// if !checker.discoveries().is_empty() {
+    ///     checker.serve("localhost:3000");
+    /// }
+    /// ```
+    fn serve(self, addresses: impl std::net::ToSocketAddrs) -> Arc<Self>
+    where
+        M: 'static + Send + Sync,
+        M::Action: Debug + Send + Sync + Clone + PartialEq,
+        M::State: Debug + Hash + Send + Sync + Clone + PartialEq,
+        Self: 'static + Sized + Send + Sync,
+    {
+        self.serve_with(addresses, ExplorerOptions::default())
+    }
+
+    /// Starts Explorer for this checker as with [`Checker::serve`], but with the specified
+    /// settings, such as to require a token.
+    fn serve_with(
+        self,
+        addresses: impl std::net::ToSocketAddrs,
+        options: ExplorerOptions<M::State>,
+    ) -> Arc<Self>
+    where
+        M: 'static + Send + Sync,
+        M::Action: Debug + Send + Sync + Clone + PartialEq,
+        M::State: Debug + Hash + Send + Sync + Clone + PartialEq,
+        Self: 'static + Sized + Send + Sync,
+    {
+        explorer::serve_spawned(self, addresses, options)
+    }
+
+    /// Returns the seed of the simulation run that found a discovery, which
+    /// [`CheckerBuilder::spawn_simulation`] replays as the first run of its first thread. `None`
+    /// for the other checkers.
+    fn discovery_seed(&self, _name: &'static str) -> Option<u64> {
+        None
+    }
+
+    /// Returns statistics about the runs of a [`CheckerBuilder::spawn_simulation`] checker so
+    /// far, such as how they ended and which actions they took. `None` for the other checkers.
+    fn simulation_stats(&self) -> Option<SimulationStats>
+    where
+        M::Action: Debug,
+    {
+        None
+    }
+
+    /// Estimates the probability that a state was omitted because it was mistaken for a state
+    /// that was already generated, for a checker with [`CheckerBuilder::bitstate`]. `None` for
+    /// checkers that record states exactly.
+    fn omission_probability(&self) -> Option<f64> {
+        None
+    }
+
+    /// Indicates whether discoveries are shrunk before they are reported, per
+    /// [`CheckerBuilder::shrink_discoveries`].
+    fn shrinks_discoveries(&self) -> bool {
+        false
+    }
+
+    /// Returns the depths that have been completed so far by a DFS checker that deepens
+    /// iteratively (see [`CheckerBuilder::iterative_deepening`]), from shallowest to deepest.
+    /// Empty for the other checkers.
+    fn completed_depths(&self) -> Vec<CompletedDepth> {
+        Vec::new()
+    }
+
     /// Wait for all threads to finish whilst reporting, reporting the finish more accurately than
     /// the interval used for the reporting.
     fn join_and_report<R>(mut self, reporter: &mut R) -> Self
@@ -369,6 +1077,7 @@ pub trait Checker<M: Model> {
         let handles = self.handles();
         let reporter_mutex = Arc::new(Mutex::new(reporter));
         let reporter_mutex2 = Arc::clone(&reporter_mutex);
+        let reported_depths = &AtomicUsize::new(0);
 
         std::thread::scope(|s| {
             let method_start = Instant::now();
@@ -377,6 +1086,11 @@ pub trait Checker<M: Model> {
             s.spawn(move || {
                 // Loop checking the status until we're done.
                 while !slf.is_done() {
+                    report_completed_depths(
+                        slf,
+                        &mut **reporter_mutex.lock().unwrap(),
+                        reported_depths,
+                    );
                     reporter_mutex.lock().unwrap().report_checking(ReportData {
                         total_states: slf.state_count(),
                         unique_states: slf.unique_state_count(),
@@ -394,6 +1108,7 @@ pub trait Checker<M: Model> {
             }
 
             // Send a final report to say we're done.
+            report_completed_depths(slf, &mut **reporter_mutex2.lock().unwrap(), reported_depths);
             reporter_mutex2.lock().unwrap().report_checking(ReportData {
                 total_states: self.state_count(),
                 unique_states: self.unique_state_count(),
@@ -401,20 +1116,21 @@ pub trait Checker<M: Model> {
                 duration: method_start2.elapsed(),
                 done: true,
             });
+            if let Some(probability) = slf.omission_probability() {
+                reporter_mutex2
+                    .lock()
+                    .unwrap()
+                    .report_omission_probability(probability);
+            }
+            if let Some(stats) = slf.simulation_stats() {
+                reporter_mutex2
+                    .lock()
+                    .unwrap()
+                    .report_simulation_stats(stats);
+            }
 
             // Finish with a discovery summary.
-            let mut discoveries = BTreeMap::new();
-            for (name, path) in slf.discoveries() {
-                let discovery = ReportDiscovery {
-                    path,
-                    classification: slf.discovery_classification(name),
-                };
-                discoveries.insert(name, discovery);
-            }
-            reporter_mutex2
-                .lock()
-                .unwrap()
-                .report_discoveries(slf.model(), discoveries);
+            report_discoveries(slf, &mut **reporter_mutex2.lock().unwrap());
         });
         self
     }
@@ -429,7 +1145,9 @@ pub trait Checker<M: Model> {
     {
         // Start with the checking status.
         let method_start = Instant::now();
+        let reported_depths = AtomicUsize::new(0);
         while !self.is_done() {
+            report_completed_depths(&self, reporter, &reported_depths);
             reporter.report_checking(ReportData {
                 total_states: self.state_count(),
                 unique_states: self.unique_state_count(),
@@ -440,6 +1158,7 @@ pub trait Checker<M: Model> {
             let delay = reporter.delay();
             std::thread::sleep(delay);
         }
+        report_completed_depths(&self, reporter, &reported_depths);
         reporter.report_checking(ReportData {
             total_states: self.state_count(),
             unique_states: self.unique_state_count(),
@@ -447,17 +1166,15 @@ pub trait Checker<M: Model> {
             duration: method_start.elapsed(),
             done: true,
         });
+        if let Some(probability) = self.omission_probability() {
+            reporter.report_omission_probability(probability);
+        }
+        if let Some(stats) = self.simulation_stats() {
+            reporter.report_simulation_stats(stats);
+        }
 
         // Finish with a discovery summary.
-        let mut discoveries = BTreeMap::new();
-        for (name, path) in self.discoveries() {
-            let discovery = ReportDiscovery {
-                path,
-                classification: self.discovery_classification(name),
-            };
-            discoveries.insert(name, discovery);
-        }
-        reporter.report_discoveries(self.model(), discoveries);
+        report_discoveries(&self, reporter);
 
         self
     }
@@ -466,11 +1183,10 @@ pub trait Checker<M: Model> {
     fn discovery_classification(&self, name: &str) -> DiscoveryClassification {
         let properties = self.model().properties();
         let property = properties.iter().find(|p| p.name == name).unwrap();
-        match property.expectation {
-            Expectation::Always | Expectation::Eventually => {
-                DiscoveryClassification::Counterexample
-            }
-            Expectation::Sometimes => DiscoveryClassification::Example,
+        if property.discovery_is_failure() {
+            DiscoveryClassification::Counterexample
+        } else {
+            DiscoveryClassification::Example
         }
     }
 
@@ -482,10 +1198,12 @@ pub trait Checker<M: Model> {
         M::State: Debug + Clone + PartialEq,
     {
         for p in self.model().properties() {
-            match p.expectation {
-                Expectation::Always => self.assert_no_discovery(p.name),
-                Expectation::Eventually => self.assert_no_discovery(p.name),
-                Expectation::Sometimes => {
+            match p.predicate {
+                Predicate::Always(_) => self.assert_no_discovery(p.name),
+                Predicate::Transition(_) => self.assert_no_discovery(p.name),
+                Predicate::Eventually(_) => self.assert_no_discovery(p.name),
+                Predicate::Ltl(_) => self.assert_no_discovery(p.name),
+                Predicate::Sometimes(_) => {
                     self.assert_any_discovery(p.name);
                 }
             }
@@ -541,22 +1259,37 @@ pub trait Checker<M: Model> {
         let found = self.assert_any_discovery(name);
         for init_state in self.model().init_states() {
             if let Some(path) = Path::from_actions(self.model(), init_state, &actions) {
+                // Actions leading back to an earlier state describe a lasso.
+                let loop_start = {
+                    let states = path.clone().into_states();
+                    states[..states.len() - 1]
+                        .iter()
+                        .position(|s| s == path.last_state())
+                };
                 let property = self.model().property(name);
-                match property.expectation {
-                    Expectation::Always => {
-                        if !(property.condition)(self.model(), path.last_state()) {
+                match &property.predicate {
+                    Predicate::Always(always) => {
+                        if !always(self.model(), path.last_state()) {
                             return;
                         }
                     }
-                    Expectation::Eventually => {
-                        let states = path.into_states();
-                        let is_liveness_satisfied =
-                            states.iter().any(|s| (property.condition)(self.model(), s));
-                        let is_path_terminal = {
+                    Predicate::Transition(transition) => {
+                        let steps = path.into_vec();
+                        if let [.., (last_state, Some(action)), (next_state, _)] = &steps[..] {
+                            if !transition(self.model(), last_state, action, next_state) {
+                                return;
+                            }
+                        }
+                    }
+                    Predicate::Eventually(eventually) => {
+                        let is_path_terminal = loop_start.is_some() || {
                             let mut actions = Vec::new();
-                            self.model().actions(states.last().unwrap(), &mut actions);
+                            self.model().actions(path.last_state(), &mut actions);
                             actions.is_empty()
                         };
+                        let states = path.into_states();
+                        let is_liveness_satisfied =
+                            states.iter().any(|s| eventually(self.model(), s));
                         if !is_liveness_satisfied && is_path_terminal {
                             return;
                         }
@@ -565,13 +1298,33 @@ pub trait Checker<M: Model> {
                                 .push("incorrect counterexample satisfies eventually property");
                         }
                         if !is_path_terminal {
-                            additional_info.push("incorrect counterexample is nonterminal");
+                            additional_info
+                                .push("incorrect counterexample is neither terminal nor cyclic");
                         }
                     }
-                    Expectation::Sometimes => {
-                        if (property.condition)(self.model(), path.last_state()) {
+                    Predicate::Sometimes(sometimes) => {
+                        if sometimes(self.model(), path.last_state()) {
+                            return;
+                        }
+                    }
+                    Predicate::Ltl(_) => {
+                        let is_path_terminal = loop_start.is_some() || {
+                            let mut actions = Vec::new();
+                            self.model().actions(path.last_state(), &mut actions);
+                            actions.is_empty()
+                        };
+                        if !is_path_terminal {
+                            additional_info
+                                .push("incorrect counterexample is neither terminal nor cyclic");
+                            continue;
+                        }
+                        let monitor = ltl::Monitor::for_property(&property).unwrap();
+                        let states = path.into_states();
+                        if liveness::accepts_path(self.model(), &monitor, &states, loop_start) {
                             return;
                         }
+                        additional_info
+                            .push("incorrect counterexample satisfies temporal logic property");
                     }
                 }
             }
@@ -590,15 +1343,147 @@ pub trait Checker<M: Model> {
     }
 }
 
+/// Reports the depths that `checker` has completed beyond the number already `reported`.
+fn report_completed_depths<M, C, R>(checker: &C, reporter: &mut R, reported: &AtomicUsize)
+where
+    M: Model,
+    C: Checker<M> + ?Sized,
+    R: Reporter<M> + ?Sized,
+{
+    let completed_depths = checker.completed_depths();
+    for depth in completed_depths
+        .into_iter()
+        .skip(reported.load(Ordering::Relaxed))
+    {
+        reporter.report_completed_depth(depth);
+        reported.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+/// Reports the discoveries of `checker`, shrinking them first if it [shrinks
+/// discoveries](CheckerBuilder::shrink_discoveries).
+fn report_discoveries<M, C, R>(checker: &C, reporter: &mut R)
+where
+    M: Model,
+    M::Action: Debug + PartialEq + Clone,
+    M::State: Debug + Hash + PartialEq + Clone,
+    C: Checker<M> + ?Sized,
+    R: Reporter<M> + ?Sized,
+{
+    let mut discoveries = BTreeMap::new();
+    for (name, path) in checker.discoveries() {
+        let mut discovery = ReportDiscovery::new(path, checker.discovery_classification(name));
+        discovery.seed = checker.discovery_seed(name);
+        if checker.shrinks_discoveries() {
+            let shrunk = discovery.path.shrink(checker.model(), name);
+            if shrunk != discovery.path {
+                let found = std::mem::replace(&mut discovery.path, shrunk);
+                discovery.shrunk_from = Some(found.into_actions().len());
+            }
+        }
+        discoveries.insert(name, discovery);
+    }
+    reporter.report_discoveries(checker.model(), discoveries);
+}
+
+/// Checks the [`Property::always_transition`] properties for a step, recording a discovery for
+/// each that is violated. `action_path` leads to the state before the step, and `action_index`
+/// identifies the action taken. A discovery that was already recorded is kept unless
+/// `keep_least`, in which case the least is kept (see [`record_least_discovery`]).
+///
+/// [`Property::always_transition`]: crate::Property::always_transition
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn check_transitions<M: Model>(
+    model: &M,
+    properties: &[Property<M>],
+    discoveries: &DashMap<&'static str, Vec<usize>>,
+    last_state: &M::State,
+    action: &M::Action,
+    next_state: &M::State,
+    action_path: &[usize],
+    action_index: usize,
+    keep_least: bool,
+) {
+    for property in properties {
+        if let Predicate::Transition(transition) = &property.predicate {
+            if (keep_least || !discoveries.contains_key(property.name))
+                && !transition(model, last_state, action, next_state)
+            {
+                let mut action_path = action_path.to_vec();
+                action_path.push(action_index);
+                if keep_least {
+                    record_least_discovery(discoveries, property.name, action_path);
+                } else {
+                    // Races other threads, but that's fine.
+                    discoveries.insert(property.name, action_path);
+                }
+            }
+        }
+    }
+}
+
+/// Records a discovery unless the property already has one with a shorter action path or an
+/// equally long one that is lexicographically smaller, so the discovery that is kept does not
+/// depend on the order in which threads find them.
+pub(crate) fn record_least_discovery(
+    discoveries: &DashMap<&'static str, Vec<usize>>,
+    name: &'static str,
+    action_path: Vec<usize>,
+) {
+    match discoveries.entry(name) {
+        dashmap::Entry::Occupied(mut entry) => {
+            let existing = entry.get();
+            if (action_path.len(), &action_path) < (existing.len(), existing) {
+                entry.insert(action_path);
+            }
+        }
+        dashmap::Entry::Vacant(entry) => {
+            entry.insert(action_path);
+        }
+    }
+}
+
+/// Returns whether an exploring checker should stop early due to its [timeout], [target state
+/// count], or [`finish_when`] condition, so that work continuing after exploration (such as the
+/// liveness pass) honors the same conditions.
+///
+/// [timeout]: CheckerBuilder::timeout
+/// [target state count]: CheckerBuilder::target_state_count
+/// [`finish_when`]: CheckerBuilder::finish_when
+pub(crate) fn should_stop<M>(
+    close_at: Option<SystemTime>,
+    target_state_count: Option<NonZeroUsize>,
+    finish_when: Arc<HasDiscoveries>,
+    properties: Arc<Vec<Property<M>>>,
+    state_count: Arc<AtomicUsize>,
+    discoveries: Arc<DashMap<&'static str, Vec<usize>>>,
+) -> impl Fn() -> bool + Send + 'static
+where
+    M: Model + 'static,
+    Property<M>: Send + Sync,
+{
+    move || {
+        close_at.is_some_and(|t| t <= SystemTime::now())
+            || target_state_count.is_some_and(|c| c.get() <= state_count.load(Ordering::Relaxed))
+            || finish_when.matches(&discoveries.iter().map(|r| *r.key()).collect(), &properties)
+    }
+}
+
 // EventuallyBits tracks one bit per 'eventually' property being checked. Properties are assigned
 // bit-numbers just by counting the 'eventually' properties up from 0 in the properties list. If a
 // bit is present in a bitset, the property has _not_ been found on this path yet. Bits are removed
 // from the propagating bitset when we find a state satisfying an `eventually` property; these
 // states are not considered discoveries. Only if we hit the "end" of a path (i.e. return to a known
 // state / no further state) with any of these bits still 1, the path is considered a discovery,
-// a counterexample to the property.
+// a counterexample to the property. Paths ending in a cycle are identified separately if enabled
+// (see liveness.rs).
 type EventuallyBits = id_set::IdSet;
 
+/// A function that maps each state to the representative of its symmetry equivalence class. See
+/// [`CheckerBuilder::symmetry_fn`].
+pub(crate) type Symmetry<M> =
+    Arc<dyn Fn(&<M as Model>::State) -> <M as Model>::State + Send + Sync>;
+
 #[cfg(test)]
 mod test_eventually_property_checker {
     use crate::test_util::dgraph::DGraph;
@@ -673,15 +1558,37 @@ mod test_eventually_property_checker {
     }
 
     #[test]
-    fn fixme_can_miss_counterexample_when_revisiting_a_state() {
-        // i.e. incorrectly verify
+    fn can_discover_counterexample_when_revisiting_a_state() {
+        assert_eq!(
+            DGraph::with_property(eventually_odd())
+                .with_path(vec![0, 2, 4, 2]) // cycle
+                .check_cycles()
+                .discovery("odd")
+                .unwrap()
+                .into_states(),
+            vec![0, 2, 4, 2]
+        );
+        assert_eq!(
+            DGraph::with_property(eventually_odd())
+                .with_path(vec![0, 2, 4])
+                .with_path(vec![1, 4, 6]) // revisiting 4
+                .check_cycles()
+                .discovery("odd")
+                .unwrap()
+                .into_states(),
+            vec![0, 2, 4, 6]
+        );
+    }
+
+    #[test]
+    fn misses_counterexample_when_revisiting_a_state_unless_detecting_cycles() {
         assert_eq!(
             DGraph::with_property(eventually_odd())
                 .with_path(vec![0, 2, 4, 2]) // cycle
                 .check()
                 .discovery("odd"),
             None
-        ); // FIXME: `unwrap().into_states()` should be [0, 2, 4, 2]
+        );
         assert_eq!(
             DGraph::with_property(eventually_odd())
                 .with_path(vec![0, 2, 4])
@@ -689,7 +1596,7 @@ mod test_eventually_property_checker {
                 .check()
                 .discovery("odd"),
             None
-        ); // FIXME: `unwrap().into_states()` should be [0, 2, 4, 6]
+        );
     }
     #[test]
     fn cannot_overwrite_discovery_when_having_other_properties() {
@@ -709,6 +1616,71 @@ mod test_eventually_property_checker {
     }
 }
 
+#[cfg(test)]
+mod test_transition_property_checker {
+    use crate::test_util::dgraph::DGraph;
+    use crate::{Checker, Model, Property};
+
+    fn increasing() -> Property<DGraph> {
+        Property::always_transition("increasing", |_, s, _, t| t > s)
+    }
+
+    #[test]
+    fn can_validate() {
+        DGraph::with_property(increasing())
+            .with_path(vec![0, 1, 2])
+            .with_path(vec![0, 2, 3])
+            .check()
+            .assert_properties();
+    }
+
+    #[test]
+    fn is_always_expected() {
+        assert_eq!(increasing().expectation(), crate::Expectation::Always);
+    }
+
+    #[test]
+    fn can_discover_counterexample() {
+        let model = DGraph::with_property(increasing())
+            .with_path(vec![0, 1, 3])
+            .with_path(vec![0, 2, 1]);
+        let checkers: Vec<Box<dyn Checker<DGraph>>> = vec![
+            Box::new(model.clone().checker().spawn_bfs().join()),
+            Box::new(model.clone().checker().spawn_dfs().join()),
+        ];
+        for checker in checkers {
+            assert_eq!(
+                checker.discovery("increasing").unwrap().into_states(),
+                vec![0, 2, 1]
+            );
+            checker.assert_discovery("increasing", vec![2, 1]);
+        }
+        let checker = model
+            .checker()
+            .spawn_simulation(0, crate::UniformChooser)
+            .join();
+        assert_eq!(
+            checker.discovery("increasing").unwrap().into_states(),
+            vec![0, 2, 1]
+        );
+    }
+
+    #[test]
+    fn checks_steps_into_visited_states() {
+        // The violating step revisits a state, so it would be missed if only newly generated
+        // states were checked.
+        assert_eq!(
+            DGraph::with_property(increasing())
+                .with_path(vec![0, 1, 2, 1])
+                .check()
+                .discovery("increasing")
+                .unwrap()
+                .into_states(),
+            vec![0, 1, 2, 1]
+        );
+    }
+}
+
 #[cfg(test)]
 mod test_path {
     use super::*;
@@ -738,6 +1710,53 @@ mod test_report {
     use super::*;
     use crate::{report::WriteReporter, test_util::linear_equation_solver::LinearEquation};
 
+    #[test]
+    fn can_shrink_reported_discoveries() {
+        // DFS increases y through every value before increasing x, but y is irrelevant.
+        let model = || {
+            LinearEquation { a: 1, b: 0, c: 2 }
+                .checker()
+                .shrink_discoveries()
+                .spawn_dfs()
+        };
+        let expected = "\
+            Discovered \"solvable\" example Path[2]:\n\
+            - IncreaseX\n\
+            - IncreaseX\n\
+            Action index path: 0/0/0\n\
+            Shrunk from a path of 257 actions.\n";
+
+        let mut written: Vec<u8> = Vec::new();
+        let checker = model().join_and_report(&mut WriteReporter::new(&mut written));
+        assert_eq!(
+            checker.discovery("solvable").unwrap().into_actions().len(),
+            257
+        );
+        let output = String::from_utf8(written).unwrap();
+        assert!(
+            output.ends_with(expected),
+            "Output did not end as expected (see test). output={output:?}`"
+        );
+
+        let mut written: Vec<u8> = Vec::new();
+        model().report(&mut WriteReporter::new(&mut written));
+        let output = String::from_utf8(written).unwrap();
+        assert!(
+            output.ends_with(expected),
+            "Output did not end as expected (see test). output={output:?}`"
+        );
+
+        // Discoveries are not shrunk by default.
+        let mut written: Vec<u8> = Vec::new();
+        LinearEquation { a: 1, b: 0, c: 2 }
+            .checker()
+            .spawn_dfs()
+            .join_and_report(&mut WriteReporter::new(&mut written));
+        let output = String::from_utf8(written).unwrap();
+        assert!(output.contains("Discovered \"solvable\" example Path[257]:\n"));
+        assert!(!output.contains("Shrunk"));
+    }
+
     #[test]
     fn report_includes_property_names_and_paths() {
         // BFS
@@ -1,52 +1,193 @@
 //! Private module for selective re-export.
 
-use crate::checker::{Checker, EventuallyBits, Expectation, Path};
-use crate::job_market::JobBroker;
-use crate::{fingerprint, CheckerBuilder, CheckerVisitor, Fingerprint, Model, Property};
-use dashmap::mapref::entry::Entry;
+use crate::checker::checkpoint::{CheckpointReader, CheckpointWriter};
+use crate::checker::disk::{self, FingerprintSet, ScratchDir};
+use crate::checker::{
+    check_transitions, liveness, record_least_discovery, should_stop, Checker, EventuallyBits,
+    Path, Predicate, SleepSet, SleepSets, Spill,
+};
+use crate::job_market::{CheckpointJobs, JobBroker};
+use crate::{fingerprint, CheckerBuilder, CheckerVisitor, Fingerprint, Model};
 use dashmap::DashMap;
-use nohash_hasher::NoHashHasher;
+use parking_lot::Mutex;
 use std::collections::{HashMap, VecDeque};
-use std::hash::{BuildHasherDefault, Hash};
+use std::hash::Hash;
 use std::num::NonZeroUsize;
-use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 use std::sync::Arc;
 use std::thread::JoinHandle;
 use std::time::SystemTime;
 
-// While this file is currently quite similar to dfs.rs, a refactoring to lift shared
-// behavior is being postponed until DPOR is implemented.
-
 pub(crate) struct BfsChecker<M: Model> {
     // Immutable state.
     model: Arc<M>,
     handles: Vec<std::thread::JoinHandle<()>>,
+    shrink_discoveries: bool,
 
     // Mutable state.
-    job_broker: JobBroker<Job<M::State>>,
+    job_broker: JobBroker<Job<M::State, M::Action>>,
     state_count: Arc<AtomicUsize>,
     max_depth: Arc<AtomicUsize>,
-    generated:
-        Arc<DashMap<Fingerprint, Option<Fingerprint>, BuildHasherDefault<NoHashHasher<u64>>>>,
+    generated: Arc<FingerprintSet>,
     // In the original fingerprint-based Path construction, the value type of `discovery` was a
     // single fingerprint since the path could be reconstructed in reverse using the parent
     // relationships in `generated`. However, with action indices encoding, this is not possible,
     // so we must store a Vec (like in dfs.rs).
     discoveries: Arc<DashMap<&'static str, Vec<usize>>>,
+    loop_starts: Arc<DashMap<&'static str, Option<usize>>>,
+    liveness_done: Arc<AtomicBool>,
+}
+pub(crate) type Job<State, Action> = (
+    State,
+    EventuallyBits,
+    NonZeroUsize,
+    Vec<usize>,
+    SleepSet<Action>,
+);
+
+/// Synchronizes threads so that every state at one depth is checked before any state at the next
+/// depth, which ensures that the first discovery of each property is also one of the shortest (see
+/// [`CheckerBuilder::shortest_discoveries`]). Jobs of the next depth are held here rather than
+/// being shared via the [`JobBroker`] until the current depth is complete.
+struct Levels<Job> {
+    inner: Mutex<LevelsInner<Job>>,
+}
+struct LevelsInner<Job> {
+    /// The depth being checked.
+    depth: usize,
+    /// Number of jobs of the current depth that are not yet checked.
+    remaining: usize,
+    /// Jobs of the next depth.
+    next: Vec<VecDeque<Job>>,
+    /// When checking deterministically, the jobs of the next depth that were claimed and the index
+    /// of the job claimed for each state (see [`Levels::commit`]).
+    claimed: Option<(VecDeque<Job>, HashMap<Fingerprint, usize>)>,
+    /// Whether the check is finishing, in which case the next depth is not checked.
+    finishing: bool,
+}
+
+/// How a thread checks the states at the depth being checked.
+#[derive(Clone, Copy)]
+pub(crate) struct Level {
+    pub(crate) depth: usize,
+    pub(crate) deterministic: bool,
+}
+
+impl<State: Hash, Action> Levels<Job<State, Action>> {
+    fn new(depth: usize, remaining: usize, deterministic: bool) -> Self {
+        Levels {
+            inner: Mutex::new(LevelsInner {
+                depth,
+                remaining,
+                next: Vec::new(),
+                claimed: deterministic.then(Default::default),
+                finishing: false,
+            }),
+        }
+    }
+
+    fn level(&self) -> Level {
+        let inner = self.inner.lock();
+        Level {
+            depth: inner.depth,
+            deterministic: inner.claimed.is_some(),
+        }
+    }
+
+    /// Records that `checked` jobs of the current depth were checked, setting aside the jobs of
+    /// the next depth in `pending`. Shares the jobs of the next depth once the current depth is
+    /// complete, unless `finishing`.
+    ///
+    /// When checking deterministically, the states of the next depth are only claimed here rather
+    /// than as they are generated, and the job with the least action path to each state is kept,
+    /// so which job is kept does not depend on which thread generated it first.
+    fn commit(
+        &self,
+        pending: &mut VecDeque<Job<State, Action>>,
+        checked: usize,
+        finishing: bool,
+        generated: &FingerprintSet,
+        job_broker: &mut JobBroker<Job<State, Action>>,
+    ) {
+        let next = {
+            let mut guard = self.inner.lock();
+            let inner = &mut *guard;
+            // Pending jobs are ordered by depth, with the deepest at the front.
+            let depth = inner.depth;
+            let next_count = pending.partition_point(|job| job.2.get() > depth);
+            let current = pending.split_off(next_count);
+            let next = std::mem::replace(pending, current);
+            match &mut inner.claimed {
+                None if !next.is_empty() => inner.next.push(next),
+                None => {}
+                Some((claimed, indices)) => {
+                    for job in next {
+                        let fingerprint = fingerprint(&job.0);
+                        match indices.get(&fingerprint) {
+                            Some(&i) => {
+                                if job.3 < claimed[i].3 {
+                                    claimed[i] = job;
+                                }
+                            }
+                            None => {
+                                if generated.insert(fingerprint) {
+                                    indices.insert(fingerprint, claimed.len());
+                                    claimed.push_back(job);
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+            inner.remaining -= checked;
+            inner.finishing |= finishing;
+            if inner.remaining > 0 || inner.finishing {
+                return;
+            }
+            if let Some((claimed, indices)) = &mut inner.claimed {
+                indices.clear();
+                if !claimed.is_empty() {
+                    inner.next.push(std::mem::take(claimed));
+                }
+            }
+            inner.depth += 1;
+            inner.remaining = inner.next.iter().map(VecDeque::len).sum();
+            std::mem::take(&mut inner.next)
+        };
+        // Shared after releasing the lock, as writing a checkpoint locks in the opposite order.
+        for jobs in next {
+            job_broker.push(jobs);
+        }
+    }
 }
-type Job<State> = (State, Fingerprint, EventuallyBits, NonZeroUsize, Vec<usize>);
 
 impl<M> BfsChecker<M>
 where
     M: Model + Send + Sync + 'static,
     M::State: Hash + Send + 'static,
+    M::Action: Send + 'static,
 {
     pub(crate) fn spawn(options: CheckerBuilder<M>) -> Self
     where
         M::State: Clone + PartialEq,
         M::Action: Clone + PartialEq,
     {
+        // Sleep sets and the depth being checked are not checkpointed, so resuming would silently
+        // forgo these guarantees.
+        if options.resume_from.is_some() {
+            for (is_enabled, method) in [
+                (options.partial_order_reduction, "partial_order_reduction"),
+                (options.shortest_discoveries, "shortest_discoveries"),
+                (options.deterministic, "deterministic"),
+            ] {
+                assert!(
+                    !is_enabled,
+                    "CheckerBuilder::resume_from does not support CheckerBuilder::{method}"
+                );
+            }
+        }
         let model = Arc::new(options.model);
+        let shrink_discoveries = options.shrink_discoveries;
         let target_state_count = options.target_state_count;
         let target_max_depth = options.target_max_depth;
         let thread_count = options.thread_count;
@@ -61,40 +202,110 @@ where
             .collect();
         let state_count = Arc::new(AtomicUsize::new(init_states.len()));
         let max_depth = Arc::new(AtomicUsize::new(0));
-        let generated = Arc::new({
-            let generated = DashMap::default();
-            for s in &init_states {
-                generated.insert(fingerprint(s), None);
-            }
-            generated
-        });
+        let scratch_dir = options.disk.as_ref().map(|disk| ScratchDir::new(&disk.dir));
+        let generated = Arc::new(FingerprintSet::new(
+            scratch_dir.as_ref().zip(options.disk.as_ref()),
+        ));
         let ebits = {
             let mut ebits = EventuallyBits::new();
             for (i, p) in model.properties().iter().enumerate() {
-                if let Property {
-                    expectation: Expectation::Eventually,
-                    ..
-                } = p
-                {
+                if let Predicate::Eventually(_) = p.predicate {
                     ebits.insert(i);
                 }
             }
             ebits
         };
-        let pending: VecDeque<_> = init_states
-            .into_iter()
-            .enumerate()
-            .map(|(i, s)| {
-                let fp = fingerprint(&s);
-                (s, fp, ebits.clone(), NonZeroUsize::new(1).unwrap(), vec![i])
-            })
-            .collect();
         let discoveries = Arc::new(DashMap::default());
         let mut handles = Vec::new();
 
         let close_at = options.timeout.map(|t| SystemTime::now() + t);
         let mut job_broker = JobBroker::new(thread_count, close_at);
-        job_broker.push(pending);
+        let pending_limit = options.disk.as_ref().map(|disk| disk.memory_limit.max(1));
+        let mut spill = scratch_dir
+            .zip(pending_limit)
+            .map(|(scratch_dir, pending_limit)| {
+                Self::spill(Arc::clone(&model), scratch_dir, pending_limit)
+            });
+        let checkpoint = options.resume_from.as_ref().and_then(|path| {
+            CheckpointReader::open(path)
+                .and_then(|reader| {
+                    reader
+                        .map(|mut reader| {
+                            Self::read_checkpoint(
+                                &mut reader,
+                                &model,
+                                &state_count,
+                                &max_depth,
+                                &generated,
+                                &discoveries,
+                                spill.as_mut(),
+                            )
+                        })
+                        .transpose()
+                })
+                .unwrap_or_else(|err| panic!("Failed to read checkpoint {}: {err}", path.display()))
+        });
+        let sleep_sets = options
+            .partial_order_reduction
+            .then(|| Arc::new(SleepSets::new()));
+        let levels = (options.shortest_discoveries || options.deterministic)
+            .then(|| Arc::new(Levels::new(1, init_states.len(), options.deterministic)));
+        match checkpoint {
+            Some(batches) => job_broker.restore(batches),
+            None => {
+                for s in &init_states {
+                    generated.insert(fingerprint(s));
+                }
+                let pending: VecDeque<_> = init_states
+                    .into_iter()
+                    .enumerate()
+                    .map(|(i, s)| {
+                        (
+                            s,
+                            ebits.clone(),
+                            NonZeroUsize::new(1).unwrap(),
+                            vec![i],
+                            SleepSet::default(),
+                        )
+                    })
+                    .collect();
+                job_broker.push(pending);
+            }
+        }
+        if let Some(spill) = spill {
+            job_broker.spill(spill);
+        }
+        if let Some(checkpoint) = options.checkpoint {
+            let state_count = Arc::clone(&state_count);
+            let max_depth = Arc::clone(&max_depth);
+            let generated = Arc::clone(&generated);
+            let discoveries = Arc::clone(&discoveries);
+            let levels = levels.clone();
+            job_broker.checkpoint(checkpoint.interval, move |(mut batches, spill)| {
+                let levels = levels.as_ref().map(|levels| levels.inner.lock());
+                if let Some(levels) = &levels {
+                    batches.extend(&levels.next);
+                    batches.extend(levels.claimed.as_ref().map(|(claimed, _)| claimed));
+                }
+                let written = CheckpointWriter::create(&checkpoint.path).and_then(|mut writer| {
+                    Self::write_checkpoint(
+                        &mut writer,
+                        &state_count,
+                        &max_depth,
+                        &generated,
+                        &discoveries,
+                        (batches, spill),
+                    )?;
+                    writer.finish()
+                });
+                if let Err(err) = written {
+                    log::error!(
+                        "Failed to write checkpoint {}: {err}",
+                        checkpoint.path.display()
+                    );
+                }
+            });
+        }
 
         for t in 0..thread_count {
             let model = Arc::clone(&model);
@@ -106,6 +317,8 @@ where
             let max_depth = Arc::clone(&max_depth);
             let generated = Arc::clone(&generated);
             let discoveries = Arc::clone(&discoveries);
+            let sleep_sets = sleep_sets.clone();
+            let levels = levels.clone();
             handles.push(
                 std::thread::Builder::new()
                     .name(format!("checker-{t}"))
@@ -129,6 +342,8 @@ where
                                     jobs
                                 };
                             }
+                            let level = levels.as_ref().map(|levels| levels.level());
+                            let pending_count = pending.len();
                             Self::check_block(
                                 &model,
                                 &state_count,
@@ -139,11 +354,34 @@ where
                                 1500,
                                 target_max_depth,
                                 &max_depth,
+                                sleep_sets.as_deref(),
+                                level,
                             );
-                            if finish_when.matches(
+                            let is_finished = finish_when.matches(
                                 &discoveries.iter().map(|r| *r.key()).collect(),
                                 &properties,
-                            ) {
+                            );
+                            let is_at_target = target_state_count.is_some_and(|target| {
+                                target.get() <= state_count.load(Ordering::Relaxed)
+                            });
+                            if let Some(levels) = &levels {
+                                // Shorter discoveries may remain at the current depth, so it is
+                                // completed before shutting down. Likewise when checking
+                                // deterministically and the target state count is reached, so
+                                // that the count does not depend on when it is noticed.
+                                let level = level.unwrap();
+                                let unchecked = pending
+                                    .iter()
+                                    .filter(|job| job.2.get() == level.depth)
+                                    .count();
+                                levels.commit(
+                                    &mut pending,
+                                    pending_count - unchecked,
+                                    is_finished || (level.deterministic && is_at_target),
+                                    &generated,
+                                    &mut job_broker,
+                                );
+                            } else if is_finished {
                                 log::debug!(
                                     "{}: Discovery complete. Shutting down... gen={}",
                                     t,
@@ -151,57 +389,308 @@ where
                                 );
                                 return;
                             }
-                            if let Some(target_state_count) = target_state_count {
-                                if target_state_count.get() <= state_count.load(Ordering::Relaxed) {
-                                    log::debug!(
-                                        "{}: Reached target state count. Shutting down... gen={}",
-                                        t,
-                                        generated.len()
-                                    );
-                                    return;
-                                }
+                            if is_at_target && !level.is_some_and(|level| level.deterministic) {
+                                log::debug!(
+                                    "{}: Reached target state count. Shutting down... gen={}",
+                                    t,
+                                    generated.len()
+                                );
+                                return;
                             }
 
                             // Step 2: Share work.
                             if pending.len() > 1 && thread_count > 1 {
                                 job_broker.split_and_push(&mut pending);
                             }
+
+                            // Step 3: Spill the most recently generated states if too many are
+                            // pending.
+                            if let Some(pending_limit) = pending_limit {
+                                if pending.len() > pending_limit {
+                                    let oldest = pending.split_off(pending.len() - pending_limit);
+                                    job_broker.push(std::mem::replace(&mut pending, oldest));
+                                }
+                            }
+
+                            // Step 4: Checkpoint.
+                            job_broker.checkpoint_if_due(&mut pending);
                         }
                     })
                     .expect("Failed to spawn a thread"),
             );
         }
+
+        // Paths ending in a cycle are not identified above, so if enabled, `eventually` and
+        // temporal logic properties are further checked once exploration completes.
+        let has_liveness = liveness::is_enabled(options.detect_cycles, &properties);
+        let loop_starts = Arc::new(DashMap::default());
+        let liveness_done = Arc::new(AtomicBool::new(!has_liveness));
+        if has_liveness {
+            let should_stop = should_stop(
+                close_at,
+                target_state_count,
+                finish_when,
+                Arc::clone(&properties),
+                Arc::clone(&state_count),
+                Arc::clone(&discoveries),
+            );
+            handles = vec![liveness::spawn_after(
+                handles,
+                Arc::clone(&model),
+                Arc::clone(&discoveries),
+                Arc::clone(&loop_starts),
+                target_max_depth,
+                options.fairness,
+                Arc::clone(&liveness_done),
+                should_stop,
+            )];
+        }
         BfsChecker {
             model,
             handles,
+            shrink_discoveries,
             job_broker,
             state_count,
             max_depth,
             generated,
             discoveries,
+            loop_starts,
+            liveness_done,
         }
     }
 
+    /// Writes pending states as the action indices that reach them, which are replayed when
+    /// reading them back.
+    fn spill(
+        model: Arc<M>,
+        scratch_dir: Arc<ScratchDir>,
+        memory_limit: usize,
+    ) -> Spill<Job<M::State, M::Action>>
+    where
+        M::State: Clone + PartialEq,
+        M::Action: Clone + PartialEq,
+    {
+        Spill::new(scratch_dir, memory_limit, Self::encode_job, move |bytes| {
+            Self::decode_job(&model, bytes).expect("Failed to decode a spilled job")
+        })
+    }
+
+    fn encode_job((_, ebits, _, action_path, _): &Job<M::State, M::Action>, bytes: &mut Vec<u8>) {
+        Self::encode_job_progress(ebits, action_path, bytes);
+    }
+
+    /// Encodes the `eventually` properties not yet satisfied along an action path, and the path.
+    pub(crate) fn encode_job_progress(
+        ebits: &EventuallyBits,
+        action_path: &[usize],
+        bytes: &mut Vec<u8>,
+    ) {
+        disk::write_usize(bytes, ebits.len());
+        for i in ebits {
+            disk::write_usize(bytes, i);
+        }
+        disk::write_usize(bytes, action_path.len());
+        for &i in action_path {
+            disk::write_usize(bytes, i);
+        }
+    }
+
+    fn decode_job(model: &M, bytes: &mut &[u8]) -> std::io::Result<Job<M::State, M::Action>>
+    where
+        M::State: Clone + PartialEq,
+        M::Action: Clone + PartialEq,
+    {
+        let (ebits, depth, action_path) = Self::decode_job_progress(bytes)?;
+        let state = Path::final_state(model, VecDeque::from(action_path.clone()))
+            .expect("Failed to replay the path to a pending state");
+        // Sleep sets are not written, as an empty sleep set only forgoes some reduction.
+        Ok((state, ebits, depth, action_path, SleepSet::default()))
+    }
+
+    /// Consumes what [`BfsChecker::encode_job_progress`] wrote, also returning the depth of the
+    /// job.
+    pub(crate) fn decode_job_progress(
+        bytes: &mut &[u8],
+    ) -> std::io::Result<(EventuallyBits, NonZeroUsize, Vec<usize>)> {
+        let ebits = (0..disk::read_usize(bytes)?)
+            .map(|_| disk::read_usize(bytes))
+            .collect::<std::io::Result<_>>()?;
+        let action_path = (0..disk::read_usize(bytes)?)
+            .map(|_| disk::read_usize(bytes))
+            .collect::<std::io::Result<Vec<_>>>()?;
+        let depth = NonZeroUsize::new(action_path.len()).ok_or_else(|| {
+            std::io::Error::new(std::io::ErrorKind::InvalidData, "empty action path")
+        })?;
+        Ok((ebits, depth, action_path))
+    }
+
+    fn write_checkpoint(
+        writer: &mut CheckpointWriter,
+        state_count: &AtomicUsize,
+        max_depth: &AtomicUsize,
+        generated: &FingerprintSet,
+        discoveries: &DashMap<&'static str, Vec<usize>>,
+        (batches, spill): CheckpointJobs<Job<M::State, M::Action>>,
+    ) -> std::io::Result<()> {
+        writer.usize(state_count.load(Ordering::Relaxed))?;
+        writer.usize(max_depth.load(Ordering::Relaxed))?;
+
+        writer.usize(discoveries.len())?;
+        for discovery in discoveries.iter() {
+            writer.bytes(discovery.key().as_bytes())?;
+            writer.usize(discovery.value().len())?;
+            for &i in discovery.value() {
+                writer.usize(i)?;
+            }
+        }
+
+        writer.usize(generated.len())?;
+        let mut written = Ok(());
+        generated.for_each(|fp| {
+            if written.is_ok() {
+                written = writer.u64(fp.get());
+            }
+        });
+        written?;
+
+        writer.usize(batches.len())?;
+        for jobs in batches {
+            let mut bytes = Vec::new();
+            for job in jobs {
+                Self::encode_job(job, &mut bytes);
+            }
+            writer.usize(jobs.len())?;
+            writer.bytes(&bytes)?;
+        }
+        let spilled: Vec<_> = spill.iter().flat_map(|s| s.encoded_batches()).collect();
+        writer.usize(spilled.len())?;
+        for (count, bytes) in spilled {
+            writer.usize(count)?;
+            writer.bytes(&bytes)?;
+        }
+        Ok(())
+    }
+
+    /// Restores the progress recorded by [`Self::write_checkpoint`], returning the batches of
+    /// pending states to restore to the job broker. Batches that were spilled are spilled again
+    /// if possible.
+    #[allow(clippy::too_many_arguments, clippy::type_complexity)]
+    fn read_checkpoint(
+        reader: &mut CheckpointReader,
+        model: &M,
+        state_count: &AtomicUsize,
+        max_depth: &AtomicUsize,
+        generated: &FingerprintSet,
+        discoveries: &DashMap<&'static str, Vec<usize>>,
+        spill: Option<&mut Spill<Job<M::State, M::Action>>>,
+    ) -> std::io::Result<Vec<VecDeque<Job<M::State, M::Action>>>>
+    where
+        M::State: Clone + PartialEq,
+        M::Action: Clone + PartialEq,
+    {
+        state_count.store(reader.usize()?, Ordering::Relaxed);
+        max_depth.store(reader.usize()?, Ordering::Relaxed);
+
+        let properties = model.properties();
+        for _ in 0..reader.usize()? {
+            let name = reader.bytes()?;
+            let action_path = (0..reader.usize()?)
+                .map(|_| reader.usize())
+                .collect::<Result<_, _>>()?;
+            match properties.iter().find(|p| p.name.as_bytes() == name) {
+                Some(property) => {
+                    discoveries.insert(property.name, action_path);
+                }
+                None => log::warn!(
+                    "Ignoring checkpointed discovery for unknown property {:?}",
+                    String::from_utf8_lossy(&name)
+                ),
+            }
+        }
+
+        for _ in 0..reader.usize()? {
+            let fp = Fingerprint::new(reader.u64()?).ok_or_else(|| {
+                std::io::Error::new(std::io::ErrorKind::InvalidData, "invalid fingerprint")
+            })?;
+            generated.insert(fp);
+        }
+
+        let mut batches = Vec::new();
+        for _ in 0..reader.usize()? {
+            let count = reader.usize()?;
+            let bytes = reader.bytes()?;
+            let mut bytes = &bytes[..];
+            batches.push(
+                (0..count)
+                    .map(|_| Self::decode_job(model, &mut bytes))
+                    .collect::<std::io::Result<_>>()?,
+            );
+        }
+        let mut spill = spill;
+        for _ in 0..reader.usize()? {
+            let count = reader.usize()?;
+            let bytes = reader.bytes()?;
+            match &mut spill {
+                Some(spill) => spill.write_encoded(count, bytes),
+                None => {
+                    let mut bytes = &bytes[..];
+                    // Spilled batches are read after those in memory, so they go to the front.
+                    batches.insert(
+                        0,
+                        (0..count)
+                            .map(|_| Self::decode_job(model, &mut bytes))
+                            .collect::<std::io::Result<_>>()?,
+                    );
+                }
+            }
+        }
+        Ok(batches)
+    }
+
     #[allow(clippy::too_many_arguments)]
-    fn check_block(
+    pub(crate) fn check_block(
         model: &M,
         state_count: &AtomicUsize,
-        generated: &DashMap<
-            Fingerprint,
-            Option<Fingerprint>,
-            BuildHasherDefault<NoHashHasher<u64>>,
-        >,
-        pending: &mut VecDeque<Job<M::State>>,
+        generated: &FingerprintSet,
+        pending: &mut VecDeque<Job<M::State, M::Action>>,
         discoveries: &DashMap<&'static str, Vec<usize>>,
         visitor: &Option<Box<dyn CheckerVisitor<M> + Send + Sync>>,
         mut max_count: usize,
         target_max_depth: Option<NonZeroUsize>,
         global_max_depth: &AtomicUsize,
+        sleep_sets: Option<&SleepSets<M::Action>>,
+        level: Option<Level>,
     ) where
         M::State: Clone + PartialEq,
         M::Action: Clone + PartialEq,
     {
         let properties = model.properties();
+        let has_transitions = properties
+            .iter()
+            .any(|p| matches!(p.predicate, Predicate::Transition(_)));
+        // Transition properties must see every step, so they preclude reduction.
+        let sleep_sets = sleep_sets.filter(|_| !has_transitions);
+
+        let deterministic = level.is_some_and(|level| level.deterministic);
+        // Reduction depends on the order in which states are reached, so it is not deterministic.
+        let sleep_sets = sleep_sets.filter(|_| !deterministic);
+
+        // When checking one depth at a time, the first discovery is kept as it is one of the
+        // shortest, and deeper states are left pending.
+        let record_discovery = |name, action_path: &Vec<usize>| match level {
+            Some(Level {
+                deterministic: true,
+                ..
+            }) => record_least_discovery(discoveries, name, action_path.clone()),
+            Some(_) => {
+                discoveries
+                    .entry(name)
+                    .or_insert_with(|| action_path.clone());
+            }
+            None => {
+                discoveries.insert(name, action_path.clone());
+            }
+        };
 
         let mut current_max_depth = global_max_depth.load(Ordering::Relaxed);
         let mut actions = Vec::new();
@@ -212,8 +701,17 @@ where
             }
             max_count -= 1;
 
+            // Done if none pending at the depth being checked.
+            if pending
+                .back()
+                .zip(level)
+                .is_some_and(|(job, level)| job.2.get() > level.depth)
+            {
+                return;
+            }
+
             // Done if none pending.
-            let (state, state_fp, mut ebits, max_depth, action_path) = match pending.pop_back() {
+            let (state, mut ebits, max_depth, action_path, sleep_set) = match pending.pop_back() {
                 None => return,
                 Some(pair) => pair,
             };
@@ -246,41 +744,29 @@ where
             let mut is_awaiting_discoveries = false;
             for (i, property) in properties.iter().enumerate() {
                 if discoveries.contains_key(property.name)
-                    && (property.expectation == Expectation::Eventually)
+                    && matches!(property.predicate, Predicate::Eventually(_))
                     && !ebits.contains(i)
                 {
                     continue;
                 }
-                match property {
-                    Property {
-                        expectation: Expectation::Always,
-                        condition: always,
-                        ..
-                    } => {
+                match &property.predicate {
+                    Predicate::Always(always) => {
                         if !always(model, &state) {
                             // Races other threads, but that's fine.
-                            discoveries.insert(property.name, action_path.clone());
+                            record_discovery(property.name, &action_path);
                         } else {
                             is_awaiting_discoveries = true;
                         }
                     }
-                    Property {
-                        expectation: Expectation::Sometimes,
-                        condition: sometimes,
-                        ..
-                    } => {
+                    Predicate::Sometimes(sometimes) => {
                         if sometimes(model, &state) {
                             // Races other threads, but that's fine.
-                            discoveries.insert(property.name, action_path.clone());
+                            record_discovery(property.name, &action_path);
                         } else {
                             is_awaiting_discoveries = true;
                         }
                     }
-                    Property {
-                        expectation: Expectation::Eventually,
-                        condition: eventually,
-                        ..
-                    } => {
+                    Predicate::Eventually(eventually) => {
                         // The checker early exits after finding discoveries for every property,
                         // and "eventually" property discoveries are only identified at terminal
                         // states, so if we are here it means we are still awaiting a corresponding
@@ -291,16 +777,33 @@ where
                             ebits.remove(i);
                         }
                     }
+                    Predicate::Transition(_) | Predicate::Ltl(_) => {
+                        // Transitions are checked as steps are taken, and temporal logic
+                        // properties are checked separately.
+                        if !discoveries.contains_key(property.name) {
+                            is_awaiting_discoveries = true;
+                        }
+                    }
                 }
             }
-            if !is_awaiting_discoveries {
+            // When checking deterministically, every state at the depth is expanded regardless, as
+            // which discoveries have been found depends on how threads are scheduled.
+            if !is_awaiting_discoveries && !deterministic {
                 return;
             }
 
             // Otherwise enqueue newly generated states (with related metadata).
             let mut is_terminal = true;
+            let mut taken = Vec::new(); // Steps of processes, for sleep sets (see sleep.rs).
             model.actions(&state, &mut actions);
             for (action_idx, action) in actions.drain(..).enumerate() {
+                // Skip if the step commutes with one taken before reaching this state.
+                if sleep_set.skips(&action) {
+                    is_terminal = false;
+                    continue;
+                }
+                let transition_action = has_transitions.then(|| action.clone());
+                let reduced_action = sleep_sets.is_some().then(|| action.clone());
                 let next_state = model.next_state(&state, action);
                 if next_state.is_none() {
                     continue;
@@ -313,46 +816,67 @@ where
                     continue;
                 }
                 state_count.fetch_add(1, Ordering::Relaxed);
+                let next_sleep_set = reduced_action
+                    .and_then(|action| {
+                        let process = model.step_process(&state, &action, &next_state)?;
+                        let next_sleep_set = sleep_set.after(&taken, process);
+                        taken.push((action, process));
+                        Some(next_sleep_set)
+                    })
+                    .unwrap_or_default();
+
+                if let Some(action) = &transition_action {
+                    check_transitions(
+                        model,
+                        &properties,
+                        discoveries,
+                        &state,
+                        action,
+                        &next_state,
+                        &action_path,
+                        action_idx,
+                        deterministic,
+                    );
+                }
 
                 // Skip if already generated.
                 //
-                // FIXME: we should really include ebits in the fingerprint here --
-                // it is possible to arrive at a DAG join with two different ebits
-                // values, and subsequently treat the fact that some eventually
-                // property held on the path leading to the first visit as meaning
-                // that it holds in the path leading to the second visit -- another
-                // possible false-negative.
+                // Note that ebits are not part of the fingerprint, so a DAG join reached with
+                // two different ebits values is only explored with the first. The liveness pass
+                // that follows exploration compensates for the resulting false negatives.
                 let next_fingerprint = fingerprint(&next_state);
-                if let Entry::Vacant(next_entry) = generated.entry(next_fingerprint) {
-                    next_entry.insert(Some(state_fp));
-                } else {
-                    // FIXME: arriving at an already-known state may be a loop (in which case it
-                    // could, in a fancier implementation, be considered a terminal state for
-                    // purposes of eventually-property checking) but it might also be a join in
-                    // a DAG, which makes it non-terminal. These cases can be disambiguated (at
-                    // some cost), but for now we just _don't_ treat them as terminal, and tell
-                    // users they need to explicitly ensure model path-acyclicality when they're
-                    // using eventually properties (using a boundary or empty actions or
-                    // whatever).
+                let next_sleep_set = match sleep_sets {
+                    // When checking deterministically, states are instead claimed once the block
+                    // is checked (see `Levels::commit`).
+                    _ if deterministic => Some(next_sleep_set),
+                    Some(sleep_sets) => sleep_sets.visit(next_fingerprint, next_sleep_set, || {
+                        generated.insert(next_fingerprint)
+                    }),
+                    None => generated.insert(next_fingerprint).then_some(next_sleep_set),
+                };
+                let Some(next_sleep_set) = next_sleep_set else {
+                    // Arriving at an already-known state may be a loop or a join in a DAG. It is
+                    // not treated as terminal here, and loops are instead identified by the
+                    // liveness pass that follows exploration (see liveness.rs).
                     is_terminal = false;
                     continue;
-                }
+                };
 
                 // Otherwise further checking is applicable.
                 is_terminal = false;
                 pending.push_front((
                     next_state,
-                    next_fingerprint,
                     ebits.clone(),
                     NonZeroUsize::new(max_depth.get() + 1).unwrap(),
                     next_action_path,
+                    next_sleep_set,
                 ));
             }
             if is_terminal {
                 for (i, property) in properties.iter().enumerate() {
                     if ebits.contains(i) {
                         // Races other threads, but that's fine.
-                        discoveries.insert(property.name, action_path.clone());
+                        record_discovery(property.name, &action_path);
                     }
                 }
             }
@@ -389,10 +913,13 @@ where
         self.discoveries
             .iter()
             .map(|mapref| {
-                (
-                    <&'static str>::clone(mapref.key()),
-                    Path::from_action_indices(self.model(), VecDeque::from(mapref.value().clone())),
-                )
+                let name = <&'static str>::clone(mapref.key());
+                let path =
+                    Path::from_action_indices(self.model(), VecDeque::from(mapref.value().clone()));
+                match self.loop_starts.get(name) {
+                    Some(loop_start) => (name, path.with_loop_start(*loop_start)),
+                    None => (name, path),
+                }
             })
             .collect()
     }
@@ -401,8 +928,13 @@ where
         std::mem::take(&mut self.handles)
     }
 
+    fn shrinks_discoveries(&self) -> bool {
+        self.shrink_discoveries
+    }
+
     fn is_done(&self) -> bool {
-        self.job_broker.is_closed() || self.discoveries.len() == self.model.properties().len()
+        (self.job_broker.is_closed() && self.liveness_done.load(Ordering::Relaxed))
+            || self.discoveries.len() == self.model.properties().len()
     }
 }
 
@@ -411,6 +943,7 @@ mod test {
     use super::*;
     use crate::test_util::linear_equation_solver::*;
     use crate::*;
+    use std::time::Duration;
 
     #[test]
     fn visits_states_in_bfs_order() {
@@ -450,6 +983,96 @@ mod test {
         assert_eq!(checker.unique_state_count(), 256 * 256);
     }
 
+    #[test]
+    fn can_complete_by_spilling_to_disk() {
+        let checker = LinearEquation { a: 2, b: 4, c: 7 }
+            .checker()
+            .spill_to_disk(std::env::temp_dir(), 5_000)
+            .spawn_bfs()
+            .join();
+        assert!(checker.is_done());
+        checker.assert_no_discovery("solvable");
+        assert_eq!(checker.unique_state_count(), 256 * 256);
+
+        // Also spills pending states.
+        for thread_count in [1, 4] {
+            let checker = LinearEquation { a: 2, b: 10, c: 14 }
+                .checker()
+                .spill_to_disk(std::env::temp_dir(), 1)
+                .threads(thread_count)
+                .spawn_bfs()
+                .join();
+            checker.assert_properties();
+        }
+    }
+
+    #[test]
+    fn can_resume_from_checkpoint() {
+        // x + y = 200 is first reached after several blocks of states have been checked.
+        let model = || LinearEquation { a: 1, b: 1, c: 200 };
+        let path = std::env::temp_dir().join(format!(
+            "stateright-bfs-test-{}.checkpoint",
+            std::process::id()
+        ));
+        let uninterrupted = model().checker().spawn_bfs().join();
+
+        let interrupted = model()
+            .checker()
+            .checkpoint(&path, Duration::ZERO)
+            .target_state_count(10_000)
+            .spawn_bfs()
+            .join();
+        assert_eq!(interrupted.discovery("solvable"), None);
+        let resumed = model()
+            .checker()
+            .checkpoint(&path, Duration::ZERO)
+            .resume_from(&path)
+            .spawn_bfs()
+            .join();
+        assert_eq!(
+            resumed.discovery("solvable").unwrap().into_actions(),
+            uninterrupted.discovery("solvable").unwrap().into_actions()
+        );
+        assert_eq!(resumed.state_count(), uninterrupted.state_count());
+        assert_eq!(
+            resumed.unique_state_count(),
+            uninterrupted.unique_state_count()
+        );
+
+        // Also with multiple threads, spilling to disk.
+        let _ = std::fs::remove_file(&path);
+        LinearEquation { a: 2, b: 4, c: 7 }
+            .checker()
+            .checkpoint(&path, Duration::ZERO)
+            .spill_to_disk(std::env::temp_dir(), 1_000)
+            .threads(4)
+            .target_state_count(20_000)
+            .spawn_bfs()
+            .join();
+        let resumed = LinearEquation { a: 2, b: 4, c: 7 }
+            .checker()
+            .spill_to_disk(std::env::temp_dir(), 1_000)
+            .resume_from(&path)
+            .threads(4)
+            .spawn_bfs()
+            .join();
+        resumed.assert_no_discovery("solvable");
+        assert_eq!(resumed.unique_state_count(), 256 * 256);
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    #[should_panic(expected = "does not support CheckerBuilder::shortest_discoveries")]
+    fn cannot_resume_with_shortest_discoveries() {
+        let path = std::env::temp_dir().join("stateright-bfs-unsupported-resume.checkpoint");
+        LinearEquation { a: 2, b: 10, c: 14 }
+            .checker()
+            .shortest_discoveries()
+            .resume_from(path)
+            .spawn_bfs()
+            .join();
+    }
+
     #[test]
     fn can_complete_by_eliminating_properties() {
         let checker = LinearEquation { a: 2, b: 10, c: 14 }
@@ -473,6 +1096,99 @@ mod test {
         );
     }
 
+    /// A tree in which each node has four children, so states that are not checked are not
+    /// reached otherwise.
+    struct Tree;
+    impl Tree {
+        const SIZE: usize = (4usize.pow(9) - 1) / 3;
+    }
+    impl Model for Tree {
+        type State = usize;
+        type Action = usize;
+        fn init_states(&self) -> Vec<Self::State> {
+            vec![0]
+        }
+        fn actions(&self, state: &Self::State, actions: &mut Vec<Self::Action>) {
+            if 4 * state + 4 < Self::SIZE {
+                actions.extend((1..=4).map(|i| 4 * state + i));
+            }
+        }
+        fn next_state(&self, _: &Self::State, action: Self::Action) -> Option<Self::State> {
+            Some(action)
+        }
+        fn properties(&self) -> Vec<Property<Self>> {
+            vec![Property::always("true", |_, _| true)]
+        }
+    }
+
+    #[test]
+    fn can_guarantee_shortest_discoveries() {
+        for thread_count in [1, 4] {
+            let checker = LinearEquation { a: 1, b: 1, c: 200 }
+                .checker()
+                .shortest_discoveries()
+                .threads(thread_count)
+                .spawn_bfs()
+                .join();
+            let discovery = checker.discovery("solvable").unwrap();
+            assert_eq!(discovery.into_actions().len(), 200);
+
+            // Also when every state is checked.
+            let checker = LinearEquation { a: 2, b: 4, c: 7 }
+                .checker()
+                .shortest_discoveries()
+                .threads(thread_count)
+                .spawn_bfs()
+                .join();
+            checker.assert_no_discovery("solvable");
+            assert_eq!(checker.unique_state_count(), 256 * 256);
+        }
+
+        // States of the next depth are included in checkpoints, which are written before a depth
+        // is complete if it has more states than a thread checks at once.
+        let path = std::env::temp_dir().join(format!(
+            "stateright-bfs-shortest-test-{}.checkpoint",
+            std::process::id()
+        ));
+        Tree.checker()
+            .checkpoint(&path, Duration::ZERO)
+            .shortest_discoveries()
+            .target_state_count(15_000)
+            .spawn_bfs()
+            .join();
+        let resumed = Tree.checker().resume_from(&path).spawn_bfs().join();
+        assert_eq!(resumed.unique_state_count(), Tree::SIZE);
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn can_check_deterministically() {
+        let check = |(a, b, c), thread_count| {
+            let checker = LinearEquation { a, b, c }
+                .checker()
+                .deterministic()
+                .threads(thread_count)
+                .target_state_count(20_000)
+                .spawn_bfs()
+                .join();
+            (
+                checker.discovery("solvable").map(Path::into_actions),
+                checker.state_count(),
+                checker.unique_state_count(),
+                checker.max_depth(),
+            )
+        };
+        // The second is not solvable, so the check stops at the target state count.
+        for model in [(1, 1, 100), (2, 4, 7)] {
+            let expected = check(model, 1);
+            for _ in 0..3 {
+                assert_eq!(check(model, 4), expected);
+            }
+        }
+        // The least of the shortest paths.
+        assert_eq!(check((1, 1, 100), 4).0, Some(vec![Guess::IncreaseX; 100]));
+    }
+
     // test that the checker shuts down all threads properly after a checker thread encounters a
     // panic in the model execution.
     #[test]
@@ -0,0 +1,143 @@
+//! Private module for selective re-export.
+
+use crate::Fingerprint;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+
+/// The size of the bit array and the number of bits per state. See [`CheckerBuilder::bitstate`].
+///
+/// [`CheckerBuilder::bitstate`]: crate::CheckerBuilder::bitstate
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct BitstateOptions {
+    pub(crate) bits: usize,
+    pub(crate) hash_count: usize,
+}
+
+/// A Bloom filter of generated states, as in Holzmann's bitstate hashing. Each state sets
+/// `hash_count` bits derived from its fingerprint, and a state whose bits are all set is
+/// considered generated, so a state can be omitted if other states happen to have set its bits.
+pub(crate) struct Bitstate {
+    words: Vec<AtomicU64>,
+    hash_count: usize,
+    set_bits: AtomicUsize,
+    inserted: AtomicUsize,
+}
+
+impl Bitstate {
+    pub(crate) fn new(options: BitstateOptions) -> Self {
+        Bitstate {
+            words: (0..options.bits.next_power_of_two().div_ceil(64))
+                .map(|_| AtomicU64::new(0))
+                .collect(),
+            hash_count: options.hash_count,
+            set_bits: AtomicUsize::new(0),
+            inserted: AtomicUsize::new(0),
+        }
+    }
+
+    /// Sets the bits of a fingerprint, returning whether any was not already set.
+    pub(crate) fn insert(&self, fingerprint: Fingerprint) -> bool {
+        let mut is_new = false;
+        for (word, mask) in self.bits(fingerprint) {
+            if word.fetch_or(mask, Ordering::Relaxed) & mask == 0 {
+                self.set_bits.fetch_add(1, Ordering::Relaxed);
+                is_new = true;
+            }
+        }
+        if is_new {
+            self.inserted.fetch_add(1, Ordering::Relaxed);
+        }
+        is_new
+    }
+
+    /// Indicates whether every bit of a fingerprint is set, which holds for the fingerprints
+    /// inserted so far and possibly for others.
+    pub(crate) fn contains(&self, fingerprint: Fingerprint) -> bool {
+        self.bits(fingerprint)
+            .all(|(word, mask)| word.load(Ordering::Relaxed) & mask != 0)
+    }
+
+    /// The word and mask of each bit of a fingerprint.
+    fn bits(&self, fingerprint: Fingerprint) -> impl Iterator<Item = (&AtomicU64, u64)> {
+        let bit_count = self.words.len() as u64 * 64;
+        // Double hashing derives the bits from two hashes. The second is odd, so with a power of
+        // two bits, the bits of a state are distinct.
+        let first = fingerprint.get();
+        let second = mix(first) | 1;
+        (0..self.hash_count as u64).map(move |i| {
+            let bit = first.wrapping_add(i.wrapping_mul(second)) % bit_count;
+            (&self.words[(bit / 64) as usize], 1 << (bit % 64))
+        })
+    }
+
+    /// The number of states that were inserted, which excludes any that were omitted.
+    pub(crate) fn len(&self) -> usize {
+        self.inserted.load(Ordering::Relaxed)
+    }
+
+    pub(crate) fn clear(&self) {
+        for word in &self.words {
+            word.store(0, Ordering::Relaxed);
+        }
+        self.set_bits.store(0, Ordering::Relaxed);
+        self.inserted.store(0, Ordering::Relaxed);
+    }
+
+    /// The probability that a new state is omitted because its bits are already set, which is an
+    /// upper bound for each state inserted so far as the bit array only fills up.
+    pub(crate) fn omission_probability(&self) -> f64 {
+        let fill = self.set_bits.load(Ordering::Relaxed) as f64 / (self.words.len() * 64) as f64;
+        fill.powi(self.hash_count as i32)
+    }
+}
+
+/// The SplitMix64 finalizer, which decorrelates the second hash from the first.
+fn mix(mut z: u64) -> u64 {
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::num::NonZeroU64;
+
+    fn fingerprint(n: u64) -> Fingerprint {
+        NonZeroU64::new(mix(n) | 1).unwrap()
+    }
+
+    #[test]
+    fn inserts_each_state_once() {
+        let bitstate = Bitstate::new(BitstateOptions {
+            bits: 1 << 20,
+            hash_count: 3,
+        });
+        for n in 0..1_000 {
+            assert!(bitstate.insert(fingerprint(n)));
+        }
+        for n in 0..1_000 {
+            assert!(!bitstate.insert(fingerprint(n)));
+        }
+        assert_eq!(bitstate.len(), 1_000);
+        assert!(bitstate.omission_probability() < 1e-6);
+
+        bitstate.clear();
+        assert_eq!(bitstate.len(), 0);
+        assert_eq!(bitstate.omission_probability(), 0.0);
+        assert!(bitstate.insert(fingerprint(0)));
+    }
+
+    #[test]
+    fn omits_states_once_full() {
+        let bitstate = Bitstate::new(BitstateOptions {
+            bits: 64,
+            hash_count: 2,
+        });
+        let inserted = (0..1_000)
+            .filter(|&n| bitstate.insert(fingerprint(n)))
+            .count();
+        assert!(inserted < 1_000);
+        assert_eq!(bitstate.len(), inserted);
+        assert_eq!(bitstate.omission_probability(), 1.0);
+    }
+}
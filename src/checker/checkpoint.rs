@@ -0,0 +1,180 @@
+//! Private module for selective re-export.
+
+use crate::checker::disk;
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter, Read, Seek, Write};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// Where and how often a checker writes checkpoints. See [`CheckerBuilder::checkpoint`].
+///
+/// [`CheckerBuilder::checkpoint`]: crate::CheckerBuilder::checkpoint
+#[derive(Clone, Debug)]
+pub(crate) struct CheckpointOptions {
+    pub(crate) path: PathBuf,
+    pub(crate) interval: Duration,
+}
+
+/// Identifies the file format, which is otherwise a sequence of variable length integers and
+/// length-prefixed byte strings.
+const HEADER: &[u8] = b"stateright checkpoint 1\n";
+
+/// Writes a checkpoint to a temporary file, which replaces the checkpoint once finished so that
+/// an interrupted write does not corrupt an earlier checkpoint.
+pub(crate) struct CheckpointWriter {
+    writer: BufWriter<File>,
+    path: PathBuf,
+    temp_path: PathBuf,
+}
+
+impl CheckpointWriter {
+    pub(crate) fn create(path: &Path) -> io::Result<Self> {
+        let mut temp_path = path.as_os_str().to_owned();
+        temp_path.push(".tmp");
+        let temp_path = PathBuf::from(temp_path);
+        let mut writer = BufWriter::new(File::create(&temp_path)?);
+        writer.write_all(HEADER)?;
+        Ok(CheckpointWriter {
+            writer,
+            path: path.to_owned(),
+            temp_path,
+        })
+    }
+
+    pub(crate) fn usize(&mut self, value: usize) -> io::Result<()> {
+        let mut bytes = Vec::with_capacity(10);
+        disk::write_usize(&mut bytes, value);
+        self.writer.write_all(&bytes)
+    }
+
+    pub(crate) fn u64(&mut self, value: u64) -> io::Result<()> {
+        self.writer.write_all(&value.to_le_bytes())
+    }
+
+    pub(crate) fn bytes(&mut self, bytes: &[u8]) -> io::Result<()> {
+        self.usize(bytes.len())?;
+        self.writer.write_all(bytes)
+    }
+
+    pub(crate) fn finish(self) -> io::Result<()> {
+        let file = self.writer.into_inner().map_err(|err| err.into_error())?;
+        file.sync_all()?;
+        drop(file);
+        std::fs::rename(&self.temp_path, &self.path)
+    }
+}
+
+/// Reads a checkpoint written by a [`CheckpointWriter`].
+pub(crate) struct CheckpointReader {
+    reader: BufReader<File>,
+    /// The length of the file, which bounds the length of a byte string.
+    len: u64,
+}
+
+impl CheckpointReader {
+    /// Opens a checkpoint, returning `None` if there is no such file.
+    pub(crate) fn open(path: &Path) -> io::Result<Option<Self>> {
+        let file = match File::open(path) {
+            Ok(file) => file,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(err) => return Err(err),
+        };
+        let len = file.metadata()?.len();
+        let mut reader = BufReader::new(file);
+        let mut header = vec![0; HEADER.len()];
+        reader.read_exact(&mut header)?;
+        if header != HEADER {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "not a checkpoint written by this version of Stateright",
+            ));
+        }
+        Ok(Some(CheckpointReader { reader, len }))
+    }
+
+    /// Reads a `usize` written by [`CheckpointWriter::usize`], failing with
+    /// [`io::ErrorKind::InvalidData`] if it does not fit in a `usize`.
+    pub(crate) fn usize(&mut self) -> io::Result<usize> {
+        let mut bytes = Vec::with_capacity(10);
+        loop {
+            let mut byte = [0];
+            self.reader.read_exact(&mut byte)?;
+            bytes.push(byte[0]);
+            // Longer encodings are rejected by `read_usize`.
+            if byte[0] < 0x80 || bytes.len() > 10 {
+                return disk::read_usize(&mut bytes.as_slice());
+            }
+        }
+    }
+
+    pub(crate) fn u64(&mut self) -> io::Result<u64> {
+        let mut bytes = [0; 8];
+        self.reader.read_exact(&mut bytes)?;
+        Ok(u64::from_le_bytes(bytes))
+    }
+
+    /// Reads a byte string written by [`CheckpointWriter::bytes`], failing with
+    /// [`io::ErrorKind::InvalidData`] if it would be longer than the rest of the file.
+    pub(crate) fn bytes(&mut self) -> io::Result<Vec<u8>> {
+        let len = self.usize()?;
+        let remaining = self.len.saturating_sub(self.reader.stream_position()?);
+        if len as u64 > remaining {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("a byte string of {len} bytes exceeds the {remaining} bytes remaining"),
+            ));
+        }
+        let mut bytes = vec![0; len];
+        self.reader.read_exact(&mut bytes)?;
+        Ok(bytes)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn temp_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("stateright-{}-{name}", std::process::id()))
+    }
+
+    #[test]
+    fn reads_what_was_written() {
+        let path = temp_path("checkpoint-round-trip");
+        let mut writer = CheckpointWriter::create(&path).unwrap();
+        writer.usize(usize::MAX).unwrap();
+        writer.u64(7).unwrap();
+        writer.bytes(b"abc").unwrap();
+        writer.finish().unwrap();
+
+        let mut reader = CheckpointReader::open(&path).unwrap().unwrap();
+        assert_eq!(reader.usize().unwrap(), usize::MAX);
+        assert_eq!(reader.u64().unwrap(), 7);
+        assert_eq!(reader.bytes().unwrap(), b"abc");
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn rejects_invalid_data() {
+        let path = temp_path("checkpoint-invalid");
+        let read = |bytes: &[u8], read: fn(&mut CheckpointReader) -> io::Result<()>| {
+            std::fs::write(&path, [HEADER, bytes].concat()).unwrap();
+            let mut reader = CheckpointReader::open(&path).unwrap().unwrap();
+            read(&mut reader).unwrap_err().kind()
+        };
+        // Too many continuation bytes for a `usize`.
+        assert_eq!(
+            read(&[0xff; 11], |r| r.usize().map(drop)),
+            io::ErrorKind::InvalidData
+        );
+        // A length beyond the end of the file, which is not allocated.
+        let mut bytes = Vec::new();
+        disk::write_usize(&mut bytes, usize::MAX);
+        bytes.extend(b"abc");
+        assert_eq!(
+            read(&bytes, |r| r.bytes().map(drop)),
+            io::ErrorKind::InvalidData
+        );
+        std::fs::remove_file(&path).unwrap();
+    }
+}
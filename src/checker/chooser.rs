@@ -0,0 +1,387 @@
+//! Private module for selective re-export.
+
+use crate::checker::{Chooser, StableRng};
+use crate::{fingerprint, Fingerprint, Model};
+use dashmap::DashMap;
+use nohash_hasher::NoHashHasher;
+use std::collections::HashMap;
+use std::hash::{BuildHasherDefault, Hash};
+use std::sync::Arc;
+
+/// A chooser for swarm testing, which disables a random subset of the kinds of actions for each
+/// run. Runs that omit some kinds of actions entirely tend to reach states that uniform choices
+/// rarely do, such as those that require many steps of one kind without interruption by another.
+///
+/// The kind of each action is determined by a function, such as [`std::mem::discriminant`] to
+/// treat each variant of an enum as a kind. Each kind is enabled with a probability of one half
+/// the first time a run encounters it. If every available action is disabled, then the run
+/// chooses among all of them.
+///
+/// # Example
+///
+/// ```
+/// # use stateright::*;
+/// # fn check<M: Model + Send + Sync + 'static>(model: M)
+/// # where M::State: Clone + std::hash::Hash + PartialEq + Send + Sync, M::Action: Clone + PartialEq + Send,
+/// # {
+/// model
+///     .checker()
+///     .spawn_simulation(0, SwarmChooser::new(std::mem::discriminant))
+///     .join();
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct SwarmChooser<F> {
+    kind: F,
+}
+
+/// The state of a [`SwarmChooser`] during a run.
+pub struct SwarmChooserState<K> {
+    rng: StableRng,
+    enabled_kinds: HashMap<K, bool>,
+    enabled_indices: Vec<usize>,
+}
+
+impl<F> SwarmChooser<F> {
+    /// Creates a chooser that determines the kind of each action via `kind`.
+    pub fn new(kind: F) -> Self {
+        Self { kind }
+    }
+}
+
+impl<M, F, K> Chooser<M> for SwarmChooser<F>
+where
+    M: Model,
+    F: Fn(&M::Action) -> K + Send + Clone + 'static,
+    K: Eq + Hash,
+{
+    type State = SwarmChooserState<K>;
+
+    fn new_state(&self, seed: u64) -> Self::State {
+        SwarmChooserState {
+            rng: StableRng::new(seed),
+            enabled_kinds: HashMap::new(),
+            enabled_indices: Vec::new(),
+        }
+    }
+
+    fn choose_initial_state(&self, state: &mut Self::State, initial_states: &[M::State]) -> usize {
+        state.rng.below(initial_states.len())
+    }
+
+    fn choose_action(
+        &self,
+        state: &mut Self::State,
+        _current_state: &M::State,
+        actions: &[M::Action],
+    ) -> usize {
+        let SwarmChooserState {
+            rng,
+            enabled_kinds,
+            enabled_indices,
+        } = state;
+        enabled_indices.clear();
+        for (i, action) in actions.iter().enumerate() {
+            let is_enabled = *enabled_kinds
+                .entry((self.kind)(action))
+                .or_insert_with(|| rng.below(2) == 0);
+            if is_enabled {
+                enabled_indices.push(i);
+            }
+        }
+        if enabled_indices.is_empty() {
+            rng.below(actions.len())
+        } else {
+            enabled_indices[rng.below(enabled_indices.len())]
+        }
+    }
+}
+
+/// A chooser that favors states that have been visited the fewest times, which steers runs
+/// toward parts of the state space that earlier runs have not covered.
+///
+/// The chooser evaluates the next state for each available action and chooses uniformly among
+/// those whose next state has been chosen the fewest times by any run on any thread. Actions
+/// without a next state are only chosen if every action lacks one. As choices depend on the runs
+/// that came before, a seed from [`Checker::discovery_seed`] does not replay a run on its own.
+///
+/// # Example
+///
+/// ```
+/// # use stateright::*;
+/// # fn check<M: Model + Clone + Send + Sync + 'static>(model: M)
+/// # where M::State: Clone + std::hash::Hash + PartialEq + Send + Sync, M::Action: Clone + PartialEq + Send,
+/// # {
+/// model
+///     .clone()
+///     .checker()
+///     .spawn_simulation(0, CoverageChooser::new(model))
+///     .join();
+/// # }
+/// ```
+///
+/// [`Checker::discovery_seed`]: crate::Checker::discovery_seed
+pub struct CoverageChooser<M> {
+    model: Arc<M>,
+    visits: Arc<DashMap<Fingerprint, usize, BuildHasherDefault<NoHashHasher<u64>>>>,
+}
+
+/// The state of a [`CoverageChooser`] during a run.
+pub struct CoverageChooserState {
+    rng: StableRng,
+    least_visited: Vec<(usize, Option<Fingerprint>)>,
+}
+
+impl<M> CoverageChooser<M> {
+    /// Creates a chooser for a model, which should be the same as the one being checked.
+    pub fn new(model: M) -> Self {
+        Self {
+            model: Arc::new(model),
+            visits: Default::default(),
+        }
+    }
+}
+
+// Not derived, as the model need not be `Clone`. Clones share visit counts.
+impl<M> Clone for CoverageChooser<M> {
+    fn clone(&self) -> Self {
+        Self {
+            model: Arc::clone(&self.model),
+            visits: Arc::clone(&self.visits),
+        }
+    }
+}
+
+impl<M> CoverageChooser<M> {
+    /// Chooses uniformly among the least visited of the candidates, which are an index and the
+    /// fingerprint of the corresponding state (if any), and records a visit to the chosen state.
+    fn choose_least_visited(
+        &self,
+        state: &mut CoverageChooserState,
+        candidates: impl Iterator<Item = (usize, Option<Fingerprint>)>,
+    ) -> usize {
+        let CoverageChooserState { rng, least_visited } = state;
+        least_visited.clear();
+        let mut least_visits = usize::MAX;
+        for (i, fingerprint) in candidates {
+            let visits = match fingerprint {
+                Some(fingerprint) => self.visits.get(&fingerprint).map_or(0, |v| *v),
+                None => usize::MAX,
+            };
+            if visits < least_visits {
+                least_visits = visits;
+                least_visited.clear();
+            }
+            if visits == least_visits {
+                least_visited.push((i, fingerprint));
+            }
+        }
+        let (i, fingerprint) = least_visited[rng.below(least_visited.len())];
+        if let Some(fingerprint) = fingerprint {
+            *self.visits.entry(fingerprint).or_default() += 1;
+        }
+        i
+    }
+}
+
+impl<M> Chooser<M> for CoverageChooser<M>
+where
+    M: Model + Send + Sync + 'static,
+    M::State: Hash,
+    M::Action: Clone,
+{
+    type State = CoverageChooserState;
+
+    fn new_state(&self, seed: u64) -> Self::State {
+        CoverageChooserState {
+            rng: StableRng::new(seed),
+            least_visited: Vec::new(),
+        }
+    }
+
+    fn choose_initial_state(&self, state: &mut Self::State, initial_states: &[M::State]) -> usize {
+        let candidates = initial_states
+            .iter()
+            .enumerate()
+            .map(|(i, s)| (i, Some(fingerprint(s))));
+        self.choose_least_visited(state, candidates)
+    }
+
+    fn choose_action(
+        &self,
+        state: &mut Self::State,
+        current_state: &M::State,
+        actions: &[M::Action],
+    ) -> usize {
+        let candidates = actions.iter().enumerate().map(|(i, action)| {
+            let next_state = self.model.next_state(current_state, action.clone());
+            (i, next_state.map(|s| fingerprint(&s)))
+        });
+        self.choose_least_visited(state, candidates)
+    }
+}
+
+/// A chooser that chooses each action with a probability proportional to a weight, such as to
+/// make failures or timeouts rarer than message deliveries.
+///
+/// The weight of each action is determined by a function of the current state and the action.
+/// Actions with a weight of zero are only chosen if every action has a weight of zero, in which
+/// case the choice is uniform.
+///
+/// # Example
+///
+/// ```
+/// # use stateright::*;
+/// # use stateright::actor::*;
+/// # type Msg = (); type Timer = (); type Random = ();
+/// # fn check<M: Model<Action = ActorModelAction<Msg, Timer, Random>> + Send + Sync + 'static>(
+/// #     model: M,
+/// # ) where
+/// #     M::State: Clone + std::hash::Hash + PartialEq + Send + Sync,
+/// #     M::Action: Clone + PartialEq + Send,
+/// # {
+/// model
+///     .checker()
+///     .spawn_simulation(
+///         0,
+///         WeightedChooser::new(|_: &_, action: &ActorModelAction<_, _, _>| match action {
+///             ActorModelAction::Deliver { .. } => 10,
+///             _ => 1,
+///         }),
+///     )
+///     .join();
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct WeightedChooser<F> {
+    weight: F,
+}
+
+/// The state of a [`WeightedChooser`] during a run.
+pub struct WeightedChooserState {
+    rng: StableRng,
+    weights: Vec<usize>,
+}
+
+impl<F> WeightedChooser<F> {
+    /// Creates a chooser that determines the weight of each action via `weight`.
+    pub fn new(weight: F) -> Self {
+        Self { weight }
+    }
+}
+
+impl<M, F> Chooser<M> for WeightedChooser<F>
+where
+    M: Model,
+    F: Fn(&M::State, &M::Action) -> usize + Send + Clone + 'static,
+{
+    type State = WeightedChooserState;
+
+    fn new_state(&self, seed: u64) -> Self::State {
+        WeightedChooserState {
+            rng: StableRng::new(seed),
+            weights: Vec::new(),
+        }
+    }
+
+    fn choose_initial_state(&self, state: &mut Self::State, initial_states: &[M::State]) -> usize {
+        state.rng.below(initial_states.len())
+    }
+
+    fn choose_action(
+        &self,
+        state: &mut Self::State,
+        current_state: &M::State,
+        actions: &[M::Action],
+    ) -> usize {
+        let WeightedChooserState { rng, weights } = state;
+        weights.clear();
+        weights.extend(actions.iter().map(|a| (self.weight)(current_state, a)));
+        let total = weights
+            .iter()
+            .try_fold(0usize, |total, &w| total.checked_add(w));
+        match total.expect("The total weight of the actions overflows") {
+            0 => rng.below(actions.len()),
+            total => {
+                let mut remaining = rng.below(total);
+                weights
+                    .iter()
+                    .position(|&w| {
+                        if remaining < w {
+                            return true;
+                        }
+                        remaining -= w;
+                        false
+                    })
+                    .unwrap()
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_util::linear_equation_solver::*;
+    use crate::{Checker, Path};
+    use std::collections::HashSet;
+
+    fn choices<C: Chooser<LinearEquation>>(chooser: &C, seed: u64) -> HashSet<usize> {
+        let mut state = chooser.new_state(seed);
+        let actions = [Guess::IncreaseX, Guess::IncreaseY];
+        (0..32)
+            .map(|_| chooser.choose_action(&mut state, &(0, 0), &actions))
+            .collect()
+    }
+
+    #[test]
+    fn swarm_chooser_disables_kinds_per_run() {
+        let chooser = SwarmChooser::new(std::mem::discriminant);
+        let runs: Vec<_> = (0..16).map(|seed| choices(&chooser, seed)).collect();
+        // Runs with only one kind enabled choose only that kind. Otherwise both are chosen.
+        assert!(runs.contains(&HashSet::from([0])));
+        assert!(runs.contains(&HashSet::from([1])));
+        assert!(runs.contains(&HashSet::from([0, 1])));
+        assert!(runs.iter().all(|run| !run.is_empty()));
+    }
+
+    #[test]
+    fn coverage_chooser_favors_unvisited_states() {
+        let chooser = CoverageChooser::new(LinearEquation { a: 2, b: 10, c: 14 });
+        let mut state = Chooser::<LinearEquation>::new_state(&chooser, 0);
+        let actions = [Guess::IncreaseX, Guess::IncreaseY];
+        let first = chooser.choose_action(&mut state, &(0, 0), &actions);
+        // Clones share visits, as each thread has its own.
+        let chooser = chooser.clone();
+        let mut state = Chooser::<LinearEquation>::new_state(&chooser, 0);
+        let second = chooser.choose_action(&mut state, &(0, 0), &actions);
+        assert_ne!(first, second);
+
+        let checker = LinearEquation { a: 2, b: 10, c: 14 }
+            .checker()
+            .spawn_simulation(0, chooser)
+            .join();
+        checker.assert_properties();
+    }
+
+    #[test]
+    fn weighted_chooser_chooses_by_weight() {
+        let only_y = WeightedChooser::new(|_: &_, guess: &Guess| match guess {
+            Guess::IncreaseX => 0,
+            Guess::IncreaseY => 1,
+        });
+        assert_eq!(choices(&only_y, 0), HashSet::from([1]));
+        let none = WeightedChooser::new(|_: &_, _: &Guess| 0);
+        assert_eq!(choices(&none, 0), HashSet::from([0, 1]));
+
+        let checker = LinearEquation { a: 2, b: 10, c: 14 }
+            .checker()
+            .spawn_simulation(0, only_y)
+            .join();
+        assert_eq!(
+            checker.discovery("solvable").map(Path::into_actions),
+            // (10*27) % 256 == 14
+            Some(vec![Guess::IncreaseY; 27])
+        );
+    }
+}
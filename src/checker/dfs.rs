@@ -1,52 +1,181 @@
 //! Private module for selective re-export.
 
-use crate::checker::{Checker, EventuallyBits, Expectation, Path};
+use crate::checker::bitstate::Bitstate;
+use crate::checker::{
+    check_transitions, liveness, should_stop, Checker, EventuallyBits, Path, Predicate, SleepSet,
+    SleepSets, Symmetry,
+};
 use crate::job_market::JobBroker;
-use crate::{fingerprint, CheckerBuilder, CheckerVisitor, Fingerprint, Model, Property};
+use crate::{
+    fingerprint, CheckerBuilder, CheckerVisitor, Fingerprint, HasDiscoveries, Model, Property,
+};
+use dashmap::mapref::entry::Entry;
 use dashmap::{DashMap, DashSet};
 use nohash_hasher::NoHashHasher;
+use parking_lot::Mutex;
 use std::collections::{HashMap, VecDeque};
 use std::hash::{BuildHasherDefault, Hash};
 use std::num::NonZeroUsize;
-use std::sync::atomic::{AtomicUsize, Ordering};
+use std::panic::AssertUnwindSafe;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 use std::sync::Arc;
 use std::thread::JoinHandle;
 use std::time::SystemTime;
 
-// While this file is currently quite similar to bfs.rs, a refactoring to lift shared
-// behavior is being postponed until DPOR is implemented.
-
 pub(crate) struct DfsChecker<M: Model> {
     // Immutable state.
     model: Arc<M>,
     handles: Vec<std::thread::JoinHandle<()>>,
+    shrink_discoveries: bool,
 
     // Mutable state.
-    job_broker: JobBroker<Job<M::State>>,
+    /// `None` when deepening iteratively, as each depth then has its own broker.
+    job_broker: Option<JobBroker<Job<M::State, M::Action>>>,
     state_count: Arc<AtomicUsize>,
     max_depth: Arc<AtomicUsize>,
-    generated: Arc<DashSet<Fingerprint, BuildHasherDefault<NoHashHasher<u64>>>>,
+    generated: Arc<Generated>,
     discoveries: Arc<DashMap<&'static str, Vec<usize>>>,
+    loop_starts: Arc<DashMap<&'static str, Option<usize>>>,
+    liveness_done: Arc<AtomicBool>,
+    completed_depths: Arc<Mutex<Vec<CompletedDepth>>>,
+    deepening_done: Arc<AtomicBool>,
+}
+type Job<State, Action> = (
+    State,
+    Vec<usize>,
+    EventuallyBits,
+    NonZeroUsize,
+    SleepSet<Action>,
+);
+
+/// A depth that an iterative deepening check has completed (see
+/// [`CheckerBuilder::iterative_deepening`]).
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CompletedDepth {
+    /// Every state at this depth or less has been checked, where initial states are at depth 1
+    /// as with [`Checker::max_depth`].
+    pub depth: usize,
+    /// The number of states generated while checking this depth, including repeats.
+    pub state_count: usize,
+    /// The number of unique states generated while checking this depth.
+    pub unique_state_count: usize,
+    /// The names of the properties with discoveries once this depth was checked.
+    pub discoveries: Vec<&'static str>,
+}
+
+/// Fingerprints of the generated states.
+enum Generated {
+    Fingerprints(DashSet<Fingerprint, BuildHasherDefault<NoHashHasher<u64>>>),
+    /// An iterative deepening check also records the depth at which each state was generated, as
+    /// a state that was first generated at the depth limit must be explored again if it is
+    /// reached at a shallower depth.
+    Depths(DashMap<Fingerprint, NonZeroUsize, BuildHasherDefault<NoHashHasher<u64>>>),
+    Bitstate(Bitstate),
+}
+
+impl Generated {
+    /// Records a state generated at a depth, returning whether it should be explored.
+    fn insert(&self, fingerprint: Fingerprint, depth: NonZeroUsize) -> bool {
+        match self {
+            Generated::Fingerprints(fingerprints) => fingerprints.insert(fingerprint),
+            Generated::Depths(depths) => match depths.entry(fingerprint) {
+                Entry::Occupied(mut entry) if depth < *entry.get() => {
+                    entry.insert(depth);
+                    true
+                }
+                Entry::Occupied(_) => false,
+                Entry::Vacant(entry) => {
+                    entry.insert(depth);
+                    true
+                }
+            },
+            Generated::Bitstate(bitstate) => bitstate.insert(fingerprint),
+        }
+    }
+
+    fn len(&self) -> usize {
+        match self {
+            Generated::Fingerprints(fingerprints) => fingerprints.len(),
+            Generated::Depths(depths) => depths.len(),
+            Generated::Bitstate(bitstate) => bitstate.len(),
+        }
+    }
+
+    fn clear(&self) {
+        match self {
+            Generated::Fingerprints(fingerprints) => fingerprints.clear(),
+            Generated::Depths(depths) => depths.clear(),
+            Generated::Bitstate(bitstate) => bitstate.clear(),
+        }
+    }
+
+    fn omission_probability(&self) -> Option<f64> {
+        match self {
+            Generated::Bitstate(bitstate) => Some(bitstate.omission_probability()),
+            _ => None,
+        }
+    }
+}
+
+/// What the threads checking the model share.
+struct Workers<M: Model> {
+    model: Arc<M>,
+    thread_count: usize,
+    symmetry: Option<Symmetry<M>>,
+    target_state_count: Option<NonZeroUsize>,
+    visitor: Arc<Option<Box<dyn CheckerVisitor<M> + Send + Sync>>>,
+    finish_when: Arc<HasDiscoveries>,
+    properties: Arc<Vec<Property<M>>>,
+    state_count: Arc<AtomicUsize>,
+    max_depth: Arc<AtomicUsize>,
+    generated: Arc<Generated>,
+    discoveries: Arc<DashMap<&'static str, Vec<usize>>>,
+    sleep_sets: Option<Arc<SleepSets<M::Action>>>,
+}
+
+impl<M: Model> Workers<M> {
+    fn is_finished(&self) -> bool {
+        self.finish_when.matches(
+            &self.discoveries.iter().map(|r| *r.key()).collect(),
+            &self.properties,
+        )
+    }
+
+    fn is_at_target_state_count(&self) -> bool {
+        self.target_state_count
+            .is_some_and(|c| c.get() <= self.state_count.load(Ordering::Relaxed))
+    }
 }
-type Job<State> = (State, Vec<usize>, EventuallyBits, NonZeroUsize);
 
 impl<M> DfsChecker<M>
 where
     M: Model + Send + Sync + 'static,
     M::State: Hash + Send + 'static,
+    M::Action: Send + 'static,
 {
     pub(crate) fn spawn(options: CheckerBuilder<M>) -> Self
     where
         M::State: Clone + PartialEq,
         M::Action: Clone + PartialEq,
     {
+        // The pass that detects cycles holds every reachable state, which bitstate hashing avoids.
+        assert!(
+            options.bitstate.is_none() || !options.detect_cycles,
+            "CheckerBuilder::bitstate does not support CheckerBuilder::detect_cycles"
+        );
         let model = Arc::new(options.model);
+        let shrink_discoveries = options.shrink_discoveries;
         let symmetry = options.symmetry;
         let target_state_count = options.target_state_count;
         let target_max_depth = options.target_max_depth;
-        let thread_count = options.thread_count;
-        let visitor = Arc::new(options.visitor);
-        let finish_when = Arc::new(options.finish_when);
+        let iterative_deepening = options.iterative_deepening;
+        // Which thread reaches a state first determines the path to it, so a deterministic check
+        // uses only one.
+        let thread_count = if options.deterministic {
+            1
+        } else {
+            options.thread_count
+        };
         let properties = Arc::new(model.properties());
 
         let init_states: Vec<_> = model
@@ -54,54 +183,233 @@ where
             .into_iter()
             .filter(|s| model.within_boundary(s))
             .collect();
-        let state_count = Arc::new(AtomicUsize::new(init_states.len()));
-        let max_depth = Arc::new(AtomicUsize::new(0));
-        let generated = Arc::new({
-            let generated = DashSet::default();
-            for s in &init_states {
-                if let Some(representative) = symmetry {
-                    generated.insert(fingerprint(&representative(s)));
-                } else {
-                    generated.insert(fingerprint(s));
-                }
-            }
-            generated
+        let generated = Arc::new(if let Some(bitstate) = options.bitstate {
+            Generated::Bitstate(Bitstate::new(bitstate))
+        } else if iterative_deepening {
+            Generated::Depths(DashMap::default())
+        } else {
+            Generated::Fingerprints(DashSet::default())
         });
         let ebits = {
             let mut ebits = EventuallyBits::new();
             for (i, p) in properties.iter().enumerate() {
-                if let Property {
-                    expectation: Expectation::Eventually,
-                    ..
-                } = p
-                {
+                if let Predicate::Eventually(_) = p.predicate {
                     ebits.insert(i);
                 }
             }
             ebits
         };
+        let workers = Workers {
+            model: Arc::clone(&model),
+            thread_count,
+            symmetry: symmetry.clone(),
+            target_state_count,
+            visitor: Arc::new(options.visitor),
+            finish_when: Arc::new(options.finish_when),
+            properties: Arc::clone(&properties),
+            state_count: Arc::new(AtomicUsize::new(0)),
+            max_depth: Arc::new(AtomicUsize::new(0)),
+            generated: Arc::clone(&generated),
+            discoveries: Arc::new(DashMap::default()),
+            // Sleep sets assume that states are only merged when equal, which symmetry reduction
+            // violates, and that a state is explored fully once visited, which depth limits
+            // violate when deepening.
+            sleep_sets: (options.partial_order_reduction
+                && symmetry.is_none()
+                && !iterative_deepening)
+                .then(|| Arc::new(SleepSets::new())),
+        };
+        let state_count = Arc::clone(&workers.state_count);
+        let max_depth = Arc::clone(&workers.max_depth);
+        let discoveries = Arc::clone(&workers.discoveries);
+        let finish_when = Arc::clone(&workers.finish_when);
+
+        let close_at = options.timeout.map(|t| SystemTime::now() + t);
+        let completed_depths = Arc::new(Mutex::new(Vec::new()));
+        let deepening_done = Arc::new(AtomicBool::new(!iterative_deepening));
+        let (mut handles, job_broker) = if iterative_deepening {
+            let completed_depths = Arc::clone(&completed_depths);
+            let deepening_done = Arc::clone(&deepening_done);
+            let handle = std::thread::Builder::new()
+                .name("deepening".to_owned())
+                .spawn(move || {
+                    let result = std::panic::catch_unwind(AssertUnwindSafe(|| {
+                        Self::deepen(
+                            &workers,
+                            init_states,
+                            ebits,
+                            target_max_depth,
+                            close_at,
+                            &completed_depths,
+                        )
+                    }));
+                    deepening_done.store(true, Ordering::Relaxed);
+                    if let Err(panic) = result {
+                        std::panic::resume_unwind(panic);
+                    }
+                })
+                .expect("Failed to spawn a thread");
+            (vec![handle], None)
+        } else {
+            let mut job_broker = JobBroker::new(thread_count, close_at);
+            let handles = Self::spawn_workers(
+                &workers,
+                &mut job_broker,
+                init_states,
+                ebits,
+                target_max_depth,
+            );
+            (handles, Some(job_broker))
+        };
+
+        // Paths ending in a cycle are not identified above, so if enabled, `eventually` and
+        // temporal logic properties are further checked once exploration completes.
+        let has_liveness = liveness::is_enabled(options.detect_cycles, &properties);
+        let loop_starts = Arc::new(DashMap::default());
+        let liveness_done = Arc::new(AtomicBool::new(!has_liveness));
+        if has_liveness {
+            let should_stop = should_stop(
+                close_at,
+                target_state_count,
+                finish_when,
+                Arc::clone(&properties),
+                Arc::clone(&state_count),
+                Arc::clone(&discoveries),
+            );
+            handles = vec![liveness::spawn_after(
+                handles,
+                Arc::clone(&model),
+                Arc::clone(&discoveries),
+                Arc::clone(&loop_starts),
+                target_max_depth,
+                options.fairness,
+                Arc::clone(&liveness_done),
+                should_stop,
+            )];
+        }
+        DfsChecker {
+            model,
+            handles,
+            shrink_discoveries,
+            job_broker,
+            state_count,
+            max_depth,
+            generated,
+            discoveries,
+            loop_starts,
+            liveness_done,
+            completed_depths,
+            deepening_done,
+        }
+    }
+
+    /// Checks every state within depth 1, then within depth 2, and so on, until the check
+    /// finishes, every state has been checked, or the depth reaches `target_max_depth`.
+    fn deepen(
+        workers: &Workers<M>,
+        init_states: Vec<M::State>,
+        ebits: EventuallyBits,
+        target_max_depth: Option<NonZeroUsize>,
+        close_at: Option<SystemTime>,
+        completed_depths: &Mutex<Vec<CompletedDepth>>,
+    ) where
+        M::State: Clone + PartialEq,
+        M::Action: Clone + PartialEq,
+    {
+        // A depth limit skips states at that depth, as with a DFS that has a target max depth.
+        let mut limit = NonZeroUsize::new(2).unwrap();
+        let mut previous_unique_state_count = 0;
+        while target_max_depth.is_none_or(|target| limit <= target) {
+            log::debug!("Checking depth {}.", limit.get() - 1);
+            let initial_state_count = workers.state_count.load(Ordering::Relaxed);
+            workers.generated.clear();
+            let mut job_broker = JobBroker::new(workers.thread_count, close_at);
+            let handles = Self::spawn_workers(
+                workers,
+                &mut job_broker,
+                init_states.clone(),
+                ebits.clone(),
+                Some(limit),
+            );
+            for h in handles {
+                if let Err(panic) = h.join() {
+                    std::panic::resume_unwind(panic);
+                }
+            }
+            // The depth is incomplete if the check stopped early.
+            if close_at.is_some_and(|t| t <= SystemTime::now())
+                || workers.is_finished()
+                || workers.is_at_target_state_count()
+            {
+                log::debug!("Stopped checking at depth {}.", limit.get() - 1);
+                return;
+            }
+
+            let unique_state_count = workers.generated.len();
+            let mut discoveries: Vec<_> = workers.discoveries.iter().map(|r| *r.key()).collect();
+            discoveries.sort_unstable();
+            completed_depths.lock().push(CompletedDepth {
+                depth: limit.get() - 1,
+                state_count: workers.state_count.load(Ordering::Relaxed) - initial_state_count,
+                unique_state_count,
+                discoveries,
+            });
+            // States at the limit are generated but not checked, so no new states means that no
+            // deeper states remain.
+            if unique_state_count == previous_unique_state_count {
+                return;
+            }
+            previous_unique_state_count = unique_state_count;
+            limit = limit.checked_add(1).unwrap();
+        }
+    }
+
+    fn spawn_workers(
+        workers: &Workers<M>,
+        job_broker: &mut JobBroker<Job<M::State, M::Action>>,
+        init_states: Vec<M::State>,
+        ebits: EventuallyBits,
+        target_max_depth: Option<NonZeroUsize>,
+    ) -> Vec<JoinHandle<()>>
+    where
+        M::State: Clone + PartialEq,
+        M::Action: Clone + PartialEq,
+    {
+        let init_depth = NonZeroUsize::new(1).unwrap();
+        workers
+            .state_count
+            .fetch_add(init_states.len(), Ordering::Relaxed);
+        for s in &init_states {
+            if let Some(representative) = &workers.symmetry {
+                workers
+                    .generated
+                    .insert(fingerprint(&representative(s)), init_depth);
+            } else {
+                workers.generated.insert(fingerprint(s), init_depth);
+            }
+        }
         let pending: VecDeque<_> = init_states
             .into_iter()
             .enumerate()
-            .map(|(i, s)| (s, vec![i], ebits.clone(), NonZeroUsize::new(1).unwrap()))
+            .map(|(i, s)| (s, vec![i], ebits.clone(), init_depth, SleepSet::default()))
             .collect();
-        let discoveries = Arc::new(DashMap::default());
-        let mut handles = Vec::new();
-
-        let close_at = options.timeout.map(|t| SystemTime::now() + t);
-        let mut job_broker = JobBroker::new(thread_count, close_at);
         job_broker.push(pending);
 
+        let thread_count = workers.thread_count;
+        let mut handles = Vec::new();
         for t in 0..thread_count {
-            let model = Arc::clone(&model);
-            let visitor = Arc::clone(&visitor);
-            let finish_when = Arc::clone(&finish_when);
-            let properties = Arc::clone(&properties);
+            let model = Arc::clone(&workers.model);
+            let visitor = Arc::clone(&workers.visitor);
+            let finish_when = Arc::clone(&workers.finish_when);
+            let properties = Arc::clone(&workers.properties);
             let mut job_broker = job_broker.clone();
-            let state_count = Arc::clone(&state_count);
-            let max_depth = Arc::clone(&max_depth);
-            let generated = Arc::clone(&generated);
-            let discoveries = Arc::clone(&discoveries);
+            let state_count = Arc::clone(&workers.state_count);
+            let max_depth = Arc::clone(&workers.max_depth);
+            let generated = Arc::clone(&workers.generated);
+            let discoveries = Arc::clone(&workers.discoveries);
+            let sleep_sets = workers.sleep_sets.clone();
+            let symmetry = workers.symmetry.clone();
+            let target_state_count = workers.target_state_count;
             handles.push(
                 std::thread::Builder::new()
                     .name(format!("checker-{t}"))
@@ -135,7 +443,8 @@ where
                                 1500,
                                 target_max_depth,
                                 &max_depth,
-                                symmetry,
+                                symmetry.as_ref(),
+                                sleep_sets.as_deref(),
                             );
                             if finish_when.matches(
                                 &discoveries.iter().map(|r| *r.key()).collect(),
@@ -168,15 +477,7 @@ where
                     .expect("Failed to spawn a thread"),
             );
         }
-        DfsChecker {
-            model,
-            handles,
-            job_broker,
-            state_count,
-            max_depth,
-            generated,
-            discoveries,
-        }
+        handles
     }
 
     #[allow(clippy::too_many_arguments)]
@@ -184,19 +485,25 @@ where
     fn check_block(
         model: &M,
         state_count: &AtomicUsize,
-        generated: &DashSet<Fingerprint, BuildHasherDefault<NoHashHasher<u64>>>,
-        pending: &mut VecDeque<Job<M::State>>,
+        generated: &Generated,
+        pending: &mut VecDeque<Job<M::State, M::Action>>,
         discoveries: &DashMap<&'static str, Vec<usize>>,
         visitor: &Option<Box<dyn CheckerVisitor<M> + Send + Sync>>,
         mut max_count: usize,
         target_max_depth: Option<NonZeroUsize>,
         global_max_depth: &AtomicUsize,
-        symmetry: Option<fn(&M::State) -> M::State>,
+        symmetry: Option<&Symmetry<M>>,
+        sleep_sets: Option<&SleepSets<M::Action>>,
     ) where
         M::State: Clone + PartialEq,
         M::Action: Clone + PartialEq,
     {
         let properties = model.properties();
+        let has_transitions = properties
+            .iter()
+            .any(|p| matches!(p.predicate, Predicate::Transition(_)));
+        // Transition properties must see every step, so they preclude reduction.
+        let sleep_sets = sleep_sets.filter(|_| !has_transitions);
 
         let mut current_max_depth = global_max_depth.load(Ordering::Relaxed);
         let mut actions = Vec::new();
@@ -208,7 +515,8 @@ where
             max_count -= 1;
 
             // Done if none pending.
-            let (state, action_indices, mut ebits, max_depth) = match pending.pop_back() {
+            let (state, action_indices, mut ebits, max_depth, sleep_set) = match pending.pop_back()
+            {
                 None => return,
                 Some(pair) => pair,
             };
@@ -240,17 +548,13 @@ where
             let mut is_awaiting_discoveries = false;
             for (i, property) in properties.iter().enumerate() {
                 if discoveries.contains_key(property.name)
-                    && (property.expectation == Expectation::Eventually)
+                    && matches!(property.predicate, Predicate::Eventually(_))
                     && !ebits.contains(i)
                 {
                     continue;
                 }
-                match property {
-                    Property {
-                        expectation: Expectation::Always,
-                        condition: always,
-                        ..
-                    } => {
+                match &property.predicate {
+                    Predicate::Always(always) => {
                         if !always(model, &state) {
                             // Races other threads, but that's fine.
                             discoveries.insert(property.name, action_indices.clone());
@@ -258,11 +562,7 @@ where
                             is_awaiting_discoveries = true;
                         }
                     }
-                    Property {
-                        expectation: Expectation::Sometimes,
-                        condition: sometimes,
-                        ..
-                    } => {
+                    Predicate::Sometimes(sometimes) => {
                         if sometimes(model, &state) {
                             // Races other threads, but that's fine.
                             discoveries.insert(property.name, action_indices.clone());
@@ -270,11 +570,7 @@ where
                             is_awaiting_discoveries = true;
                         }
                     }
-                    Property {
-                        expectation: Expectation::Eventually,
-                        condition: eventually,
-                        ..
-                    } => {
+                    Predicate::Eventually(eventually) => {
                         // The checker early exits after finding discoveries for every property,
                         // and "eventually" property discoveries are only identified at terminal
                         // states, so if we are here it means we are still awaiting a corresponding
@@ -285,6 +581,13 @@ where
                             ebits.remove(i);
                         }
                     }
+                    Predicate::Transition(_) | Predicate::Ltl(_) => {
+                        // Transitions are checked as steps are taken, and temporal logic
+                        // properties are checked separately.
+                        if !discoveries.contains_key(property.name) {
+                            is_awaiting_discoveries = true;
+                        }
+                    }
                 }
             }
             if !is_awaiting_discoveries {
@@ -293,8 +596,16 @@ where
 
             // Otherwise enqueue newly generated states (with related metadata).
             let mut is_terminal = true;
+            let mut taken = Vec::new(); // Steps of processes, for sleep sets (see sleep.rs).
             model.actions(&state, &mut actions);
             for (action_idx, action) in actions.drain(..).enumerate() {
+                // Skip if the step commutes with one taken before reaching this state.
+                if sleep_set.skips(&action) {
+                    is_terminal = false;
+                    continue;
+                }
+                let transition_action = has_transitions.then(|| action.clone());
+                let reduced_action = sleep_sets.is_some().then(|| action.clone());
                 let next_state = match model.next_state(&state, action) {
                     None => continue,
                     Some(next_state) => next_state,
@@ -305,40 +616,65 @@ where
                     continue;
                 }
                 state_count.fetch_add(1, Ordering::Relaxed);
+                let next_sleep_set = reduced_action
+                    .and_then(|action| {
+                        let process = model.step_process(&state, &action, &next_state)?;
+                        let next_sleep_set = sleep_set.after(&taken, process);
+                        taken.push((action, process));
+                        Some(next_sleep_set)
+                    })
+                    .unwrap_or_default();
+
+                if let Some(action) = &transition_action {
+                    check_transitions(
+                        model,
+                        &properties,
+                        discoveries,
+                        &state,
+                        action,
+                        &next_state,
+                        &action_indices,
+                        action_idx,
+                        false,
+                    );
+                }
 
                 // Skip if already generated.
                 //
-                // FIXME: we should really include ebits in the fingerprint here --
-                // it is possible to arrive at a DAG join with two different ebits
-                // values, and subsequently treat the fact that some eventually
-                // property held on the path leading to the first visit as meaning
-                // that it holds in the path leading to the second visit -- another
-                // possible false-negative.
-                let _next_fingerprint = if let Some(representative) = symmetry {
+                // Note that ebits are not part of the fingerprint, so a DAG join reached with
+                // two different ebits values is only explored with the first. The liveness pass
+                // that follows exploration compensates for the resulting false negatives.
+                let next_depth = NonZeroUsize::new(max_depth.get() + 1).unwrap();
+                let next_sleep_set = if let Some(representative) = symmetry {
                     let representative_fingerprint = fingerprint(&representative(&next_state));
-                    if !generated.insert(representative_fingerprint) {
+                    if !generated.insert(representative_fingerprint, next_depth) {
                         is_terminal = false;
                         continue;
                     }
                     // IMPORTANT: continue the path with the pre-canonicalized state/fingerprint to
                     // avoid jumping to another part of the state space for which there may not be
                     // a path extension from the previously collected path.
-                    fingerprint(&next_state)
+                    next_sleep_set
                 } else {
                     let next_fingerprint = fingerprint(&next_state);
-                    if !generated.insert(next_fingerprint) {
-                        // FIXME: arriving at an already-known state may be a loop (in which case it
-                        // could, in a fancier implementation, be considered a terminal state for
-                        // purposes of eventually-property checking) but it might also be a join in
-                        // a DAG, which makes it non-terminal. These cases can be disambiguated (at
-                        // some cost), but for now we just _don't_ treat them as terminal, and tell
-                        // users they need to explicitly ensure model path-acyclicality when they're
-                        // using eventually properties (using a boundary or empty actions or
-                        // whatever).
+                    let next_sleep_set = match sleep_sets {
+                        Some(sleep_sets) => {
+                            sleep_sets.visit(next_fingerprint, next_sleep_set, || {
+                                generated.insert(next_fingerprint, next_depth)
+                            })
+                        }
+                        None => generated
+                            .insert(next_fingerprint, next_depth)
+                            .then_some(next_sleep_set),
+                    };
+                    let Some(next_sleep_set) = next_sleep_set else {
+                        // Arriving at an already-known state may be a loop or a join in a DAG. It
+                        // is not treated as terminal here, and loops are instead identified by the
+                        // liveness pass that follows exploration (see liveness.rs).
                         is_terminal = false;
                         continue;
-                    }
-                    next_fingerprint
+                    };
+                    next_sleep_set
                 };
 
                 // Otherwise further checking is applicable.
@@ -352,7 +688,8 @@ where
                     next_state,
                     next_action_indices,
                     ebits.clone(),
-                    NonZeroUsize::new(max_depth.get() + 1).unwrap(),
+                    next_depth,
+                    next_sleep_set,
                 ));
             }
             if is_terminal {
@@ -381,7 +718,16 @@ where
     }
 
     fn unique_state_count(&self) -> usize {
-        self.generated.len()
+        // Deepening starts each depth over, so until a depth generates more states than the
+        // previous one, that depth's count is reported instead. A depth is recorded before its
+        // states are cleared, so reading them first never misses both.
+        let generated = self.generated.len();
+        let completed = self
+            .completed_depths
+            .lock()
+            .last()
+            .map_or(0, |depth| depth.unique_state_count);
+        generated.max(completed)
     }
 
     fn max_depth(&self) -> usize {
@@ -396,20 +742,38 @@ where
         self.discoveries
             .iter()
             .map(|mapref| {
-                (
-                    <&'static str>::clone(mapref.key()),
-                    Path::from_action_indices(self.model(), VecDeque::from(mapref.value().clone())),
-                )
+                let name = <&'static str>::clone(mapref.key());
+                let path =
+                    Path::from_action_indices(self.model(), VecDeque::from(mapref.value().clone()));
+                match self.loop_starts.get(name) {
+                    Some(loop_start) => (name, path.with_loop_start(*loop_start)),
+                    None => (name, path),
+                }
             })
             .collect()
     }
 
+    fn omission_probability(&self) -> Option<f64> {
+        self.generated.omission_probability()
+    }
+
+    fn completed_depths(&self) -> Vec<CompletedDepth> {
+        self.completed_depths.lock().clone()
+    }
+
     fn handles(&mut self) -> Vec<JoinHandle<()>> {
         std::mem::take(&mut self.handles)
     }
 
+    fn shrinks_discoveries(&self) -> bool {
+        self.shrink_discoveries
+    }
+
     fn is_done(&self) -> bool {
-        self.job_broker.is_closed() || self.discoveries.len() == self.model.properties().len()
+        (self.job_broker.as_ref().is_none_or(JobBroker::is_closed)
+            && self.deepening_done.load(Ordering::Relaxed)
+            && self.liveness_done.load(Ordering::Relaxed))
+            || self.discoveries.len() == self.model.properties().len()
     }
 }
 
@@ -584,6 +948,176 @@ mod test {
         assert_eq!(checker.unique_state_count(), 6);
     }
 
+    #[test]
+    fn can_check_deterministically() {
+        let check = |thread_count| {
+            let checker = LinearEquation { a: 2, b: 10, c: 14 }
+                .checker()
+                .deterministic()
+                .threads(thread_count)
+                .spawn_dfs()
+                .join();
+            (
+                checker.discovery("solvable").map(Path::into_actions),
+                checker.state_count(),
+                checker.unique_state_count(),
+                checker.max_depth(),
+            )
+        };
+        let expected = check(1);
+        for _ in 0..3 {
+            assert_eq!(check(4), expected);
+        }
+    }
+
+    #[test]
+    fn can_deepen_iteratively() {
+        let depths = |checker: &dyn Checker<_>| -> Vec<_> {
+            checker
+                .completed_depths()
+                .into_iter()
+                .map(|d| (d.depth, d.state_count, d.unique_state_count))
+                .collect()
+        };
+
+        // The solution is 3 steps away, so it is found while checking depth 4.
+        let checker = LinearEquation { a: 2, b: 10, c: 14 }
+            .checker()
+            .iterative_deepening()
+            .spawn_dfs()
+            .join();
+        assert_eq!(
+            checker.discovery("solvable").unwrap().into_actions().len(),
+            3
+        );
+        assert_eq!(depths(&checker), vec![(1, 3, 3), (2, 7, 6), (3, 13, 10)]);
+        assert!(checker
+            .completed_depths()
+            .iter()
+            .all(|d| d.discoveries.is_empty()));
+
+        // The last depth checks the same states as a DFS with the same target.
+        let checker = LinearEquation { a: 2, b: 4, c: 7 }
+            .checker()
+            .iterative_deepening()
+            .target_max_depth(4)
+            .threads(2)
+            .spawn_dfs()
+            .join();
+        assert!(checker.is_done());
+        assert_eq!(depths(&checker), vec![(1, 3, 3), (2, 7, 6), (3, 13, 10)]);
+        let bounded = LinearEquation { a: 2, b: 4, c: 7 }
+            .checker()
+            .target_max_depth(4)
+            .spawn_dfs()
+            .join();
+        assert_eq!(bounded.unique_state_count(), 10);
+        assert_eq!(bounded.completed_depths(), Vec::new());
+
+        // The unique state count does not drop as each depth starts over.
+        let checker = LinearEquation { a: 2, b: 4, c: 7 }
+            .checker()
+            .iterative_deepening()
+            .target_max_depth(40)
+            .spawn_dfs();
+        let mut unique_state_count = 0;
+        while !checker.is_done() {
+            assert!(unique_state_count <= checker.unique_state_count());
+            unique_state_count = checker.unique_state_count();
+        }
+        let checker = checker.join();
+        assert_eq!(
+            checker.unique_state_count(),
+            checker
+                .completed_depths()
+                .last()
+                .unwrap()
+                .unique_state_count
+        );
+    }
+
+    #[test]
+    fn deepens_until_no_deeper_states_remain() {
+        use crate::test_util::dgraph::DGraph;
+        // DFS reaches 4 via 2 and 3 first, at which point it is at the limit while checking depth
+        // 3, but it must still be explored via 1 to reach 5.
+        let checker = DGraph::with_property(Property::always("true", |_, _| true))
+            .with_path(vec![0, 1, 4, 5])
+            .with_path(vec![0, 2, 3, 4])
+            .checker()
+            .iterative_deepening()
+            .spawn_dfs()
+            .join();
+        assert!(checker.is_done());
+        let depths: Vec<_> = checker
+            .completed_depths()
+            .into_iter()
+            .map(|d| (d.depth, d.unique_state_count))
+            .collect();
+        assert_eq!(depths, vec![(1, 3), (2, 5), (3, 6), (4, 6)]);
+
+        let mut written = Vec::new();
+        let _ = DGraph::with_property(Property::sometimes("reaches 2", |_, s| *s == 2))
+            .with_path(vec![0, 1, 2])
+            .checker()
+            .iterative_deepening()
+            .spawn_dfs()
+            .report(&mut crate::report::WriteReporter::new(&mut written));
+        let written = String::from_utf8(written).unwrap();
+        assert!(written.contains(
+            "Completed depth 1. states=2, unique=2, discoveries=[]\n\
+             Completed depth 2. states=3, unique=3, discoveries=[]\n"
+        ));
+    }
+
+    #[test]
+    fn can_check_with_bitstate() {
+        let check = |checker: CheckerBuilder<LinearEquation>| {
+            checker.target_max_depth(20).spawn_dfs().join()
+        };
+        let exact = check(LinearEquation { a: 2, b: 4, c: 7 }.checker());
+        assert_eq!(exact.omission_probability(), None);
+
+        // Few collisions with plenty of bits...
+        let checker = check(
+            LinearEquation { a: 2, b: 4, c: 7 }
+                .checker()
+                .bitstate(1 << 16, 3),
+        );
+        assert_eq!(checker.unique_state_count(), exact.unique_state_count());
+        assert_eq!(checker.state_count(), exact.state_count());
+        assert!(checker.omission_probability().unwrap() < 1e-6);
+
+        // ... but many states are omitted without.
+        let checker = check(
+            LinearEquation { a: 2, b: 4, c: 7 }
+                .checker()
+                .bitstate(64, 1),
+        );
+        assert!(checker.unique_state_count() < exact.unique_state_count());
+        assert!(checker.omission_probability().unwrap() > 0.5);
+
+        let mut written = Vec::new();
+        LinearEquation { a: 2, b: 10, c: 14 }
+            .checker()
+            .bitstate(1 << 16, 3)
+            .spawn_dfs()
+            .report(&mut crate::report::WriteReporter::new(&mut written))
+            .assert_discovery("solvable", vec![Guess::IncreaseY; 27]);
+        let written = String::from_utf8(written).unwrap();
+        assert!(written.contains("\nOmission probability: "), "{written}");
+    }
+
+    #[test]
+    #[should_panic(expected = "does not support CheckerBuilder::detect_cycles")]
+    fn cannot_detect_cycles_with_bitstate() {
+        let _ = LinearEquation { a: 2, b: 10, c: 14 }
+            .checker()
+            .bitstate(1 << 16, 3)
+            .detect_cycles()
+            .spawn_dfs();
+    }
+
     // test that the checker shuts down all threads properly after a checker thread encounters a
     // panic in the model execution.
     #[test]
@@ -595,4 +1129,15 @@ mod test {
             .spawn_dfs()
             .join();
     }
+
+    #[test]
+    #[should_panic]
+    fn handles_panics_gracefully_when_deepening() {
+        crate::test_util::panicker::Panicker
+            .checker()
+            .iterative_deepening()
+            .threads(2)
+            .spawn_dfs()
+            .join();
+    }
 }
@@ -0,0 +1,586 @@
+//! Private module for selective re-export.
+
+use serde::ser::{self, Serialize};
+use std::fmt::{self, Display, Formatter};
+
+/// A value as serialized via serde, in a form that can be compared structurally. Primitives are
+/// rendered as text, as are map keys, so maps need not have string keys (unlike JSON).
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) enum Tree {
+    Leaf(String),
+    Seq(Vec<Tree>),
+    /// A map or struct.
+    Map(Vec<(String, Tree)>),
+    /// An enum variant with data, such as a tuple variant.
+    Variant(&'static str, Box<Tree>),
+}
+
+impl Tree {
+    pub(crate) fn new(value: &(impl Serialize + ?Sized)) -> Result<Tree, Error> {
+        value.serialize(TreeSerializer)
+    }
+}
+
+impl Display for Tree {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Tree::Leaf(text) => write!(f, "{text}"),
+            Tree::Seq(items) => {
+                write!(f, "[")?;
+                for (i, item) in items.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{item}")?;
+                }
+                write!(f, "]")
+            }
+            Tree::Map(entries) => {
+                write!(f, "{{")?;
+                for (i, (key, value)) in entries.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{key}: {value}")?;
+                }
+                write!(f, "}}")
+            }
+            Tree::Variant(variant, tree) => match &**tree {
+                Tree::Map(_) => write!(f, "{variant} {tree}"),
+                Tree::Seq(_) => {
+                    // Tuple fields, without the brackets of a sequence.
+                    let text = tree.to_string();
+                    write!(f, "{variant}({})", &text[1..text.len() - 1])
+                }
+                _ => write!(f, "{variant}({tree})"),
+            },
+        }
+    }
+}
+
+/// How a value changed between two states, with unchanged parts collapsed.
+#[derive(Clone, Debug, Eq, PartialEq, serde::Serialize)]
+#[serde(rename_all = "snake_case", tag = "kind")]
+pub(crate) enum Diff {
+    Unchanged,
+    /// Differing primitives, or values of different shapes.
+    Changed {
+        before: String,
+        after: String,
+    },
+    /// A value only present in the later state, such as a newly sent message.
+    Added {
+        after: String,
+    },
+    /// A value only present in the earlier state, such as a delivered message.
+    Removed {
+        before: String,
+    },
+    /// Sequences or maps compared entry by entry.
+    Entries {
+        entries: Vec<Entry>,
+    },
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, serde::Serialize)]
+pub(crate) struct Entry {
+    /// The field name, map key, or sequence index, which is absent for the elements of
+    /// sequences that are compared as multisets.
+    pub(crate) key: Option<String>,
+    pub(crate) diff: Diff,
+}
+
+impl Diff {
+    /// Compares two values. Maps are compared by key. Sequences of the same length are compared
+    /// by index, unless comparing them as multisets finds fewer differences, which is the case
+    /// for sets that serialize in an arbitrary order, such as an unordered network.
+    pub(crate) fn between(before: &Tree, after: &Tree) -> Diff {
+        if before == after {
+            return Diff::Unchanged;
+        }
+        match (before, after) {
+            (Tree::Variant(before_variant, before), Tree::Variant(after_variant, after))
+                if before_variant == after_variant =>
+            {
+                Diff::Entries {
+                    entries: vec![Entry {
+                        key: Some(before_variant.to_string()),
+                        diff: Diff::between(before, after),
+                    }],
+                }
+            }
+            (Tree::Map(before_entries), Tree::Map(after_entries)) => {
+                let mut entries = Vec::new();
+                for (key, before) in before_entries {
+                    let diff = match after_entries.iter().find(|(k, _)| k == key) {
+                        Some((_, after)) => Diff::between(before, after),
+                        None => Diff::Removed {
+                            before: before.to_string(),
+                        },
+                    };
+                    entries.push(Entry {
+                        key: Some(key.clone()),
+                        diff,
+                    });
+                }
+                for (key, after) in after_entries {
+                    if !before_entries.iter().any(|(k, _)| k == key) {
+                        entries.push(Entry {
+                            key: Some(key.clone()),
+                            diff: Diff::Added {
+                                after: after.to_string(),
+                            },
+                        });
+                    }
+                }
+                Diff::Entries { entries }
+            }
+            (Tree::Seq(before_items), Tree::Seq(after_items)) => {
+                let (entries, removed_count) = multiset_entries(before_items, after_items);
+                if before_items.len() == after_items.len() {
+                    let changed_count = before_items
+                        .iter()
+                        .zip(after_items)
+                        .filter(|(before, after)| before != after)
+                        .count();
+                    if changed_count <= removed_count {
+                        let entries = before_items
+                            .iter()
+                            .zip(after_items)
+                            .enumerate()
+                            .map(|(i, (before, after))| Entry {
+                                key: Some(i.to_string()),
+                                diff: Diff::between(before, after),
+                            })
+                            .collect();
+                        return Diff::Entries { entries };
+                    }
+                }
+                Diff::Entries { entries }
+            }
+            _ => Diff::Changed {
+                before: before.to_string(),
+                after: after.to_string(),
+            },
+        }
+    }
+}
+
+/// Compares sequences as multisets, returning the entries and the number of removed elements.
+fn multiset_entries(before_items: &[Tree], after_items: &[Tree]) -> (Vec<Entry>, usize) {
+    let mut matched = vec![false; after_items.len()];
+    let mut entries = Vec::new();
+    let mut removed_count = 0;
+    for before in before_items {
+        let position = after_items
+            .iter()
+            .enumerate()
+            .position(|(i, after)| !matched[i] && after == before);
+        let diff = match position {
+            Some(i) => {
+                matched[i] = true;
+                Diff::Unchanged
+            }
+            None => {
+                removed_count += 1;
+                Diff::Removed {
+                    before: before.to_string(),
+                }
+            }
+        };
+        entries.push(Entry { key: None, diff });
+    }
+    for (after, _) in after_items.iter().zip(matched).filter(|(_, m)| !m) {
+        entries.push(Entry {
+            key: None,
+            diff: Diff::Added {
+                after: after.to_string(),
+            },
+        });
+    }
+    (entries, removed_count)
+}
+
+#[derive(Debug)]
+pub(crate) struct Error(String);
+
+impl Display for Error {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl ser::Error for Error {
+    fn custom<T: Display>(msg: T) -> Self {
+        Error(msg.to_string())
+    }
+}
+
+struct TreeSerializer;
+
+/// Builds a [`Tree::Seq`], wrapped in a [`Tree::Variant`] for a tuple variant.
+struct SeqBuilder {
+    items: Vec<Tree>,
+    variant: Option<&'static str>,
+}
+
+/// Builds a [`Tree::Map`], wrapped in a [`Tree::Variant`] for a struct variant.
+struct MapBuilder {
+    entries: Vec<(String, Tree)>,
+    key: Option<String>,
+    variant: Option<&'static str>,
+}
+
+fn wrap(tree: Tree, variant: Option<&'static str>) -> Tree {
+    match variant {
+        Some(variant) => Tree::Variant(variant, Box::new(tree)),
+        None => tree,
+    }
+}
+
+macro_rules! serialize_display {
+    ($($method:ident: $type:ty),*) => {
+        $(
+            fn $method(self, v: $type) -> Result<Tree, Error> {
+                Ok(Tree::Leaf(v.to_string()))
+            }
+        )*
+    };
+}
+
+impl ser::Serializer for TreeSerializer {
+    type Ok = Tree;
+    type Error = Error;
+    type SerializeSeq = SeqBuilder;
+    type SerializeTuple = SeqBuilder;
+    type SerializeTupleStruct = SeqBuilder;
+    type SerializeTupleVariant = SeqBuilder;
+    type SerializeMap = MapBuilder;
+    type SerializeStruct = MapBuilder;
+    type SerializeStructVariant = MapBuilder;
+
+    serialize_display!(
+        serialize_bool: bool,
+        serialize_i8: i8,
+        serialize_i16: i16,
+        serialize_i32: i32,
+        serialize_i64: i64,
+        serialize_i128: i128,
+        serialize_u8: u8,
+        serialize_u16: u16,
+        serialize_u32: u32,
+        serialize_u64: u64,
+        serialize_u128: u128,
+        serialize_f32: f32,
+        serialize_f64: f64
+    );
+
+    fn serialize_char(self, v: char) -> Result<Tree, Error> {
+        Ok(Tree::Leaf(format!("{v:?}")))
+    }
+
+    fn serialize_str(self, v: &str) -> Result<Tree, Error> {
+        Ok(Tree::Leaf(format!("{v:?}")))
+    }
+
+    fn serialize_bytes(self, v: &[u8]) -> Result<Tree, Error> {
+        Ok(Tree::Seq(
+            v.iter().map(|b| Tree::Leaf(b.to_string())).collect(),
+        ))
+    }
+
+    fn serialize_none(self) -> Result<Tree, Error> {
+        Ok(Tree::Leaf("None".to_string()))
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result<Tree, Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<Tree, Error> {
+        Ok(Tree::Leaf("()".to_string()))
+    }
+
+    fn serialize_unit_struct(self, name: &'static str) -> Result<Tree, Error> {
+        Ok(Tree::Leaf(name.to_string()))
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+    ) -> Result<Tree, Error> {
+        Ok(Tree::Leaf(variant.to_string()))
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> Result<Tree, Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<Tree, Error> {
+        Ok(wrap(value.serialize(self)?, Some(variant)))
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> Result<SeqBuilder, Error> {
+        Ok(SeqBuilder {
+            items: Vec::with_capacity(len.unwrap_or(0)),
+            variant: None,
+        })
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<SeqBuilder, Error> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_struct(self, _name: &'static str, len: usize) -> Result<SeqBuilder, Error> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<SeqBuilder, Error> {
+        Ok(SeqBuilder {
+            items: Vec::with_capacity(len),
+            variant: Some(variant),
+        })
+    }
+
+    fn serialize_map(self, len: Option<usize>) -> Result<MapBuilder, Error> {
+        Ok(MapBuilder {
+            entries: Vec::with_capacity(len.unwrap_or(0)),
+            key: None,
+            variant: None,
+        })
+    }
+
+    fn serialize_struct(self, _name: &'static str, len: usize) -> Result<MapBuilder, Error> {
+        self.serialize_map(Some(len))
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<MapBuilder, Error> {
+        Ok(MapBuilder {
+            entries: Vec::with_capacity(len),
+            key: None,
+            variant: Some(variant),
+        })
+    }
+}
+
+macro_rules! impl_seq_builder {
+    ($($trait:ident::$method:ident),*) => {
+        $(
+            impl ser::$trait for SeqBuilder {
+                type Ok = Tree;
+                type Error = Error;
+
+                fn $method<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+                    self.items.push(Tree::new(value)?);
+                    Ok(())
+                }
+
+                fn end(self) -> Result<Tree, Error> {
+                    Ok(wrap(Tree::Seq(self.items), self.variant))
+                }
+            }
+        )*
+    };
+}
+
+impl_seq_builder!(
+    SerializeSeq::serialize_element,
+    SerializeTuple::serialize_element,
+    SerializeTupleStruct::serialize_field,
+    SerializeTupleVariant::serialize_field
+);
+
+impl ser::SerializeMap for MapBuilder {
+    type Ok = Tree;
+    type Error = Error;
+
+    fn serialize_key<T: Serialize + ?Sized>(&mut self, key: &T) -> Result<(), Error> {
+        self.key = Some(Tree::new(key)?.to_string());
+        Ok(())
+    }
+
+    fn serialize_value<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        let key = self
+            .key
+            .take()
+            .ok_or_else(|| Error("Map value serialized without a key".to_string()))?;
+        self.entries.push((key, Tree::new(value)?));
+        Ok(())
+    }
+
+    fn end(self) -> Result<Tree, Error> {
+        Ok(wrap(Tree::Map(self.entries), self.variant))
+    }
+}
+
+macro_rules! impl_struct_builder {
+    ($($trait:ident),*) => {
+        $(
+            impl ser::$trait for MapBuilder {
+                type Ok = Tree;
+                type Error = Error;
+
+                fn serialize_field<T: Serialize + ?Sized>(
+                    &mut self,
+                    key: &'static str,
+                    value: &T,
+                ) -> Result<(), Error> {
+                    self.entries.push((key.to_string(), Tree::new(value)?));
+                    Ok(())
+                }
+
+                fn end(self) -> Result<Tree, Error> {
+                    Ok(wrap(Tree::Map(self.entries), self.variant))
+                }
+            }
+        )*
+    };
+}
+
+impl_struct_builder!(SerializeStruct, SerializeStructVariant);
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::collections::BTreeMap;
+
+    #[derive(serde::Serialize)]
+    enum Msg {
+        Ping(u8),
+        Pong { id: u8 },
+        Stop,
+    }
+
+    #[test]
+    fn can_build_tree() {
+        let mut map = BTreeMap::new();
+        map.insert((1, 2), vec![Msg::Ping(3), Msg::Pong { id: 4 }, Msg::Stop]);
+        let tree = Tree::new(&(map, Some("text"), None::<u8>, 'c')).unwrap();
+        assert_eq!(
+            tree.to_string(),
+            "[{[1, 2]: [Ping(3), Pong {id: 4}, Stop]}, \"text\", None, 'c']"
+        );
+    }
+
+    #[test]
+    fn collapses_unchanged_values() {
+        let before = Tree::new(&(vec![1, 2, 3], "same")).unwrap();
+        let after = Tree::new(&(vec![1, 5, 3], "same")).unwrap();
+        assert_eq!(
+            serde_json::to_value(Diff::between(&before, &after)).unwrap(),
+            serde_json::json!({
+                "kind": "entries",
+                "entries": [
+                    {
+                        "key": "0",
+                        "diff": {
+                            "kind": "entries",
+                            "entries": [
+                                {"key": "0", "diff": {"kind": "unchanged"}},
+                                {"key": "1", "diff": {"kind": "changed", "before": "2", "after": "5"}},
+                                {"key": "2", "diff": {"kind": "unchanged"}},
+                            ],
+                        },
+                    },
+                    {"key": "1", "diff": {"kind": "unchanged"}},
+                ],
+            })
+        );
+        assert_eq!(Diff::between(&before, &before), Diff::Unchanged);
+    }
+
+    #[test]
+    fn compares_sequences_as_multisets_when_reordered() {
+        // Such as a set of envelopes where one message was delivered and another sent.
+        let before = Tree::new(&vec![1, 2, 3]).unwrap();
+        let after = Tree::new(&vec![0, 1, 2]).unwrap();
+        let unchanged = Entry {
+            key: None,
+            diff: Diff::Unchanged,
+        };
+        assert_eq!(
+            Diff::between(&before, &after),
+            Diff::Entries {
+                entries: vec![
+                    unchanged.clone(),
+                    unchanged,
+                    Entry {
+                        key: None,
+                        diff: Diff::Removed { before: "3".into() },
+                    },
+                    Entry {
+                        key: None,
+                        diff: Diff::Added { after: "0".into() },
+                    },
+                ],
+            }
+        );
+    }
+
+    #[test]
+    fn compares_maps_by_key() {
+        let mut before = BTreeMap::new();
+        before.insert("a", 1);
+        before.insert("b", 2);
+        let mut after = before.clone();
+        after.remove("a");
+        after.insert("c", 3);
+        assert_eq!(
+            Diff::between(&Tree::new(&before).unwrap(), &Tree::new(&after).unwrap()),
+            Diff::Entries {
+                entries: vec![
+                    Entry {
+                        key: Some("\"a\"".into()),
+                        diff: Diff::Removed { before: "1".into() },
+                    },
+                    Entry {
+                        key: Some("\"b\"".into()),
+                        diff: Diff::Unchanged,
+                    },
+                    Entry {
+                        key: Some("\"c\"".into()),
+                        diff: Diff::Added { after: "3".into() },
+                    },
+                ],
+            }
+        );
+
+        // A different enum variant is a change rather than a removal and an addition.
+        assert_eq!(
+            Diff::between(
+                &Tree::new(&Msg::Ping(1)).unwrap(),
+                &Tree::new(&Msg::Pong { id: 1 }).unwrap()
+            ),
+            Diff::Changed {
+                before: "Ping(1)".into(),
+                after: "Pong {id: 1}".into(),
+            }
+        );
+    }
+}
@@ -0,0 +1,518 @@
+//! Private module for selective re-export.
+
+use crate::checker::bitstate::{Bitstate, BitstateOptions};
+use crate::Fingerprint;
+use dashmap::DashSet;
+use nohash_hasher::NoHashHasher;
+use parking_lot::RwLock;
+use std::collections::VecDeque;
+use std::fs::File;
+use std::hash::BuildHasherDefault;
+use std::io::{BufReader, BufWriter, Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+/// Where and when a checker moves state to disk. See [`CheckerBuilder::spill_to_disk`].
+///
+/// [`CheckerBuilder::spill_to_disk`]: crate::CheckerBuilder::spill_to_disk
+#[derive(Clone, Debug)]
+pub(crate) struct DiskOptions {
+    pub(crate) dir: PathBuf,
+    pub(crate) memory_limit: usize,
+}
+
+/// A uniquely named directory for the files of one checker, which is removed once dropped.
+pub(crate) struct ScratchDir(PathBuf);
+
+impl ScratchDir {
+    pub(crate) fn new(parent: &Path) -> Arc<Self> {
+        static NEXT_ID: AtomicUsize = AtomicUsize::new(0);
+        let path = parent.join(format!(
+            "stateright-{}-{}",
+            std::process::id(),
+            NEXT_ID.fetch_add(1, Ordering::Relaxed)
+        ));
+        std::fs::create_dir_all(&path).expect("Failed to create a directory for spilled state");
+        Arc::new(ScratchDir(path))
+    }
+
+    fn file(&self, name: &str) -> PathBuf {
+        self.0.join(name)
+    }
+}
+
+impl Drop for ScratchDir {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.0);
+    }
+}
+
+type MemoryFingerprintSet = DashSet<Fingerprint, BuildHasherDefault<NoHashHasher<u64>>>;
+
+/// The fingerprints of the states generated by a checker, which are kept in memory unless the
+/// checker spills to disk.
+pub(crate) enum FingerprintSet {
+    Memory(MemoryFingerprintSet),
+    Disk(DiskFingerprintSet),
+}
+
+impl FingerprintSet {
+    pub(crate) fn new(dir: Option<(&Arc<ScratchDir>, &DiskOptions)>) -> Self {
+        match dir {
+            None => FingerprintSet::Memory(Default::default()),
+            Some((dir, options)) => FingerprintSet::Disk(DiskFingerprintSet::new(
+                Arc::clone(dir),
+                options.memory_limit,
+            )),
+        }
+    }
+
+    /// Adds a fingerprint, returning `true` if it was not already present.
+    pub(crate) fn insert(&self, fingerprint: Fingerprint) -> bool {
+        match self {
+            FingerprintSet::Memory(set) => set.insert(fingerprint),
+            FingerprintSet::Disk(set) => set.insert(fingerprint),
+        }
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        match self {
+            FingerprintSet::Memory(set) => set.len(),
+            FingerprintSet::Disk(set) => set.len(),
+        }
+    }
+
+    /// Visits every fingerprint, in no particular order.
+    pub(crate) fn for_each(&self, mut f: impl FnMut(Fingerprint)) {
+        match self {
+            FingerprintSet::Memory(set) => set.iter().for_each(|fp| f(*fp)),
+            FingerprintSet::Disk(set) => set.for_each(f),
+        }
+    }
+}
+
+/// The number of fingerprints in each page of a [`SortedRun`].
+const PAGE_LEN: usize = 512;
+
+/// The number of runs in a tier that are merged into one run in the next tier.
+const TIER_FANOUT: usize = 4;
+
+/// The bits of a run's filter per fingerprint, and the bits that each fingerprint sets, for which
+/// about 1% of lookups of a fingerprint that is not in the run read from disk anyway.
+const FILTER_BITS_PER_FINGERPRINT: usize = 10;
+const FILTER_HASH_COUNT: usize = 4;
+
+/// A set of fingerprints that keeps at most a fixed number in memory. Once that limit is reached,
+/// the fingerprints in memory are written to a new sorted run on disk, as in a log-structured
+/// merge tree. See [`SortedRuns`].
+pub(crate) struct DiskFingerprintSet {
+    memory: MemoryFingerprintSet,
+    memory_limit: usize,
+    // Holding a read lock prevents the fingerprints in memory from being moved to disk, so a
+    // fingerprint is never in both.
+    disk: RwLock<SortedRuns>,
+}
+
+/// Disjoint sorted files of fingerprints. Each flush adds a run to the first tier, and once a tier
+/// holds [`TIER_FANOUT`] runs they are merged into one run in the next tier, so each fingerprint
+/// is rewritten a logarithmic number of times and a lookup checks a logarithmic number of runs.
+struct SortedRuns {
+    dir: Arc<ScratchDir>,
+    next_id: usize,
+    /// Ordered by tier, from the last tier to the first.
+    runs: Vec<SortedRun>,
+}
+
+struct SortedRun {
+    path: PathBuf,
+    file: File,
+    tier: usize,
+    len: usize,
+    /// The first fingerprint of each page, so a lookup reads at most one page.
+    page_starts: Vec<u64>,
+    /// Includes the fingerprints of the run, so most lookups of other fingerprints skip the disk.
+    filter: Bitstate,
+}
+
+impl DiskFingerprintSet {
+    pub(crate) fn new(dir: Arc<ScratchDir>, memory_limit: usize) -> Self {
+        DiskFingerprintSet {
+            memory: Default::default(),
+            memory_limit: memory_limit.max(1),
+            disk: RwLock::new(SortedRuns {
+                dir,
+                next_id: 0,
+                runs: Vec::new(),
+            }),
+        }
+    }
+
+    /// Adds a fingerprint, returning `true` if it was not already present.
+    pub(crate) fn insert(&self, fingerprint: Fingerprint) -> bool {
+        let is_new = {
+            let disk = self.disk.read();
+            !self.memory.contains(&fingerprint)
+                && !disk.contains(fingerprint)
+                && self.memory.insert(fingerprint)
+        };
+        if is_new && self.memory.len() >= self.memory_limit {
+            let mut disk = self.disk.write();
+            // Another thread may have flushed while this one awaited the lock.
+            if self.memory.len() >= self.memory_limit {
+                let mut fingerprints: Vec<u64> = self.memory.iter().map(|fp| fp.get()).collect();
+                fingerprints.sort_unstable();
+                disk.flush(&fingerprints);
+                self.memory.clear();
+            }
+        }
+        is_new
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        let disk = self.disk.read();
+        disk.runs.iter().map(|run| run.len).sum::<usize>() + self.memory.len()
+    }
+
+    fn for_each(&self, mut f: impl FnMut(Fingerprint)) {
+        let disk = self.disk.read();
+        for run in &disk.runs {
+            run.reader()
+                .for_each(|fingerprint| f(Fingerprint::new(fingerprint).unwrap()));
+        }
+        self.memory.iter().for_each(|fp| f(*fp));
+    }
+}
+
+impl SortedRuns {
+    fn contains(&self, fingerprint: Fingerprint) -> bool {
+        self.runs.iter().any(|run| run.contains(fingerprint))
+    }
+
+    /// Adds a run of the specified sorted fingerprints, merging tiers that become full.
+    fn flush(&mut self, fingerprints: &[u64]) {
+        let run = self.write(0, fingerprints.len(), fingerprints.iter().copied());
+        self.runs.push(run);
+        loop {
+            let tier = self.runs.last().unwrap().tier;
+            let tier_len = self
+                .runs
+                .iter()
+                .rev()
+                .take_while(|run| run.tier == tier)
+                .count();
+            if tier_len < TIER_FANOUT {
+                return;
+            }
+            let runs = self.runs.split_off(self.runs.len() - tier_len);
+            let len = runs.iter().map(|run| run.len).sum();
+            let mut readers: Vec<_> = runs.iter().map(|run| run.reader().peekable()).collect();
+            // The runs are disjoint, so repeatedly taking the least next fingerprint merges them.
+            let merged = std::iter::from_fn(|| {
+                readers
+                    .iter_mut()
+                    .filter_map(|reader| Some((*reader.peek()?, reader)))
+                    .min_by_key(|(fingerprint, _)| *fingerprint)
+                    .and_then(|(_, reader)| reader.next())
+            });
+            let run = self.write(tier + 1, len, merged);
+            drop(readers);
+            for run in runs {
+                let _ = std::fs::remove_file(&run.path);
+            }
+            self.runs.push(run);
+        }
+    }
+
+    fn write(
+        &mut self,
+        tier: usize,
+        len: usize,
+        fingerprints: impl Iterator<Item = u64>,
+    ) -> SortedRun {
+        let path = self.dir.file(&format!("fingerprints-{}", self.next_id));
+        self.next_id += 1;
+        let mut writer =
+            BufWriter::new(File::create(&path).expect("Failed to create a fingerprint file"));
+        let filter = Bitstate::new(BitstateOptions {
+            bits: len * FILTER_BITS_PER_FINGERPRINT,
+            hash_count: FILTER_HASH_COUNT,
+        });
+        let mut page_starts = Vec::with_capacity(len.div_ceil(PAGE_LEN));
+        for (i, fingerprint) in fingerprints.enumerate() {
+            if i % PAGE_LEN == 0 {
+                page_starts.push(fingerprint);
+            }
+            filter.insert(Fingerprint::new(fingerprint).unwrap());
+            writer
+                .write_all(&fingerprint.to_le_bytes())
+                .expect("Failed to write fingerprints to disk");
+        }
+        writer
+            .flush()
+            .expect("Failed to write fingerprints to disk");
+        drop(writer);
+        SortedRun {
+            file: File::open(&path).expect("Failed to open a fingerprint file"),
+            path,
+            tier,
+            len,
+            page_starts,
+            filter,
+        }
+    }
+}
+
+impl SortedRun {
+    fn contains(&self, fingerprint: Fingerprint) -> bool {
+        if !self.filter.contains(fingerprint) {
+            return false;
+        }
+        let fingerprint = fingerprint.get();
+        let page = match self
+            .page_starts
+            .partition_point(|&start| start <= fingerprint)
+        {
+            0 => return false,
+            after => after - 1,
+        };
+        self.page(page).binary_search(&fingerprint).is_ok()
+    }
+
+    fn page(&self, page: usize) -> Vec<u64> {
+        let offset = page * PAGE_LEN;
+        let count = PAGE_LEN.min(self.len - offset);
+        let mut bytes = vec![0; count * 8];
+        read_exact_at(&self.file, &mut bytes, (offset * 8) as u64)
+            .expect("Failed to read fingerprints from disk");
+        bytes
+            .chunks_exact(8)
+            .map(|chunk| u64::from_le_bytes(chunk.try_into().unwrap()))
+            .collect()
+    }
+
+    /// Reads the fingerprints of the run in order.
+    fn reader(&self) -> impl Iterator<Item = u64> {
+        let mut reader =
+            BufReader::new(File::open(&self.path).expect("Failed to open a fingerprint file"));
+        (0..self.len).map(move |_| {
+            let mut bytes = [0; 8];
+            reader
+                .read_exact(&mut bytes)
+                .expect("Failed to read fingerprints from disk");
+            u64::from_le_bytes(bytes)
+        })
+    }
+}
+
+#[cfg(unix)]
+fn read_exact_at(file: &File, buf: &mut [u8], offset: u64) -> std::io::Result<()> {
+    std::os::unix::fs::FileExt::read_exact_at(file, buf, offset)
+}
+
+#[cfg(windows)]
+fn read_exact_at(file: &File, mut buf: &mut [u8], mut offset: u64) -> std::io::Result<()> {
+    while !buf.is_empty() {
+        match std::os::windows::fs::FileExt::seek_read(file, buf, offset)? {
+            0 => return Err(std::io::ErrorKind::UnexpectedEof.into()),
+            n => {
+                buf = &mut buf[n..];
+                offset += n as u64;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Jobs that a [`JobBroker`] writes to disk rather than holding in memory once it holds more
+/// than a fixed number. Batches are read back in the order they were written.
+///
+/// [`JobBroker`]: crate::job_market::JobBroker
+pub(crate) struct Spill<Job> {
+    dir: Arc<ScratchDir>,
+    pub(crate) memory_limit: usize,
+    #[allow(clippy::type_complexity)]
+    encode: Box<dyn Fn(&Job, &mut Vec<u8>) + Send>,
+    #[allow(clippy::type_complexity)]
+    decode: Box<dyn Fn(&mut &[u8]) -> Job + Send>,
+    batches: VecDeque<(PathBuf, usize)>,
+    next_batch: usize,
+}
+
+impl<Job> Spill<Job> {
+    pub(crate) fn new(
+        dir: Arc<ScratchDir>,
+        memory_limit: usize,
+        encode: impl Fn(&Job, &mut Vec<u8>) + Send + 'static,
+        decode: impl Fn(&mut &[u8]) -> Job + Send + 'static,
+    ) -> Self {
+        Spill {
+            dir,
+            memory_limit,
+            encode: Box::new(encode),
+            decode: Box::new(decode),
+            batches: VecDeque::new(),
+            next_batch: 0,
+        }
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.batches.is_empty()
+    }
+
+    pub(crate) fn write(&mut self, jobs: VecDeque<Job>) {
+        let bytes = self.encode(&jobs);
+        self.write_encoded(jobs.len(), bytes);
+    }
+
+    pub(crate) fn read(&mut self) -> Option<VecDeque<Job>> {
+        let (path, count) = self.batches.pop_front()?;
+        let bytes = std::fs::read(&path).expect("Failed to read jobs from disk");
+        let _ = std::fs::remove_file(&path);
+        Some(self.decode(count, &bytes))
+    }
+
+    /// Writes a batch of `count` jobs that were already encoded.
+    pub(crate) fn write_encoded(&mut self, count: usize, bytes: Vec<u8>) {
+        let path = self.dir.file(&format!("jobs-{}", self.next_batch));
+        self.next_batch += 1;
+        std::fs::write(&path, bytes).expect("Failed to write jobs to disk");
+        self.batches.push_back((path, count));
+    }
+
+    /// The encoded batches on disk, in the order they will be read.
+    pub(crate) fn encoded_batches(&self) -> impl Iterator<Item = (usize, Vec<u8>)> + '_ {
+        self.batches.iter().map(|(path, count)| {
+            let bytes = std::fs::read(path).expect("Failed to read jobs from disk");
+            (*count, bytes)
+        })
+    }
+
+    pub(crate) fn encode<'a>(&self, jobs: impl IntoIterator<Item = &'a Job>) -> Vec<u8>
+    where
+        Job: 'a,
+    {
+        let mut bytes = Vec::new();
+        for job in jobs {
+            (self.encode)(job, &mut bytes);
+        }
+        bytes
+    }
+
+    pub(crate) fn decode(&self, count: usize, mut bytes: &[u8]) -> VecDeque<Job> {
+        (0..count).map(|_| (self.decode)(&mut bytes)).collect()
+    }
+
+    pub(crate) fn clear(&mut self) {
+        for (path, _) in self.batches.drain(..) {
+            let _ = std::fs::remove_file(path);
+        }
+    }
+}
+
+/// Appends a `usize` using a variable length encoding.
+pub(crate) fn write_usize(bytes: &mut Vec<u8>, mut value: usize) {
+    while value >= 0x80 {
+        bytes.push(value as u8 | 0x80);
+        value >>= 7;
+    }
+    bytes.push(value as u8);
+}
+
+/// Consumes a `usize` written by [`write_usize`], failing if the bytes end early or encode a
+/// value that does not fit in a `usize`.
+pub(crate) fn read_usize(bytes: &mut &[u8]) -> std::io::Result<usize> {
+    let mut value = 0;
+    let mut shift = 0;
+    loop {
+        let Some((&byte, rest)) = bytes.split_first() else {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                "truncated integer",
+            ));
+        };
+        *bytes = rest;
+        let bits = (byte & 0x7f) as usize;
+        if shift >= usize::BITS || (bits << shift) >> shift != bits {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                "integer is too large",
+            ));
+        }
+        value |= bits << shift;
+        if byte < 0x80 {
+            return Ok(value);
+        }
+        shift += 7;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::num::NonZeroU64;
+
+    fn fp(value: u64) -> Fingerprint {
+        NonZeroU64::new(value).unwrap()
+    }
+
+    #[test]
+    fn disk_fingerprint_set_deduplicates_across_flushes() {
+        let dir = ScratchDir::new(&std::env::temp_dir());
+        let set = DiskFingerprintSet::new(Arc::clone(&dir), 3);
+        let values: Vec<u64> = (1..=2_000).map(|i| (i * 7_919) % 10_007 + 1).collect();
+        for &value in &values {
+            assert!(set.insert(fp(value)));
+        }
+        assert_eq!(set.len(), values.len());
+        for &value in &values {
+            assert!(!set.insert(fp(value)));
+        }
+        assert_eq!(set.len(), values.len());
+        assert!(set.insert(fp(u64::MAX)));
+    }
+
+    #[test]
+    fn disk_fingerprint_set_merges_runs_in_tiers() {
+        let dir = ScratchDir::new(&std::env::temp_dir());
+        let set = DiskFingerprintSet::new(Arc::clone(&dir), 10);
+        for value in (1..=1_000).rev() {
+            assert!(set.insert(fp(value)));
+        }
+        // 100 flushes are 1 run in tier 3, 2 in tier 2, 1 in tier 1, and none in tier 0.
+        let tiers: Vec<usize> = set.disk.read().runs.iter().map(|run| run.tier).collect();
+        assert_eq!(tiers, vec![3, 2, 2, 1]);
+        assert_eq!(std::fs::read_dir(&dir.0).unwrap().count(), 4);
+
+        let mut fingerprints = Vec::new();
+        set.for_each(|fingerprint| fingerprints.push(fingerprint.get()));
+        fingerprints.sort_unstable();
+        assert_eq!(fingerprints, (1..=1_000).collect::<Vec<_>>());
+        for value in 1..=1_000 {
+            assert!(!set.insert(fp(value)));
+        }
+        assert_eq!(set.len(), 1_000);
+    }
+
+    #[test]
+    fn spill_reads_batches_in_order_and_cleans_up() {
+        let dir = ScratchDir::new(&std::env::temp_dir());
+        let path = dir.0.clone();
+        let mut spill = Spill::new(
+            Arc::clone(&dir),
+            0,
+            |job: &usize, bytes: &mut Vec<u8>| write_usize(bytes, *job),
+            |bytes: &mut &[u8]| read_usize(bytes).unwrap(),
+        );
+        spill.write(VecDeque::from([1, 300, usize::MAX]));
+        spill.write(VecDeque::from([4]));
+        assert_eq!(spill.read(), Some(VecDeque::from([1, 300, usize::MAX])));
+        assert_eq!(spill.read(), Some(VecDeque::from([4])));
+        assert_eq!(spill.read(), None);
+        spill.write(VecDeque::from([5]));
+        drop(dir);
+        assert!(path.exists());
+        drop(spill);
+        assert!(!path.exists());
+    }
+}
@@ -0,0 +1,646 @@
+//! Private module for selective re-export.
+
+use crate::checker::bfs::{BfsChecker, Job, Level};
+use crate::checker::disk::{self, FingerprintSet};
+use crate::checker::{record_least_discovery, Checker, EventuallyBits, Path, Predicate, SleepSet};
+use crate::job_market::{JobBroker, Network};
+use crate::{fingerprint, CheckerBuilder, CheckerVisitor, Fingerprint, Model};
+use dashmap::DashMap;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::hash::Hash;
+use std::net::SocketAddr;
+use std::num::NonZeroUsize;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::thread::JoinHandle;
+use std::time::{Duration, SystemTime};
+
+// Each process owns the states whose fingerprints are congruent to its index modulo the number of
+// processes. The processes check one depth at a time, as when a BFS checks deterministically (see
+// `Levels` in bfs.rs): each checks the states it owns at the current depth, and the states of the
+// next depth are sent to their owners, which keep the least action path to each state that was
+// not generated at an earlier depth. The processes then exchange their discoveries and counts so
+// that they all agree on whether to check the next depth. Paths ending in a cycle are not
+// identified, as no process has the whole state graph.
+
+/// How long to await the other processes when connecting to them.
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(60);
+
+pub(crate) struct DistributedChecker<M: Model> {
+    // Immutable state.
+    model: Arc<M>,
+    handles: Vec<JoinHandle<()>>,
+    shrink_discoveries: bool,
+
+    // Mutable state. Counts are totals across processes as of the last depth that was checked.
+    state_count: Arc<AtomicUsize>,
+    unique_state_count: Arc<AtomicUsize>,
+    max_depth: Arc<AtomicUsize>,
+    discoveries: Arc<DashMap<&'static str, Vec<usize>>>,
+    exploration_done: Arc<AtomicBool>,
+}
+
+/// What each process sends every other after checking a depth, following the states of the next
+/// depth that the other owns.
+#[derive(Deserialize, Serialize)]
+struct Checked {
+    state_count: usize,
+    max_depth: usize,
+    timed_out: bool,
+    discoveries: Vec<(String, Vec<usize>)>,
+}
+
+/// What each process sends every other after claiming the states of the next depth.
+#[derive(Deserialize, Serialize)]
+struct Claimed {
+    unique_state_count: usize,
+    pending_count: usize,
+}
+
+impl<M> DistributedChecker<M>
+where
+    M: Model + Send + Sync + 'static,
+    M::State: Hash + Send + Sync + Clone + PartialEq + Serialize + DeserializeOwned + 'static,
+    M::Action: Send + Clone + PartialEq + 'static,
+{
+    pub(crate) fn spawn(
+        options: CheckerBuilder<M>,
+        index: usize,
+        addresses: Vec<SocketAddr>,
+    ) -> Self {
+        let model = Arc::new(options.model);
+        let shrink_discoveries = options.shrink_discoveries;
+        let target_state_count = options.target_state_count;
+        let target_max_depth = options.target_max_depth;
+        let thread_count = options.thread_count;
+        let visitor = options.visitor;
+        let finish_when = Arc::new(options.finish_when);
+        let properties = Arc::new(model.properties());
+        let close_at = options.timeout.map(|t| SystemTime::now() + t);
+        assert!(
+            !options.detect_cycles,
+            "Distributed checking does not support CheckerBuilder::detect_cycles"
+        );
+        for property in properties.iter() {
+            assert!(
+                !matches!(property.predicate, Predicate::Ltl(_)),
+                "Distributed checking does not support the temporal logic property {:?}",
+                property.name
+            );
+        }
+
+        let init_states: Vec<_> = model
+            .init_states()
+            .into_iter()
+            .filter(|s| model.within_boundary(s))
+            .collect();
+        let state_count = Arc::new(AtomicUsize::new(init_states.len()));
+        let unique_state_count = Arc::new(AtomicUsize::new(0));
+        let max_depth = Arc::new(AtomicUsize::new(0));
+        let discoveries = Arc::new(DashMap::default());
+        let exploration_done = Arc::new(AtomicBool::new(false));
+        let ebits = {
+            let mut ebits = EventuallyBits::new();
+            for (i, p) in model.properties().iter().enumerate() {
+                if let Predicate::Eventually(_) = p.predicate {
+                    ebits.insert(i);
+                }
+            }
+            ebits
+        };
+
+        let handle = {
+            let model = Arc::clone(&model);
+            let finish_when = Arc::clone(&finish_when);
+            let properties = Arc::clone(&properties);
+            let global_state_count = Arc::clone(&state_count);
+            let global_unique_state_count = Arc::clone(&unique_state_count);
+            let global_max_depth = Arc::clone(&max_depth);
+            let discoveries = Arc::clone(&discoveries);
+            let exploration_done = Arc::clone(&exploration_done);
+            std::thread::Builder::new()
+                .name(format!("distributed-{index}"))
+                .spawn(move || {
+                    // Marks exploration as done even if a panic ends it.
+                    struct Done(Arc<AtomicBool>);
+                    impl Drop for Done {
+                        fn drop(&mut self) {
+                            self.0.store(true, Ordering::Relaxed);
+                        }
+                    }
+                    let _done = Done(exploration_done);
+
+                    let network = Network::connect(
+                        index,
+                        &addresses,
+                        CONNECT_TIMEOUT,
+                        Self::encode_job,
+                        Self::decode_job,
+                    )
+                    .unwrap_or_else(|err| {
+                        panic!("Process {index} failed to connect to the others: {err}")
+                    });
+                    let mut job_broker = JobBroker::new(thread_count, None);
+                    job_broker.network(network);
+                    log::debug!("{index}: Connected to {} processes.", addresses.len() - 1);
+                    let process_count = addresses.len();
+                    let owner = |fingerprint: Fingerprint| {
+                        (fingerprint.get() % process_count as u64) as usize
+                    };
+
+                    let generated = FingerprintSet::new(None);
+                    let state_count = AtomicUsize::new(0);
+                    let max_depth = AtomicUsize::new(0);
+                    let mut pending: VecDeque<_> = init_states
+                        .into_iter()
+                        .enumerate()
+                        .filter(|(_, s)| {
+                            let fingerprint = fingerprint(s);
+                            owner(fingerprint) == index && {
+                                generated.insert(fingerprint);
+                                true
+                            }
+                        })
+                        .map(|(i, s)| {
+                            (
+                                s,
+                                ebits.clone(),
+                                NonZeroUsize::new(1).unwrap(),
+                                vec![i],
+                                SleepSet::default(),
+                            )
+                        })
+                        .collect();
+                    state_count.store(pending.len(), Ordering::Relaxed);
+
+                    for depth in 1.. {
+                        let next = Self::check_level(
+                            &mut job_broker,
+                            &model,
+                            &state_count,
+                            &generated,
+                            pending,
+                            &discoveries,
+                            &visitor,
+                            thread_count,
+                            target_max_depth,
+                            &max_depth,
+                            depth,
+                            close_at,
+                        );
+
+                        // Step 1: Send the states of the next depth to their owners, keeping the
+                        // least action path to each.
+                        let mut claims = HashMap::new();
+                        for job in next {
+                            Self::claim(&mut claims, job);
+                        }
+                        let mut outgoing: Vec<_> = (0..process_count).map(|_| Vec::new()).collect();
+                        let mut claims: HashMap<_, _> = claims
+                            .into_iter()
+                            .filter_map(|(fingerprint, job)| {
+                                let owner = owner(fingerprint);
+                                if owner == index {
+                                    return Some((fingerprint, job));
+                                }
+                                outgoing[owner].push(job);
+                                None
+                            })
+                            .collect();
+                        for (peer, jobs) in outgoing.iter().enumerate() {
+                            if peer != index {
+                                job_broker.send(peer, jobs).unwrap_or_else(|err| {
+                                    panic!("Failed to send to process {peer}: {err}")
+                                });
+                            }
+                        }
+                        drop(outgoing);
+
+                        // Step 2: Receive the states of the next depth that this process owns, and
+                        // the discoveries of the others.
+                        let checked = Checked {
+                            state_count: state_count.load(Ordering::Relaxed),
+                            max_depth: max_depth.load(Ordering::Relaxed),
+                            timed_out: close_at.is_some_and(|t| t <= SystemTime::now()),
+                            discoveries: discoveries
+                                .iter()
+                                .map(|d| (d.key().to_string(), d.value().clone()))
+                                .collect(),
+                        };
+                        let (received, mut summaries) = Self::exchange(&mut job_broker, &checked);
+                        summaries.push(checked);
+                        for job in received {
+                            Self::claim(&mut claims, job);
+                        }
+                        for (name, action_path) in summaries.iter().flat_map(|s| &s.discoveries) {
+                            let name = properties
+                                .iter()
+                                .find(|p| p.name == *name)
+                                .unwrap_or_else(|| panic!("Unknown property {name:?}"))
+                                .name;
+                            record_least_discovery(&discoveries, name, action_path.clone());
+                        }
+
+                        // Step 3: Claim the states that were not generated at an earlier depth.
+                        let mut claims: Vec<_> = claims
+                            .into_iter()
+                            .filter(|(fingerprint, _)| generated.insert(*fingerprint))
+                            .map(|(_, job)| job)
+                            .collect();
+                        claims.sort_by(|a, b| a.3.cmp(&b.3));
+                        pending = claims.into();
+                        let claimed = Claimed {
+                            unique_state_count: generated.len(),
+                            pending_count: pending.len(),
+                        };
+                        let (_, others) = Self::exchange(&mut job_broker, &claimed);
+                        let total_unique_state_count = claimed.unique_state_count
+                            + others.iter().map(|c| c.unique_state_count).sum::<usize>();
+                        let total_pending_count = claimed.pending_count
+                            + others.iter().map(|c| c.pending_count).sum::<usize>();
+
+                        // Step 4: Stop once every process agrees.
+                        let total_state_count = summaries.iter().map(|s| s.state_count).sum();
+                        global_state_count.store(total_state_count, Ordering::Relaxed);
+                        global_unique_state_count
+                            .store(total_unique_state_count, Ordering::Relaxed);
+                        global_max_depth.store(
+                            summaries.iter().map(|s| s.max_depth).max().unwrap_or(0),
+                            Ordering::Relaxed,
+                        );
+                        let is_finished = finish_when
+                            .matches(&discoveries.iter().map(|r| *r.key()).collect(), &properties);
+                        let is_at_target =
+                            target_state_count.is_some_and(|t| t.get() <= total_state_count);
+                        let is_timed_out = summaries.iter().any(|s| s.timed_out);
+                        if total_pending_count == 0 || is_finished || is_at_target || is_timed_out {
+                            log::debug!(
+                                "{index}: Shutting down after depth {depth}. gen={}",
+                                generated.len()
+                            );
+                            return;
+                        }
+                    }
+                })
+                .expect("Failed to spawn a thread")
+        };
+
+        DistributedChecker {
+            model,
+            handles: vec![handle],
+            shrink_discoveries,
+            state_count,
+            unique_state_count,
+            max_depth,
+            discoveries,
+            exploration_done,
+        }
+    }
+
+    /// Checks the states of one depth on `thread_count` threads, returning the jobs of the next
+    /// depth. Discoveries are recorded as when a BFS checks deterministically.
+    #[allow(clippy::too_many_arguments)]
+    fn check_level(
+        job_broker: &mut JobBroker<Job<M::State, M::Action>>,
+        model: &M,
+        state_count: &AtomicUsize,
+        generated: &FingerprintSet,
+        pending: VecDeque<Job<M::State, M::Action>>,
+        discoveries: &DashMap<&'static str, Vec<usize>>,
+        visitor: &Option<Box<dyn CheckerVisitor<M> + Send + Sync>>,
+        thread_count: usize,
+        target_max_depth: Option<NonZeroUsize>,
+        max_depth: &AtomicUsize,
+        depth: usize,
+        close_at: Option<SystemTime>,
+    ) -> Vec<Job<M::State, M::Action>> {
+        if pending.is_empty() {
+            return Vec::new();
+        }
+        let level = Level {
+            depth,
+            deterministic: true,
+        };
+        job_broker.reopen();
+        job_broker.push(pending);
+        std::thread::scope(|scope| {
+            let workers: Vec<_> = (0..thread_count)
+                .map(|_| {
+                    let mut job_broker = job_broker.clone();
+                    scope.spawn(move || {
+                        let mut next = Vec::new();
+                        let mut pending = VecDeque::new();
+                        loop {
+                            if pending.is_empty() {
+                                pending = job_broker.pop();
+                                if pending.is_empty() {
+                                    return next;
+                                }
+                            }
+                            BfsChecker::check_block(
+                                model,
+                                state_count,
+                                generated,
+                                &mut pending,
+                                discoveries,
+                                visitor,
+                                1500,
+                                target_max_depth,
+                                max_depth,
+                                None,
+                                Some(level),
+                            );
+                            // Jobs of the next depth are at the front.
+                            let next_count = pending.partition_point(|job| job.2.get() > depth);
+                            next.extend(pending.drain(..next_count));
+                            if close_at.is_some_and(|t| t <= SystemTime::now()) {
+                                // Dropping the broker closes it for the other threads.
+                                return next;
+                            }
+                            if pending.len() > 1 && thread_count > 1 {
+                                job_broker.split_and_push(&mut pending);
+                            }
+                        }
+                    })
+                })
+                .collect();
+            workers
+                .into_iter()
+                .flat_map(|worker| {
+                    worker
+                        .join()
+                        .unwrap_or_else(|panic| std::panic::resume_unwind(panic))
+                })
+                .collect()
+        })
+    }
+
+    /// Keeps the least action path to a state.
+    fn claim(
+        claims: &mut HashMap<Fingerprint, Job<M::State, M::Action>>,
+        job: Job<M::State, M::Action>,
+    ) {
+        match claims.entry(fingerprint(&job.0)) {
+            std::collections::hash_map::Entry::Occupied(mut entry) => {
+                if job.3 < entry.get().3 {
+                    entry.insert(job);
+                }
+            }
+            std::collections::hash_map::Entry::Vacant(entry) => {
+                entry.insert(job);
+            }
+        }
+    }
+
+    #[allow(clippy::type_complexity)]
+    fn exchange<Summary>(
+        job_broker: &mut JobBroker<Job<M::State, M::Action>>,
+        summary: &Summary,
+    ) -> (Vec<Job<M::State, M::Action>>, Vec<Summary>)
+    where
+        Summary: Serialize + DeserializeOwned,
+    {
+        job_broker
+            .exchange(summary)
+            .unwrap_or_else(|err| panic!("Failed to exchange with the other processes: {err}"))
+    }
+
+    /// Encodes a state along with its progress, as sleep sets are not sent.
+    fn encode_job(
+        (state, ebits, _, action_path, _): &Job<M::State, M::Action>,
+        bytes: &mut Vec<u8>,
+    ) {
+        let state = serde_json::to_vec(state).expect("Failed to serialize a state");
+        disk::write_usize(bytes, state.len());
+        bytes.extend(state);
+        BfsChecker::<M>::encode_job_progress(ebits, action_path, bytes);
+    }
+
+    fn decode_job(bytes: &mut &[u8]) -> std::io::Result<Job<M::State, M::Action>> {
+        let len = disk::read_usize(bytes)?;
+        let (state, rest) = bytes.split_at_checked(len).ok_or_else(|| {
+            std::io::Error::new(std::io::ErrorKind::InvalidData, "truncated state")
+        })?;
+        let state = serde_json::from_slice(state)?;
+        *bytes = rest;
+        let (ebits, depth, action_path) = BfsChecker::<M>::decode_job_progress(bytes)?;
+        Ok((state, ebits, depth, action_path, SleepSet::default()))
+    }
+}
+
+impl<M> Checker<M> for DistributedChecker<M>
+where
+    M: Model,
+    M::State: Hash,
+{
+    fn model(&self) -> &M {
+        &self.model
+    }
+
+    fn state_count(&self) -> usize {
+        self.state_count.load(Ordering::Relaxed)
+    }
+
+    fn unique_state_count(&self) -> usize {
+        self.unique_state_count.load(Ordering::Relaxed)
+    }
+
+    fn max_depth(&self) -> usize {
+        self.max_depth.load(Ordering::Relaxed)
+    }
+
+    fn discoveries(&self) -> HashMap<&'static str, Path<M::State, M::Action>>
+    where
+        M::State: Clone + PartialEq,
+        M::Action: Clone + PartialEq,
+    {
+        self.discoveries
+            .iter()
+            .map(|mapref| {
+                let name = <&'static str>::clone(mapref.key());
+                let path =
+                    Path::from_action_indices(self.model(), VecDeque::from(mapref.value().clone()));
+                (name, path)
+            })
+            .collect()
+    }
+
+    fn handles(&mut self) -> Vec<JoinHandle<()>> {
+        std::mem::take(&mut self.handles)
+    }
+
+    fn shrinks_discoveries(&self) -> bool {
+        self.shrink_discoveries
+    }
+
+    fn is_done(&self) -> bool {
+        self.exploration_done.load(Ordering::Relaxed)
+            || self.discoveries.len() == self.model.properties().len()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_util::dgraph::DGraph;
+    use crate::test_util::linear_equation_solver::*;
+    use crate::*;
+    use std::net::TcpListener;
+
+    /// The discoveries, state count, unique state count, and max depth of a check.
+    type Outcome<M> = (
+        HashMap<&'static str, Path<<M as Model>::State, <M as Model>::Action>>,
+        usize,
+        usize,
+        usize,
+    );
+
+    fn addresses(count: usize) -> Vec<SocketAddr> {
+        // The listeners are dropped so that the checkers can bind the same ports.
+        let listeners: Vec<_> = (0..count)
+            .map(|_| TcpListener::bind("127.0.0.1:0").unwrap())
+            .collect();
+        listeners
+            .iter()
+            .map(|listener| listener.local_addr().unwrap())
+            .collect()
+    }
+
+    /// Checks a model in several processes, which are simulated with threads, and returns the
+    /// discoveries and counts, which must be the same in every process.
+    fn check_distributed<M>(
+        model: impl Fn() -> CheckerBuilder<M>,
+        process_count: usize,
+    ) -> Outcome<M>
+    where
+        M: Model + Send + Sync + 'static,
+        M::State:
+            Debug + Hash + Send + Sync + Clone + PartialEq + Serialize + DeserializeOwned + 'static,
+        M::Action: Debug + Send + Clone + PartialEq + 'static,
+    {
+        let addresses = addresses(process_count);
+        let checkers: Vec<_> = (0..process_count)
+            .map(|index| {
+                model()
+                    .threads(2)
+                    .spawn_distributed_bfs(index, addresses.clone())
+            })
+            .collect();
+        let results: Vec<_> = checkers
+            .into_iter()
+            .map(|checker| {
+                let checker = checker.join();
+                assert!(checker.is_done());
+                (
+                    checker.discoveries(),
+                    checker.state_count(),
+                    checker.unique_state_count(),
+                    checker.max_depth(),
+                )
+            })
+            .collect();
+        for result in &results[1..] {
+            assert_eq!(result, &results[0]);
+        }
+        results.into_iter().next().unwrap()
+    }
+
+    fn check_local<M>(model: CheckerBuilder<M>) -> Outcome<M>
+    where
+        M: Model + Send + Sync + 'static,
+        M::State: Hash + Send + Sync + Clone + PartialEq + 'static,
+        M::Action: Send + Clone + PartialEq + 'static,
+    {
+        let checker = model.deterministic().spawn_bfs().join();
+        (
+            checker.discoveries(),
+            checker.state_count(),
+            checker.unique_state_count(),
+            checker.max_depth(),
+        )
+    }
+
+    #[test]
+    fn matches_local_bfs() {
+        let model = |(a, b, c)| move || LinearEquation { a, b, c }.checker();
+        // The second is not solvable, so every state is checked.
+        for equation in [(2, 10, 14), (2, 4, 7)] {
+            let expected = check_local(model(equation)());
+            assert_eq!(check_distributed(model(equation), 1), expected);
+            assert_eq!(check_distributed(model(equation), 3), expected);
+        }
+        let (discoveries, ..) = check_distributed(model((2, 4, 7)), 2);
+        assert!(discoveries.is_empty());
+
+        // Also when stopping early.
+        let model = || {
+            LinearEquation { a: 2, b: 4, c: 7 }
+                .checker()
+                .target_max_depth(20)
+        };
+        assert_eq!(check_distributed(model, 3), check_local(model()));
+        let model = || {
+            LinearEquation { a: 2, b: 4, c: 7 }
+                .checker()
+                .target_state_count(20_000)
+        };
+        assert_eq!(check_distributed(model, 3), check_local(model()));
+    }
+
+    #[test]
+    fn matches_local_bfs_for_eventually() {
+        // Only the counterexample that ends in a terminal state is found, as cycles are not
+        // detected.
+        let model = || {
+            DGraph::with_property(Property::eventually("odd", |_, s| s % 2 == 1))
+                .with_path(vec![0, 2, 4, 6])
+                .with_path(vec![0, 2, 8, 2])
+                .with_path(vec![0, 1])
+                .checker()
+        };
+        let expected = check_local(model());
+        assert!(expected.0.contains_key("odd"));
+        assert_eq!(check_distributed(model, 3), expected);
+    }
+
+    #[test]
+    fn rejects_invalid_jobs() {
+        type Checker = DistributedChecker<LinearEquation>;
+        let job = (
+            (1, 2),
+            EventuallyBits::new(),
+            NonZeroUsize::new(2).unwrap(),
+            vec![0, 1],
+            SleepSet::default(),
+        );
+        let mut bytes = Vec::new();
+        Checker::encode_job(&job, &mut bytes);
+        let (state, _, depth, action_path, _) = Checker::decode_job(&mut &bytes[..]).unwrap();
+        assert_eq!((state, depth, action_path), (job.0, job.2, job.3));
+        for len in 0..bytes.len() {
+            assert!(Checker::decode_job(&mut &bytes[..len]).is_err());
+        }
+        assert!(Checker::decode_job(&mut &[0xff; 16][..]).is_err());
+    }
+
+    #[test]
+    #[should_panic(expected = "does not support CheckerBuilder::detect_cycles")]
+    fn cannot_detect_cycles() {
+        let _ = DGraph::with_property(Property::eventually("odd", |_, s| s % 2 == 1))
+            .with_path(vec![0, 2, 0])
+            .checker()
+            .detect_cycles()
+            .spawn_distributed_bfs(0, addresses(1));
+    }
+
+    #[test]
+    #[should_panic(expected = "does not support the temporal logic property")]
+    fn cannot_check_temporal_logic() {
+        let _ = DGraph::with_property(Property::ltl("odd", Ltl::state(|_, s| s % 2 == 1)))
+            .with_path(vec![0, 2, 0])
+            .checker()
+            .spawn_distributed_bfs(0, addresses(1));
+    }
+}
@@ -1,16 +1,42 @@
+use crate::checker::diff::{Diff, Tree};
+use crate::checker::graph::{find_shortest_path, StateGraph};
 use crate::*;
 use parking_lot::RwLock;
 use serde::ser::{SerializeStruct, Serializer};
 use serde::Serialize;
 use std::collections::VecDeque;
 use std::net::ToSocketAddrs;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
 use std::thread::{sleep, spawn};
 use std::time::Duration;
 use tiny_http::{Method, Response, ResponseBox, StatusCode};
 
-// (expectation, name, encoded path to discovery)
-type Property = (Expectation, String, Option<String>);
+// (kind, name, encoded path to discovery)
+type Property = (PropertyKind, String, Option<String>);
+
+/// The kind of a property as shown by the UI, which is the [`Expectation`] of the property or
+/// `Ltl` for a temporal logic property.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize)]
+enum PropertyKind {
+    Always,
+    Eventually,
+    Sometimes,
+    Ltl,
+}
+
+impl PropertyKind {
+    fn of<M: Model>(property: &crate::Property<M>) -> Self {
+        match property.predicate {
+            Predicate::Ltl(_) => PropertyKind::Ltl,
+            _ => match property.expectation() {
+                Expectation::Always => PropertyKind::Always,
+                Expectation::Eventually => PropertyKind::Eventually,
+                Expectation::Sometimes => PropertyKind::Sometimes,
+            },
+        }
+    }
+}
 
 #[derive(Clone, Debug, Default, Eq, PartialEq, Serialize)]
 struct StatusView {
@@ -21,6 +47,92 @@ struct StatusView {
     max_depth: usize,
     properties: Vec<Property>,
     recent_path: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    simulation_stats: Option<SimulationStats>,
+    read_only: bool,
+    /// Whether `POST /.runtocompletion`, `/.pause`, and `/.resume` are allowed.
+    can_run: bool,
+    can_shut_down: bool,
+    diffs: bool,
+}
+
+/// A property and the encoded path to its discovery, if any.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+struct PropertyView {
+    name: String,
+    expectation: PropertyKind,
+    discovery: Option<String>,
+}
+
+/// The discovery of a property, including every step of its path.
+#[derive(Debug, Eq, PartialEq, Serialize)]
+struct DiscoveryView {
+    name: String,
+    expectation: PropertyKind,
+    path: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    loop_start: Option<usize>,
+    steps: Vec<StepView>,
+}
+
+/// A state of a path and the action taken from it, which is absent for the last state.
+#[derive(Debug, Eq, PartialEq, Serialize)]
+struct StepView {
+    state: String,
+    fingerprint: String,
+    action: Option<String>,
+}
+
+/// The neighbourhood of a state, with nodes identified by fingerprint.
+#[derive(Debug, Eq, PartialEq, Serialize)]
+struct GraphView {
+    nodes: Vec<NodeView>,
+    edges: Vec<EdgeView>,
+}
+
+#[derive(Debug, Eq, PartialEq, Serialize)]
+struct NodeView {
+    fingerprint: String,
+    state: String,
+    path: String,
+    depth: usize,
+}
+
+#[derive(Debug, Eq, PartialEq, Serialize)]
+struct EdgeView {
+    source: String,
+    target: String,
+    action: String,
+}
+
+/// The outcome of searching for a state matching a query, which includes the encoded path to the
+/// nearest match, if any.
+#[derive(Debug, Eq, PartialEq, Serialize)]
+struct QueryView {
+    name: String,
+    path: Option<String>,
+    state_count: usize,
+}
+
+/// The greatest number of unique states to visit when searching for a query match, which bounds
+/// the cost of a search.
+const MAX_QUERY_STATE_COUNT: usize = 1_000_000;
+
+/// The greatest number of unique states to visit when searching for a query match in read-only
+/// mode, which further bounds the work that visitors can cause.
+const MAX_READ_ONLY_QUERY_STATE_COUNT: usize = 100_000;
+
+/// The greatest number of query searches in progress at once, each on its own thread.
+const MAX_QUERY_SEARCHES: usize = 4;
+
+/// The greatest depth of a neighbourhood, which bounds the cost of exploring one.
+const MAX_GRAPH_DEPTH: usize = 5;
+
+/// How a neighbourhood is rendered, as specified by the `format` query parameter.
+#[derive(Debug, Eq, PartialEq)]
+enum GraphFormat {
+    Json,
+    Dot,
 }
 
 #[derive(Debug, Eq, PartialEq)]
@@ -31,6 +143,8 @@ struct StateView<State> {
     properties: Vec<Property>,
     svg: Option<String>,
     action_index: Option<usize>,
+    /// The changes from the state that the action was taken from.
+    diff: Option<Diff>,
 }
 
 impl<State> serde::Serialize for StateView<State>
@@ -57,6 +171,9 @@ where
         if self.action_index.is_some() {
             field_cnt += 1;
         }
+        if self.diff.is_some() {
+            field_cnt += 1;
+        }
         let mut out = ser.serialize_struct("StateView", field_cnt)?;
         if let Some(ref action) = self.action {
             out.serialize_field("action", action)?;
@@ -78,6 +195,9 @@ where
             // NOTE: key name should be identical to the front-end (app.js)
             out.serialize_field("actionIndex", &action_index)?;
         }
+        if let Some(ref diff) = self.diff {
+            out.serialize_field("diff", diff)?;
+        }
         out.end()
     }
 }
@@ -111,22 +231,45 @@ where
 {
     let snapshot = Arc::new(RwLock::new(Snapshot(true, None)));
     let snapshot_for_visitor = Arc::clone(&snapshot);
-    let snapshot_for_server = Arc::clone(&snapshot);
+    let snapshot_for_refresher = Arc::downgrade(&snapshot);
     spawn(move || loop {
         sleep(Duration::from_secs(4));
+        let Some(snapshot) = snapshot_for_refresher.upgrade() else {
+            break;
+        };
         snapshot.write().0 = true;
     });
+    let options = checker_builder.explorer.clone();
     let checker = checker_builder
         .visitor(snapshot_for_visitor)
         .spawn_on_demand();
-    serve_checker(checker, snapshot_for_server, addresses)
+    serve_checker(checker, snapshot, addresses, options, true)
+}
+
+/// Serves a checker that was spawned without Explorer, so there is no recently visited path to
+/// show, and the checker cannot be run, paused, or resumed. See [`Checker::serve_with`].
+pub(crate) fn serve_spawned<M, C>(
+    checker: C,
+    addresses: impl ToSocketAddrs,
+    options: ExplorerOptions<M::State>,
+) -> Arc<C>
+where
+    M: 'static + Model + Send + Sync,
+    M::Action: Debug + Send + Sync + Clone + PartialEq,
+    M::State: Debug + Hash + Send + Sync + Clone + PartialEq,
+    C: 'static + Checker<M> + Send + Sync,
+{
+    let snapshot = Arc::new(RwLock::new(Snapshot(false, None)));
+    serve_checker(checker, snapshot, addresses, options, false)
 }
 
 fn serve_checker<M, C>(
     checker: C,
     snapshot: Arc<RwLock<Snapshot<M::Action>>>,
     addresses: impl ToSocketAddrs,
-) -> Arc<impl Checker<M>>
+    options: ExplorerOptions<M::State>,
+    on_demand: bool,
+) -> Arc<C>
 where
     M: 'static + Model + Send + Sync,
     M::Action: Debug + Send + Sync + Clone + PartialEq,
@@ -136,8 +279,14 @@ where
     let checker = Arc::new(checker);
 
     let server = tiny_http::Server::http(addresses).unwrap();
-
-    let server = Arc::new(server);
+    if let Some(address) = server.server_addr().to_ip() {
+        if !address.ip().is_loopback() && options.token.is_none() {
+            log::warn!(
+                "Explorer is listening on {address}, which other machines may be able to reach. \
+                 Consider `CheckerBuilder::explorer_token` or `CheckerBuilder::explorer_read_only`."
+            );
+        }
+    }
 
     macro_rules! get_ui_file {
         ($filename:literal) => {{
@@ -153,43 +302,358 @@ where
     }
 
     let data = Arc::new((snapshot, Arc::clone(&checker)));
-    let web_handle = std::thread::spawn(move || loop {
-        let rq = server.recv().unwrap();
-        let response = match (rq.method(), rq.url()) {
-            (Method::Get, "/") => get_ui_file!("index.htm"),
-            (Method::Get, "/app.css") => get_ui_file!("app.css"),
-            (Method::Get, "/app.js") => get_ui_file!("app.js"),
-            (Method::Get, "/knockout-3.5.0.js") => get_ui_file!("knockout-3.5.0.js"),
-            (Method::Get, "/.status") => {
-                let view = status(Arc::clone(&data));
-                let status_json = serde_json::to_vec(&view).unwrap();
-                Response::from_data(status_json).boxed()
+    let query_searches = Arc::new(AtomicUsize::new(0));
+    let web_handle = std::thread::spawn(move || {
+        for rq in server.incoming_requests() {
+            let (url, query_token) = strip_token(rq.url());
+            if let Some(token) = &options.token {
+                if !is_authorized(token, query_token.as_deref(), rq.headers()) {
+                    respond(
+                        rq,
+                        Response::from_string("Missing or incorrect token")
+                            .with_status_code(StatusCode(401))
+                            .boxed(),
+                    );
+                    continue;
+                }
             }
-            (Method::Post, "/.runtocompletion") => run_to_completion(Arc::clone(&data)),
-            (Method::Get, url) => {
-                if let Some(fingerprints) = url.strip_prefix("/.states") {
-                    match states(fingerprints, Arc::clone(&data)) {
-                        Ok(states) => {
-                            let states_json = serde_json::to_vec(&states).unwrap();
-                            Response::from_data(states_json).boxed()
-                        }
+            if is_forbidden(&options, rq.method(), &url) {
+                respond(
+                    rq,
+                    Response::from_string("Explorer is read-only")
+                        .with_status_code(StatusCode(403))
+                        .boxed(),
+                );
+                continue;
+            }
+
+            // A search may visit many states, so it responds from another thread rather than
+            // delaying other requests.
+            if let (Method::Get, Some(name)) = (rq.method(), url.strip_prefix("/.queries/")) {
+                if query_searches.fetch_add(1, Ordering::SeqCst) >= MAX_QUERY_SEARCHES {
+                    query_searches.fetch_sub(1, Ordering::SeqCst);
+                    let response = Response::from_string("Too many queries are in progress")
+                        .with_status_code(StatusCode(503))
+                        .boxed();
+                    respond(rq, with_token_cookie(response, query_token, &options));
+                    continue;
+                }
+                let name = name.to_string();
+                let data = Arc::clone(&data);
+                let options = options.clone();
+                let query_searches = Arc::clone(&query_searches);
+                spawn(move || {
+                    let max_state_count = if options.read_only {
+                        MAX_READ_ONLY_QUERY_STATE_COUNT
+                    } else {
+                        MAX_QUERY_STATE_COUNT
+                    };
+                    let response = match query(&name, data.1.model(), max_state_count) {
+                        Ok(view) => json_response(&view),
                         Err(err) => Response::from_string(err)
                             .with_status_code(StatusCode(404))
                             .boxed(),
+                    };
+                    query_searches.fetch_sub(1, Ordering::SeqCst);
+                    respond(rq, with_token_cookie(response, query_token, &options));
+                });
+                continue;
+            }
+
+            let mut shutdown = false;
+            let response = match (rq.method(), url.as_str()) {
+                (Method::Get, "/") => get_ui_file!("index.htm"),
+                (Method::Get, "/app.css") => get_ui_file!("app.css"),
+                (Method::Get, "/app.js") => get_ui_file!("app.js"),
+                (Method::Get, "/knockout-3.5.0.js") => get_ui_file!("knockout-3.5.0.js"),
+                (Method::Get, "/.status") => {
+                    let view = StatusView {
+                        read_only: options.read_only,
+                        can_run: on_demand && !options.read_only,
+                        can_shut_down: options.can_shut_down(),
+                        diffs: options.to_tree.is_some(),
+                        ..status(Arc::clone(&data))
+                    };
+                    let status_json = serde_json::to_vec(&view).unwrap();
+                    Response::from_data(status_json).boxed()
+                }
+                // Only a checker that Explorer started checks states as they are browsed.
+                (Method::Post, "/.runtocompletion" | "/.pause" | "/.resume") if !on_demand => {
+                    Response::from_string("Checking was not started by Explorer")
+                        .with_status_code(StatusCode(404))
+                        .boxed()
+                }
+                (Method::Post, "/.runtocompletion") => run_to_completion(Arc::clone(&data)),
+                (Method::Get, "/.properties") => json_response(&properties(&data.1)),
+                (Method::Get, "/.queries") => json_response(&queries(data.1.model())),
+                (Method::Post, "/.pause") => {
+                    data.1.pause();
+                    Response::empty(StatusCode(200)).boxed()
+                }
+                (Method::Post, "/.resume") => run_to_completion(Arc::clone(&data)),
+                (Method::Post, "/.shutdown") => {
+                    shutdown = true;
+                    Response::empty(StatusCode(200)).boxed()
+                }
+                (Method::Get, url) => {
+                    if let Some(name) = url.strip_prefix("/.discoveries/") {
+                        match discovery(name, &data.1) {
+                            Ok(discovery) => json_response(&discovery),
+                            Err(err) => Response::from_string(err)
+                                .with_status_code(StatusCode(404))
+                                .boxed(),
+                        }
+                    } else if let Some(path) = url.strip_prefix("/.graph") {
+                        match graph(path, &data.1) {
+                            Ok((graph, GraphFormat::Dot)) => {
+                                let mut dot = Vec::new();
+                                graph.write_dot(&mut dot).unwrap();
+                                let content_type = tiny_http::Header::from_bytes(
+                                    "Content-Type",
+                                    "text/vnd.graphviz",
+                                )
+                                .unwrap();
+                                Response::from_data(dot).with_header(content_type).boxed()
+                            }
+                            Ok((graph, GraphFormat::Json)) => json_response(&graph_view(&graph)),
+                            Err(err) => Response::from_string(err)
+                                .with_status_code(StatusCode(404))
+                                .boxed(),
+                        }
+                    } else if let Some(fingerprints) = url.strip_prefix("/.states") {
+                        match states(fingerprints, Arc::clone(&data), options.to_tree) {
+                            Ok(states) => {
+                                let states_json = serde_json::to_vec(&states).unwrap();
+                                Response::from_data(states_json).boxed()
+                            }
+                            Err(err) => Response::from_string(err)
+                                .with_status_code(StatusCode(404))
+                                .boxed(),
+                        }
+                    } else {
+                        Response::empty(StatusCode(404)).boxed()
                     }
-                } else {
-                    Response::empty(StatusCode(404)).boxed()
                 }
+                _ => Response::empty(StatusCode(404)).boxed(),
+            };
+            respond(rq, with_token_cookie(response, query_token, &options));
+            if shutdown {
+                log::info!("Explorer shutting down.");
+                break;
             }
-            _ => Response::empty(StatusCode(404)).boxed(),
-        };
-        rq.respond(response).unwrap();
+        }
     });
     web_handle.join().unwrap();
 
     checker
 }
 
+/// Settings of the Explorer web service for [`Checker::serve_with`]. [`CheckerBuilder::serve`]
+/// instead uses the settings of the builder, such as [`CheckerBuilder::explorer_read_only`].
+///
+/// # Example
+///
+/// ```no_run
+/// # use stateright::*;
+/// # #[derive(Clone)] struct M;
+/// # impl Model for M {
+/// #     type State = (); type Action = ();
+/// #     fn init_states(&self) -> Vec<()> { vec![()] }
+/// #     fn actions(&self, _: &(), _: &mut Vec<()>) {}
+/// #     fn next_state(&self, _: &(), _: ()) -> Option<()> { None }
+/// # }
+/// # let model = M;
+/// let checker = model.checker().spawn_bfs().join();
+/// let options = ExplorerOptions::default().read_only().token("a long random token");
+/// checker.serve_with("0.0.0.0:3000", options);
+/// ```
+pub struct ExplorerOptions<State> {
+    pub(crate) read_only: bool,
+    pub(crate) token: Option<String>,
+    /// Converts states for diffs between steps, if they are shown.
+    pub(crate) to_tree: Option<ToTree<State>>,
+}
+
+/// Converts a state for a diff. See [`to_tree`].
+type ToTree<State> = fn(&State) -> Option<Tree>;
+
+impl<State> Clone for ExplorerOptions<State> {
+    fn clone(&self) -> Self {
+        ExplorerOptions {
+            read_only: self.read_only,
+            token: self.token.clone(),
+            to_tree: self.to_tree,
+        }
+    }
+}
+
+impl<State> Default for ExplorerOptions<State> {
+    fn default() -> Self {
+        ExplorerOptions {
+            read_only: false,
+            token: None,
+            to_tree: None,
+        }
+    }
+}
+
+impl<State> Debug for ExplorerOptions<State> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ExplorerOptions")
+            .field("read_only", &self.read_only)
+            .field("token", &self.token.as_ref().map(|_| "..."))
+            .field("diffs", &self.to_tree.is_some())
+            .finish()
+    }
+}
+
+impl<State> ExplorerOptions<State> {
+    /// Disables the endpoints that change what the checker or web service is doing. See
+    /// [`CheckerBuilder::explorer_read_only`].
+    pub fn read_only(self) -> Self {
+        Self {
+            read_only: true,
+            ..self
+        }
+    }
+
+    /// Requires each request to present a shared token. See
+    /// [`CheckerBuilder::explorer_token`].
+    pub fn token(self, token: impl Into<String>) -> Self {
+        Self {
+            token: Some(token.into()),
+            ..self
+        }
+    }
+
+    /// Shows the changes made by each step. See [`CheckerBuilder::explorer_diffs`].
+    pub fn diffs(self) -> Self
+    where
+        State: Serialize,
+    {
+        Self {
+            to_tree: Some(to_tree),
+            ..self
+        }
+    }
+
+    /// Indicates whether `POST /.shutdown` is allowed, which read-only mode only allows if a
+    /// token is required.
+    fn can_shut_down(&self) -> bool {
+        !self.read_only || self.token.is_some()
+    }
+}
+
+/// Converts a state for a diff, or returns `None` if it fails to serialize.
+fn to_tree<State: Serialize>(state: &State) -> Option<Tree> {
+    Tree::new(state).ok()
+}
+
+/// The cookie that saves a token given in the query string.
+const TOKEN_COOKIE: &str = "explorer_token";
+
+/// Responds to a request, ignoring clients that disconnected before receiving the response.
+fn respond(rq: tiny_http::Request, response: ResponseBox) {
+    if let Err(err) = rq.respond(response) {
+        log::debug!("Unable to respond to Explorer request. {err}");
+    }
+}
+
+/// Saves a valid token from the query string as a cookie, so that it need only be in the URL used
+/// to open the UI. An invalid token is not saved, even if the request was authorized by another
+/// means, as it would otherwise replace a valid cookie.
+fn with_token_cookie<State>(
+    response: ResponseBox,
+    query_token: Option<String>,
+    options: &ExplorerOptions<State>,
+) -> ResponseBox {
+    if let (Some(token), Some(expected)) = (query_token, &options.token) {
+        if !tokens_match(expected, &token) {
+            return response;
+        }
+        let cookie = format!("{TOKEN_COOKIE}={token}; Path=/; HttpOnly; SameSite=Strict");
+        if let Ok(header) = tiny_http::Header::from_bytes("Set-Cookie", cookie) {
+            return response.with_header(header);
+        }
+    }
+    response
+}
+
+/// Indicates whether read-only mode forbids a request.
+fn is_forbidden<State>(options: &ExplorerOptions<State>, method: &Method, url: &str) -> bool {
+    options.read_only
+        && is_mutating(method, url)
+        && !(url == "/.shutdown" && options.can_shut_down())
+}
+
+/// Indicates whether a request changes what the checker or server is doing, which read-only
+/// mode forbids.
+fn is_mutating(method: &Method, url: &str) -> bool {
+    *method == Method::Post
+        && matches!(
+            url,
+            "/.runtocompletion" | "/.pause" | "/.resume" | "/.shutdown"
+        )
+}
+
+/// Removes a `token` parameter from the query string of a URL, also returning its decoded value.
+fn strip_token(url: &str) -> (String, Option<String>) {
+    let Some((path, query)) = url.split_once('?') else {
+        return (url.to_string(), None);
+    };
+    let mut token = None;
+    let rest: Vec<_> = query
+        .split('&')
+        .filter(|pair| match pair.strip_prefix("token=") {
+            Some(value) => {
+                token = percent_decode(value);
+                false
+            }
+            None => true,
+        })
+        .collect();
+    if rest.is_empty() {
+        (path.to_string(), token)
+    } else {
+        (format!("{path}?{}", rest.join("&")), token)
+    }
+}
+
+/// Indicates whether a request presents the token, whether in the query string, as a bearer
+/// token in the `Authorization` header, or in the cookie set by an earlier request.
+fn is_authorized(token: &str, query_token: Option<&str>, headers: &[tiny_http::Header]) -> bool {
+    let header_tokens = headers.iter().filter_map(|header| {
+        let value = header.value.as_str();
+        if header.field.equiv("Authorization") {
+            value.strip_prefix("Bearer ")
+        } else if header.field.equiv("Cookie") {
+            value.split(';').find_map(|cookie| {
+                cookie
+                    .trim()
+                    .strip_prefix(TOKEN_COOKIE)
+                    .and_then(|c| c.strip_prefix('='))
+            })
+        } else {
+            None
+        }
+    });
+    query_token
+        .into_iter()
+        .chain(header_tokens)
+        .any(|candidate| tokens_match(token, candidate))
+}
+
+/// Compares tokens in time that only depends on their lengths, so that response times do not
+/// reveal how much of a guess was correct.
+fn tokens_match(expected: &str, actual: &str) -> bool {
+    expected.len() == actual.len()
+        && expected
+            .bytes()
+            .zip(actual.bytes())
+            .fold(0, |diff, (e, a)| diff | (e ^ a))
+            == 0
+}
+
 type Data<Action, Checker> = Arc<(Arc<RwLock<Snapshot<Action>>>, Arc<Checker>)>;
 
 fn status<M, C>(data: Data<M::Action, C>) -> StatusView
@@ -210,6 +674,11 @@ where
         max_depth: checker.max_depth(),
         properties: get_properties(checker),
         recent_path: snapshot.read().1.as_ref().map(|p| format!("{p:?}")),
+        simulation_stats: checker.simulation_stats(),
+        read_only: false,
+        can_run: true,
+        can_shut_down: true,
+        diffs: false,
     }
 }
 
@@ -238,7 +707,7 @@ where
         .into_iter()
         .map(|p| {
             (
-                p.expectation,
+                PropertyKind::of(&p),
                 p.name.to_string(),
                 checker
                     .discovery(p.name)
@@ -248,22 +717,119 @@ where
         .collect()
 }
 
-fn states<M, C>(path: &str, data: Data<M::Action, C>) -> Result<Vec<StateView<M::State>>, String>
+fn json_response(value: &impl Serialize) -> ResponseBox {
+    let json = serde_json::to_vec(value).unwrap();
+    let content_type = tiny_http::Header::from_bytes("Content-Type", "application/json").unwrap();
+    Response::from_data(json).with_header(content_type).boxed()
+}
+
+fn properties<C, M>(checker: &Arc<C>) -> Vec<PropertyView>
+where
+    M: Model,
+    M::State: Hash + Clone + PartialEq,
+    M::Action: Clone + PartialEq,
+    C: Checker<M>,
+{
+    get_properties(checker)
+        .into_iter()
+        .map(|(expectation, name, discovery)| PropertyView {
+            name,
+            expectation,
+            discovery,
+        })
+        .collect()
+}
+
+/// Looks up the discovery of a property by its percent-encoded name.
+fn discovery<M, C>(encoded_name: &str, checker: &Arc<C>) -> Result<DiscoveryView, String>
 where
     M: Model,
     M::Action: Debug + Clone + PartialEq,
     M::State: Debug + Hash + Clone + PartialEq,
     C: Checker<M>,
 {
-    let checker = &data.1;
-    let model = &checker.model();
+    let model = checker.model();
+    let name = percent_decode(encoded_name)
+        .ok_or_else(|| format!("Unable to decode property name {encoded_name}"))?;
+    let property = model
+        .properties()
+        .into_iter()
+        .find(|p| p.name == name)
+        .ok_or_else(|| format!("Unable to find property {name}"))?;
+    let path = checker
+        .discovery(property.name)
+        .ok_or_else(|| format!("Unable to find discovery for property {name}"))?;
+    Ok(DiscoveryView {
+        name,
+        expectation: PropertyKind::of(&property),
+        path: path.encode(model),
+        loop_start: path.loop_start(),
+        steps: path
+            .into_vec()
+            .into_iter()
+            .map(|(state, action)| StepView {
+                state: format!("{state:#?}"),
+                fingerprint: format!("{:?}", fingerprint(&state)),
+                action: action.map(|action| model.format_action(&action)),
+            })
+            .collect(),
+    })
+}
+
+fn queries<M: Model>(model: &M) -> Vec<&'static str> {
+    model.queries().into_iter().map(|q| q.name).collect()
+}
+
+/// Searches breadth-first for a shortest path to a state matching a query, which is looked up by
+/// its percent-encoded name.
+fn query<M>(encoded_name: &str, model: &M, max_state_count: usize) -> Result<QueryView, String>
+where
+    M: Model,
+    M::State: Hash,
+{
+    let name = percent_decode(encoded_name)
+        .ok_or_else(|| format!("Unable to decode query name {encoded_name}"))?;
+    let query = model
+        .queries()
+        .into_iter()
+        .find(|q| q.name == name)
+        .ok_or_else(|| format!("Unable to find query {name}"))?;
+    let (path, state_count) = find_shortest_path(model, &*query.condition, max_state_count);
+    Ok(QueryView {
+        name,
+        path: path.map(|path| {
+            path.iter()
+                .map(usize::to_string)
+                .collect::<Vec<_>>()
+                .join("/")
+        }),
+        state_count,
+    })
+}
 
-    // extract action indices
-    let mut indices_str = path.to_string();
-    if indices_str.ends_with('/') {
-        let relevant_len = indices_str.len() - 1;
-        indices_str.truncate(relevant_len);
+/// Decodes a percent-encoded URL path segment.
+fn percent_decode(encoded: &str) -> Option<String> {
+    let mut bytes = Vec::new();
+    let mut rest = encoded.as_bytes();
+    while let Some((&byte, tail)) = rest.split_first() {
+        if byte == b'%' {
+            let hex = tail
+                .get(..2)
+                .filter(|hex| hex.iter().all(u8::is_ascii_hexdigit))?;
+            bytes.push(u8::from_str_radix(std::str::from_utf8(hex).ok()?, 16).ok()?);
+            rest = &tail[2..];
+        } else {
+            bytes.push(byte);
+            rest = tail;
+        }
     }
+    String::from_utf8(bytes).ok()
+}
+
+/// Extracts the action indices from a path such as `/0/1/`, also returning the path without any
+/// trailing slash.
+fn parse_indices(path: &str) -> Result<(&str, VecDeque<usize>), String> {
+    let indices_str = path.strip_suffix('/').unwrap_or(path);
     let indices: VecDeque<_> = indices_str
         .split('/')
         .filter_map(|idx| idx.parse::<usize>().ok())
@@ -273,6 +839,104 @@ where
     if indices.len() + 1 != indices_str.split('/').count() {
         return Err(format!("Unable to parse action indices {indices_str}"));
     }
+    Ok((indices_str, indices))
+}
+
+/// Explores the neighbourhood of the state that a path of action indices leads to, or of the
+/// initial states if the path is empty. The query string may specify the `depth` of the
+/// neighbourhood (2 by default, and at most [`MAX_GRAPH_DEPTH`]) and its `format` (`json` by
+/// default, or `dot`).
+fn graph<M, C>(url: &str, checker: &Arc<C>) -> Result<(StateGraph<M::State>, GraphFormat), String>
+where
+    M: Model,
+    M::Action: Debug + Clone + PartialEq,
+    M::State: Hash + Clone + PartialEq,
+    C: Checker<M>,
+{
+    let model = checker.model();
+    let (path, query) = url.split_once('?').unwrap_or((url, ""));
+    let (indices_str, indices) = parse_indices(path)?;
+    let mut depth = 2;
+    let mut format = GraphFormat::Json;
+    for (key, value) in query.split('&').filter_map(|pair| pair.split_once('=')) {
+        match key {
+            "depth" => {
+                depth = value
+                    .parse::<usize>()
+                    .map_err(|_| format!("Unable to parse depth {value}"))?
+                    .min(MAX_GRAPH_DEPTH);
+            }
+            "format" => {
+                format = match value {
+                    "json" => GraphFormat::Json,
+                    "dot" => GraphFormat::Dot,
+                    _ => return Err(format!("Unknown graph format {value}")),
+                };
+            }
+            _ => {}
+        }
+    }
+    let roots = if indices.is_empty() {
+        model
+            .init_states()
+            .into_iter()
+            .enumerate()
+            .map(|(i, s)| (s, vec![i]))
+            .collect()
+    } else {
+        let state = Path::final_state::<M>(model, indices.clone()).ok_or_else(|| {
+            format!("Unable to find state following action indices {indices_str}")
+        })?;
+        vec![(state, Vec::from(indices))]
+    };
+    Ok((StateGraph::explore(model, roots, Some(depth)), format))
+}
+
+fn graph_view<State: Debug + Hash>(graph: &StateGraph<State>) -> GraphView {
+    GraphView {
+        nodes: graph
+            .nodes
+            .iter()
+            .map(|node| NodeView {
+                fingerprint: format!("{:?}", node.fingerprint),
+                state: format!("{:#?}", node.state),
+                path: node
+                    .path
+                    .iter()
+                    .map(usize::to_string)
+                    .collect::<Vec<_>>()
+                    .join("/"),
+                depth: node.depth,
+            })
+            .collect(),
+        edges: graph
+            .edges
+            .iter()
+            .map(|edge| EdgeView {
+                source: format!("{:?}", graph.nodes[edge.source].fingerprint),
+                target: format!("{:?}", graph.nodes[edge.target].fingerprint),
+                action: edge.action.clone(),
+            })
+            .collect(),
+    }
+}
+
+/// Follows a path of action indices, returning the states it can lead to next, each with a diff
+/// from the state it leads to if `to_tree` is specified.
+fn states<M, C>(
+    path: &str,
+    data: Data<M::Action, C>,
+    to_tree: Option<ToTree<M::State>>,
+) -> Result<Vec<StateView<M::State>>, String>
+where
+    M: Model,
+    M::Action: Debug + Clone + PartialEq,
+    M::State: Debug + Hash + Clone + PartialEq,
+    C: Checker<M>,
+{
+    let checker = &data.1;
+    let model = &checker.model();
+    let (indices_str, indices) = parse_indices(path)?;
 
     // now build up all the subsequent `StateView`s
     let mut results = Vec::new();
@@ -289,9 +953,12 @@ where
                 properties: get_properties(checker),
                 svg,
                 action_index: Some(init_index),
+                diff: None,
             });
         }
     } else if let Some(last_state) = Path::final_state::<M>(model, indices.clone()) {
+        // A state that fails to serialize simply has no diff.
+        let last_tree = to_tree.and_then(|to_tree| to_tree(&last_state));
         // Must generate the actions three times because they are consumed by `next_state`
         // and `display_outcome`.
         let mut actions1 = Vec::new();
@@ -316,6 +983,11 @@ where
                     indices.push_back(action_index);
                     model.as_svg(Path::from_action_indices::<M>(model, indices))
                 };
+                let diff = to_tree
+                    .zip(last_tree.as_ref())
+                    .and_then(|(to_tree, last_tree)| {
+                        Some(Diff::between(last_tree, &to_tree(&state)?))
+                    });
                 results.push(StateView {
                     action: Some(model.format_action(&action)),
                     outcome,
@@ -323,6 +995,7 @@ where
                     properties: get_properties(checker),
                     svg,
                     action_index: Some(action_index),
+                    diff,
                 });
             } else {
                 // "Action ignored" case is still returned, as it may be useful for debugging.
@@ -333,6 +1006,7 @@ where
                     properties: get_properties(checker),
                     svg: None,
                     action_index: Some(action_index),
+                    diff: None,
                 });
             }
         }
@@ -350,6 +1024,7 @@ mod test {
     use super::*;
     use crate::actor::{RandomChoices, Timers};
     use crate::test_util::binary_clock::*;
+    use crate::test_util::dgraph::DGraph;
 
     #[test]
     fn can_init() {
@@ -361,17 +1036,19 @@ mod test {
                     action: None,
                     outcome: None,
                     state: Some(0),
-                    properties: vec![(Expectation::Always, "in [0, 1]".to_owned(), None)],
+                    properties: vec![(PropertyKind::Always, "in [0, 1]".to_owned(), None)],
                     svg: None,
                     action_index: Some(0),
+                    diff: None,
                 },
                 StateView {
                     action: None,
                     outcome: None,
                     state: Some(1),
-                    properties: vec![(Expectation::Always, "in [0, 1]".to_owned(), None)],
+                    properties: vec![(PropertyKind::Always, "in [0, 1]".to_owned(), None)],
                     svg: None,
                     action_index: Some(1),
+                    diff: None,
                 },
             ]
         );
@@ -380,17 +1057,29 @@ mod test {
     #[test]
     fn can_next() {
         let checker = Arc::new(BinaryClock.checker().spawn_bfs().join());
+        let snapshot = Arc::new(RwLock::new(Snapshot(true, None)));
+        let data = Arc::new((snapshot, Arc::clone(&checker)));
         assert_eq!(
-            get_states(Arc::clone(&checker), "/1/0").unwrap(),
+            states("/1/0", data, Some(to_tree)).unwrap(),
             vec![StateView {
                 action: Some("GoHigh".to_string()),
                 outcome: Some("1".to_string()),
                 state: Some(1),
-                properties: vec![(Expectation::Always, "in [0, 1]".to_owned(), None)],
+                properties: vec![(PropertyKind::Always, "in [0, 1]".to_owned(), None)],
                 svg: None,
                 action_index: Some(0),
+                diff: Some(Diff::Changed {
+                    before: "0".into(),
+                    after: "1".into(),
+                }),
             },]
         );
+
+        // Diffs are only computed if enabled.
+        assert_eq!(
+            get_states(Arc::clone(&checker), "/1/0").unwrap()[0].diff,
+            None
+        );
     }
 
     #[test]
@@ -447,19 +1136,26 @@ mod test {
                         actor_storages: vec![None; 2],
                     }),
                     properties: vec![
-                        (Expectation::Always, "delta within 1".into(), None),
-                        (Expectation::Sometimes, "can reach max".into(), Some("0/1/1/1/1/0".into())),
-                        (Expectation::Eventually, "must reach max".into(), Some("0/1/1/0".into())),
-                        (Expectation::Eventually, "must exceed max".into(), Some("0/1/1/1/1/0".into())),
-                        (Expectation::Always, "#in <= #out".into(), None),
-                        (Expectation::Eventually, "#out <= #in + 1".into(), None),
+                        (PropertyKind::Always, "delta within 1".into(), None),
+                        (PropertyKind::Sometimes, "can reach max".into(), Some("0/1/1/1/1/0".into())),
+                        (PropertyKind::Eventually, "must reach max".into(), Some("0/1/1/0".into())),
+                        (PropertyKind::Eventually, "must exceed max".into(), Some("0/1/1/1/1/0".into())),
+                        (PropertyKind::Always, "#in <= #out".into(), None),
+                        (PropertyKind::Eventually, "#out <= #in + 1".into(), None),
                     ],
                     svg: Some("<svg version=\'1.1\' baseProfile=\'full\' width=\'500\' height=\'30\' viewbox=\'-20 -20 520 50\' xmlns=\'http://www.w3.org/2000/svg\'><defs><marker class=\'svg-event-shape\' id=\'arrow\' markerWidth=\'12\' markerHeight=\'10\' refX=\'12\' refY=\'5\' orient=\'auto\'><polygon points=\'0 0, 12 5, 0 10\' /></marker></defs><line x1=\'0\' y1=\'0\' x2=\'0\' y2=\'30\' class=\'svg-actor-timeline\' />\n<text x=\'0\' y=\'0\' class=\'svg-actor-label\'>0</text>\n<line x1=\'100\' y1=\'0\' x2=\'100\' y2=\'30\' class=\'svg-actor-timeline\' />\n<text x=\'100\' y=\'0\' class=\'svg-actor-label\'>1</text>\n</svg>\n".to_string()),
                     action_index: Some(0),
+                    diff: None,
                 },
             ]);
-        let states = get_states(Arc::clone(&checker), "/0").unwrap();
+        let snapshot = Arc::new(RwLock::new(Snapshot(true, None)));
+        let data = Arc::new((snapshot, Arc::clone(&checker)));
+        let mut states = states("/0", data, Some(to_tree)).unwrap();
         assert_eq!(states.len(), 2);
+        let diffs: Vec<_> = states
+            .iter_mut()
+            .map(|s| serde_json::to_value(s.diff.take()).unwrap())
+            .collect();
         assert_eq!(
             states[0],
             StateView {
@@ -475,15 +1171,16 @@ mod test {
                     actor_storages: vec![None; 2],
                 }),
                 properties: vec![
-                    (Expectation::Always, "delta within 1".into(), None),
-                    (Expectation::Sometimes, "can reach max".into(), Some("0/1/1/1/1/0".into())),
-                    (Expectation::Eventually, "must reach max".into(), Some("0/1/1/0".into())),
-                    (Expectation::Eventually, "must exceed max".into(), Some("0/1/1/1/1/0".into())),
-                    (Expectation::Always, "#in <= #out".into(), None),
-                    (Expectation::Eventually, "#out <= #in + 1".into(), None),
+                    (PropertyKind::Always, "delta within 1".into(), None),
+                    (PropertyKind::Sometimes, "can reach max".into(), Some("0/1/1/1/1/0".into())),
+                    (PropertyKind::Eventually, "must reach max".into(), Some("0/1/1/0".into())),
+                    (PropertyKind::Eventually, "must exceed max".into(), Some("0/1/1/1/1/0".into())),
+                    (PropertyKind::Always, "#in <= #out".into(), None),
+                    (PropertyKind::Eventually, "#out <= #in + 1".into(), None),
                 ],
                 svg: Some("<svg version='1.1' baseProfile='full' width='500' height='60' viewbox='-20 -20 520 80' xmlns='http://www.w3.org/2000/svg'><defs><marker class='svg-event-shape' id='arrow' markerWidth='12' markerHeight='10' refX='12' refY='5' orient='auto'><polygon points='0 0, 12 5, 0 10' /></marker></defs><line x1='0' y1='0' x2='0' y2='60' class='svg-actor-timeline' />\n<text x='0' y='0' class='svg-actor-label'>0</text>\n<line x1='100' y1='0' x2='100' y2='60' class='svg-actor-timeline' />\n<text x='100' y='0' class='svg-actor-label'>1</text>\n</svg>\n".to_string()),
                 action_index: Some(0),
+                diff: None,
             });
         assert_eq!(
             states[1],
@@ -505,16 +1202,64 @@ mod test {
                     actor_storages: vec![None; 2],
                 }),
                 properties: vec![
-                    (Expectation::Always, "delta within 1".into(), None),
-                    (Expectation::Sometimes, "can reach max".into(), Some("0/1/1/1/1/0".into())),
-                    (Expectation::Eventually, "must reach max".into(), Some("0/1/1/0".into())),
-                    (Expectation::Eventually, "must exceed max".into(), Some("0/1/1/1/1/0".into())),
-                    (Expectation::Always, "#in <= #out".into(), None),
-                    (Expectation::Eventually, "#out <= #in + 1".into(), None),
+                    (PropertyKind::Always, "delta within 1".into(), None),
+                    (PropertyKind::Sometimes, "can reach max".into(), Some("0/1/1/1/1/0".into())),
+                    (PropertyKind::Eventually, "must reach max".into(), Some("0/1/1/0".into())),
+                    (PropertyKind::Eventually, "must exceed max".into(), Some("0/1/1/1/1/0".into())),
+                    (PropertyKind::Always, "#in <= #out".into(), None),
+                    (PropertyKind::Eventually, "#out <= #in + 1".into(), None),
                 ],
                 svg: Some("<svg version='1.1' baseProfile='full' width='500' height='60' viewbox='-20 -20 520 80' xmlns='http://www.w3.org/2000/svg'><defs><marker class='svg-event-shape' id='arrow' markerWidth='12' markerHeight='10' refX='12' refY='5' orient='auto'><polygon points='0 0, 12 5, 0 10' /></marker></defs><line x1='0' y1='0' x2='0' y2='60' class='svg-actor-timeline' />\n<text x='0' y='0' class='svg-actor-label'>0</text>\n<line x1='100' y1='0' x2='100' y2='60' class='svg-actor-timeline' />\n<text x='100' y='0' class='svg-actor-label'>1</text>\n<line x1='0' x2='100' y1='0' y2='30' marker-end='url(#arrow)' class='svg-event-line' />\n<text x='100' y='30' class='svg-event-label'>Ping(0)</text>\n</svg>\n".to_string()),
                 action_index: Some(1),
+                diff: None,
             });
+
+        // Unchanged actors and envelopes are collapsed.
+        let unchanged = serde_json::json!({"kind": "unchanged"});
+        let network = |entries| {
+            serde_json::json!({"key": "network", "diff": {
+                "kind": "entries",
+                "entries": [{"key": "UnorderedNonDuplicating", "diff": {
+                    "kind": "entries",
+                    "entries": entries,
+                }}],
+            }})
+        };
+        let ping = "{src: 0, dst: 1, msg: Ping(0)}";
+        assert_eq!(
+            diffs[0],
+            serde_json::json!({
+                "kind": "entries",
+                "entries": [
+                    {"key": "actor_states", "diff": unchanged},
+                    network(serde_json::json!([
+                        {"key": ping, "diff": {"kind": "removed", "before": "1"}},
+                    ])),
+                    {"key": "timers_set", "diff": unchanged},
+                    {"key": "random_choices", "diff": unchanged},
+                    {"key": "crashed", "diff": unchanged},
+                    {"key": "history", "diff": unchanged},
+                    {"key": "storages", "diff": unchanged},
+                ],
+            })
+        );
+        assert_eq!(
+            diffs[1]["entries"][0],
+            serde_json::json!({"key": "actor_states", "diff": {
+                "kind": "entries",
+                "entries": [
+                    {"key": "0", "diff": unchanged},
+                    {"key": "1", "diff": {"kind": "changed", "before": "0", "after": "1"}},
+                ],
+            }})
+        );
+        assert_eq!(
+            diffs[1]["entries"][1],
+            network(serde_json::json!([
+                {"key": ping, "diff": {"kind": "removed", "before": "1"}},
+                {"key": "{src: 1, dst: 0, msg: Pong(0)}", "diff": {"kind": "added", "after": "1"}},
+            ]))
+        );
     }
 
     #[test]
@@ -546,7 +1291,7 @@ mod test {
         assert_eq!(status.unique_state_count, 5);
         assert_eq!(status.max_depth, 5);
         let assert_discovery = |status: &StatusView,
-                                expectation: Expectation,
+                                expectation: PropertyKind,
                                 name: &'static str,
                                 has_discovery: bool| {
             let match_found = status
@@ -560,13 +1305,404 @@ mod test {
                 );
             }
         };
-        assert_discovery(&status, Expectation::Always, "delta within 1", false);
-        assert_discovery(&status, Expectation::Sometimes, "can reach max", true);
-        assert_discovery(&status, Expectation::Eventually, "must reach max", false);
-        assert_discovery(&status, Expectation::Eventually, "must exceed max", true);
-        assert_discovery(&status, Expectation::Always, "#in <= #out", false);
-        assert_discovery(&status, Expectation::Eventually, "#out <= #in + 1", false);
+        assert_discovery(&status, PropertyKind::Always, "delta within 1", false);
+        assert_discovery(&status, PropertyKind::Sometimes, "can reach max", true);
+        assert_discovery(&status, PropertyKind::Eventually, "must reach max", false);
+        assert_discovery(&status, PropertyKind::Eventually, "must exceed max", true);
+        assert_discovery(&status, PropertyKind::Always, "#in <= #out", false);
+        assert_discovery(&status, PropertyKind::Eventually, "#out <= #in + 1", false);
         assert!(status.recent_path.unwrap().starts_with('['));
+        assert_eq!(status.simulation_stats, None);
+    }
+
+    #[test]
+    fn status_includes_simulation_stats() {
+        use crate::actor::actor_test_util::ping_pong::PingPongCfg;
+        use crate::actor::{LossyNetwork, Network};
+
+        let snapshot = Arc::new(RwLock::new(Snapshot(true, None)));
+        let checker = PingPongCfg {
+            max_nat: 2,
+            maintains_history: false,
+        }
+        .into_model()
+        .init_network(Network::new_unordered_nonduplicating([]))
+        .lossy_network(LossyNetwork::Yes)
+        .checker()
+        .target_state_count(100)
+        .spawn_simulation(0, UniformChooser)
+        .join();
+        let status = get_status(Arc::new(checker), snapshot);
+        let stats = status.simulation_stats.clone().unwrap();
+        assert!(stats.runs > 0);
+        assert!(stats.actions.contains_key("Deliver"));
+
+        let status_json = serde_json::to_value(&status).unwrap();
+        assert_eq!(
+            status_json["simulation_stats"]["runs"],
+            serde_json::json!(stats.runs)
+        );
+    }
+
+    #[test]
+    fn smoke_test_discoveries() {
+        use crate::actor::actor_test_util::ping_pong::PingPongCfg;
+        use crate::actor::{LossyNetwork, Network};
+
+        let checker = Arc::new(
+            PingPongCfg {
+                max_nat: 2,
+                maintains_history: true,
+            }
+            .into_model()
+            .init_network(Network::new_unordered_nonduplicating([]))
+            .lossy_network(LossyNetwork::Yes)
+            .checker()
+            .spawn_bfs()
+            .join(),
+        );
+        let views = properties(&checker);
+        assert_eq!(
+            views[1],
+            PropertyView {
+                name: "can reach max".into(),
+                expectation: PropertyKind::Sometimes,
+                discovery: Some("0/1/1/1/1/0".into()),
+            }
+        );
+        assert_eq!(
+            serde_json::to_value(&views[0]).unwrap(),
+            serde_json::json!({
+                "name": "delta within 1",
+                "expectation": "Always",
+                "discovery": null,
+            })
+        );
+
+        let view = discovery("can%20reach%20max", &checker).unwrap();
+        assert_eq!(view.name, "can reach max");
+        assert_eq!(view.path, "0/1/1/1/1/0");
+        assert_eq!(view.loop_start, None);
+        assert_eq!(view.steps.len(), 6);
+        assert_eq!(
+            view.steps[0].action.as_deref(),
+            Some("Id(0) → Ping(0) → Id(1)")
+        );
+        assert_eq!(view.steps[5].action, None);
+        assert!(view.steps[5].state.contains("actor_states"));
+
+        assert_eq!(
+            discovery("delta%20within%201", &checker).unwrap_err(),
+            "Unable to find discovery for property delta within 1"
+        );
+        assert_eq!(
+            discovery("unknown", &checker).unwrap_err(),
+            "Unable to find property unknown"
+        );
+    }
+
+    #[test]
+    fn can_explore_graph() {
+        let checker = Arc::new(BinaryClock.checker().spawn_bfs().join());
+        let (zero, one) = (
+            format!("{:?}", fingerprint(&0i8)),
+            format!("{:?}", fingerprint(&1i8)),
+        );
+        assert_eq!(
+            graph_view(&graph("/1?depth=1", &checker).unwrap().0),
+            GraphView {
+                nodes: vec![
+                    NodeView {
+                        fingerprint: one.clone(),
+                        state: "1".into(),
+                        path: "1".into(),
+                        depth: 0,
+                    },
+                    NodeView {
+                        fingerprint: zero.clone(),
+                        state: "0".into(),
+                        path: "1/0".into(),
+                        depth: 1,
+                    },
+                ],
+                edges: vec![EdgeView {
+                    source: one.clone(),
+                    target: zero.clone(),
+                    action: "GoLow".into(),
+                }],
+            }
+        );
+
+        // The initial states by default, and edges between known states once the depth allows.
+        let view = graph_view(&graph("/", &checker).unwrap().0);
+        assert_eq!(view.nodes.len(), 2);
+        assert_eq!(view.edges.len(), 2);
+        let view = graph_view(&graph("?depth=0", &checker).unwrap().0);
+        assert_eq!(view.nodes.len(), 2);
+        assert_eq!(view.edges.len(), 0);
+
+        assert_eq!(
+            graph("/1/2/3", &checker).err().unwrap(),
+            "Unable to find state following action indices /1/2/3"
+        );
+        assert_eq!(
+            graph("/1?depth=many", &checker).err().unwrap(),
+            "Unable to parse depth many"
+        );
+
+        // Only the format parameter selects the format.
+        assert_eq!(graph("/1", &checker).unwrap().1, GraphFormat::Json);
+        assert_eq!(
+            graph("/1?depth=1&format=dot", &checker).unwrap().1,
+            GraphFormat::Dot
+        );
+        assert_eq!(
+            graph("/1?note=format=dot", &checker).unwrap().1,
+            GraphFormat::Json
+        );
+        assert_eq!(
+            graph("/1?format=svg", &checker).err().unwrap(),
+            "Unknown graph format svg"
+        );
+    }
+
+    #[test]
+    fn can_query() {
+        use crate::actor::actor_test_util::ping_pong::PingPongCfg;
+        use crate::actor::{ActorModel, LossyNetwork, Network};
+
+        let model = PingPongCfg {
+            max_nat: 2,
+            maintains_history: false,
+        }
+        .into_model()
+        .init_network(Network::new_unordered_nonduplicating([]))
+        .lossy_network(LossyNetwork::Yes)
+        .query("both at 1", |_, state| {
+            state.actor_states.iter().all(|s| **s == 1)
+        })
+        .query("at 5", |_, state| {
+            state.actor_states.iter().any(|s| **s == 5)
+        });
+        assert_eq!(queries(&model), vec!["both at 1", "at 5"]);
+
+        let view = query("both%20at%201", &model, MAX_QUERY_STATE_COUNT).unwrap();
+        assert_eq!(view.name, "both at 1");
+        assert_eq!(view.path.as_deref(), Some("0/1/1"));
+        assert_eq!(view.state_count, 5);
+        assert_eq!(
+            Path::final_state::<ActorModel<_, _, _>>(&model, VecDeque::from([0, 1, 1]))
+                .unwrap()
+                .actor_states
+                .iter()
+                .map(|s| **s)
+                .collect::<Vec<_>>(),
+            vec![1, 1]
+        );
+
+        assert_eq!(
+            query("unknown", &model, MAX_QUERY_STATE_COUNT).unwrap_err(),
+            "Unable to find query unknown"
+        );
+
+        // Stops once it visits enough states.
+        let view = query("at%205", &model, 3).unwrap();
+        assert_eq!(view.path, None);
+        assert_eq!(view.state_count, 3);
+
+        // Exhausts the state space, which the max nat bounds.
+        let view = query("at%205", &model, MAX_QUERY_STATE_COUNT).unwrap();
+        assert_eq!(view.path, None);
+        assert_eq!(
+            view.state_count,
+            model.checker().spawn_bfs().join().unique_state_count()
+        );
+    }
+
+    fn unused_address() -> std::net::SocketAddr {
+        std::net::TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap()
+    }
+
+    /// Sends a request such as `GET / HTTP/1.1`, retrying until the server is listening.
+    fn http_request(address: std::net::SocketAddr, request: &str) -> String {
+        use std::io::{Read, Write};
+        let mut stream = loop {
+            match std::net::TcpStream::connect(address) {
+                Ok(stream) => break stream,
+                Err(_) => sleep(Duration::from_millis(10)),
+            }
+        };
+        write!(stream, "{request}\r\nConnection: close\r\n\r\n").unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        response
+    }
+
+    #[test]
+    fn can_require_token_and_shut_down() {
+        let address = unused_address();
+        // Returns once shut down.
+        let server = spawn(move || {
+            BinaryClock
+                .checker()
+                .explorer_token("secret")
+                .serve(address);
+        });
+        let request = |request: &str| http_request(address, request);
+
+        assert!(request("GET /.status HTTP/1.1").starts_with("HTTP/1.1 401"));
+        assert!(
+            request("GET /.status HTTP/1.1\r\nAuthorization: Bearer wrong")
+                .starts_with("HTTP/1.1 401")
+        );
+        let response = request("GET /.status?token=secret HTTP/1.1");
+        assert!(response.starts_with("HTTP/1.1 200"));
+        assert!(response.contains("Set-Cookie: explorer_token=secret; Path=/"));
+        assert!(response.contains("\"read_only\":false"));
+        assert!(
+            request("GET /.states HTTP/1.1\r\nCookie: a=b; explorer_token=secret")
+                .starts_with("HTTP/1.1 200")
+        );
+        let response = request("GET /.status?token=wrong HTTP/1.1\r\nAuthorization: Bearer secret");
+        assert!(response.starts_with("HTTP/1.1 200"));
+        assert!(!response.contains("Set-Cookie"));
+
+        assert!(
+            request("POST /.shutdown HTTP/1.1\r\nAuthorization: Bearer secret")
+                .starts_with("HTTP/1.1 200")
+        );
+        server.join().unwrap();
+    }
+
+    #[test]
+    fn can_shut_down_read_only_with_token() {
+        let address = unused_address();
+        let server = spawn(move || {
+            BinaryClock
+                .checker()
+                .explorer_read_only()
+                .explorer_token("secret")
+                .serve(address);
+        });
+        let request = |request: &str| {
+            http_request(
+                address,
+                &format!("{request} HTTP/1.1\r\nAuthorization: Bearer secret"),
+            )
+        };
+
+        let response = request("GET /.status");
+        assert!(response.contains("\"read_only\":true"));
+        assert!(response.contains("\"can_shut_down\":true"));
+        assert!(request("POST /.runtocompletion").starts_with("HTTP/1.1 403"));
+        assert!(request("POST /.shutdown").starts_with("HTTP/1.1 200"));
+        server.join().unwrap();
+    }
+
+    #[test]
+    fn can_serve_spawned_checker() {
+        let address = unused_address();
+        let checker = DGraph::with_property(crate::Property::eventually("odd", |_, s| s % 2 == 1))
+            .with_path(vec![0, 2, 4, 2])
+            .check_cycles();
+        let discoveries = checker.discoveries();
+        // Returns once shut down.
+        let server = spawn(move || checker.serve(address));
+
+        let response = http_request(address, "GET /.status HTTP/1.1");
+        assert!(response.contains("\"done\":true"));
+        assert!(response.contains("\"recent_path\":null"));
+        assert!(response.contains("\"can_run\":false"));
+        for url in ["/.runtocompletion", "/.pause", "/.resume"] {
+            let response = http_request(address, &format!("POST {url} HTTP/1.1"));
+            assert!(response.starts_with("HTTP/1.1 404"));
+        }
+        let response = http_request(address, "GET /.discoveries/odd HTTP/1.1");
+        assert!(response.starts_with("HTTP/1.1 200"));
+        assert!(response.contains("\"path\":\"0/0/0/0\""));
+        assert!(response.contains("\"loop_start\":1"));
+
+        http_request(address, "POST /.shutdown HTTP/1.1");
+        assert_eq!(server.join().unwrap().discoveries(), discoveries);
+    }
+
+    #[test]
+    fn can_serve_spawned_checker_with_options() {
+        let address = unused_address();
+        let checker = BinaryClock.checker().spawn_bfs().join();
+        let options = ExplorerOptions::default().read_only().token("secret");
+        let server = spawn(move || checker.serve_with(address, options));
+        let request = |request: &str| http_request(address, request);
+
+        assert!(request("GET /.status HTTP/1.1").starts_with("HTTP/1.1 401"));
+        let response = request("GET /.status HTTP/1.1\r\nAuthorization: Bearer secret");
+        assert!(response.contains("\"read_only\":true"));
+        assert!(
+            request("POST /.shutdown HTTP/1.1\r\nAuthorization: Bearer secret")
+                .starts_with("HTTP/1.1 200")
+        );
+        server.join().unwrap();
+    }
+
+    #[test]
+    fn can_check_access() {
+        assert!(is_mutating(&Method::Post, "/.runtocompletion"));
+        assert!(is_mutating(&Method::Post, "/.shutdown"));
+        assert!(!is_mutating(&Method::Get, "/.status"));
+        assert!(!is_mutating(&Method::Get, "/.states/0"));
+
+        // Read-only mode allows shutting down only if a token is required.
+        let mut options = ExplorerOptions::<()> {
+            read_only: true,
+            ..ExplorerOptions::default()
+        };
+        assert!(is_forbidden(&options, &Method::Post, "/.runtocompletion"));
+        assert!(is_forbidden(&options, &Method::Post, "/.shutdown"));
+        assert!(!is_forbidden(&options, &Method::Get, "/.status"));
+        options.token = Some("secret".into());
+        assert!(is_forbidden(&options, &Method::Post, "/.runtocompletion"));
+        assert!(!is_forbidden(&options, &Method::Post, "/.shutdown"));
+
+        assert_eq!(strip_token("/"), ("/".into(), None));
+        assert_eq!(
+            strip_token("/?token=a%20b"),
+            ("/".into(), Some("a b".into()))
+        );
+        assert_eq!(
+            strip_token("/.graph/0?depth=1&token=t&format=dot"),
+            ("/.graph/0?depth=1&format=dot".into(), Some("t".into()))
+        );
+
+        let header = |text: &str| text.parse::<tiny_http::Header>().unwrap();
+        assert!(is_authorized("t", Some("t"), &[]));
+        assert!(!is_authorized("t", Some("tt"), &[]));
+        assert!(is_authorized(
+            "t",
+            None,
+            &[header("authorization: Bearer t")]
+        ));
+        assert!(is_authorized(
+            "t",
+            None,
+            &[header("Cookie: explorer_token=t; other=x")]
+        ));
+        assert!(!is_authorized(
+            "t",
+            None,
+            &[header("Cookie: explorer_token=x")]
+        ));
+        assert!(!is_authorized("t", None, &[]));
+    }
+
+    #[test]
+    fn can_percent_decode() {
+        assert_eq!(percent_decode("a%20b%2Fc").as_deref(), Some("a b/c"));
+        assert_eq!(percent_decode("%E2%86%92").as_deref(), Some("→"));
+        assert_eq!(percent_decode("%2"), None);
+        assert_eq!(percent_decode("%+1"), None);
     }
 
     fn get_states<M, C>(
@@ -581,7 +1717,7 @@ mod test {
     {
         let snapshot = Arc::new(RwLock::new(Snapshot(true, None)));
         let data = Arc::new((snapshot, checker));
-        states(path_name, data)
+        states(path_name, data, None)
     }
 
     fn get_status<M, C>(checker: Arc<C>, snapshot: Arc<RwLock<Snapshot<M::Action>>>) -> StatusView
@@ -0,0 +1,303 @@
+//! Private module for selective re-export.
+
+use crate::{fingerprint, Fingerprint, Model};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::fmt::Debug;
+use std::hash::Hash;
+use std::io::{self, Write};
+
+/// The states within some number of steps of a set of root states, such as the initial states,
+/// along with the steps between them. Backs [`Checker::write_dot`] and the graph view of Explorer.
+///
+/// [`Checker::write_dot`]: crate::Checker::write_dot
+pub(crate) struct StateGraph<State> {
+    pub(crate) nodes: Vec<Node<State>>,
+    pub(crate) edges: Vec<Edge>,
+}
+
+pub(crate) struct Node<State> {
+    pub(crate) fingerprint: Fingerprint,
+    pub(crate) state: State,
+    /// The action indices of a shortest path to the state, starting with the index of an initial
+    /// state (see [`Path::from_action_indices`]).
+    ///
+    /// [`Path::from_action_indices`]: crate::Path::from_action_indices
+    pub(crate) path: Vec<usize>,
+    /// The number of steps from the nearest root.
+    pub(crate) depth: usize,
+}
+
+/// A step between the nodes at two indices, labelled by [`Model::format_action`].
+pub(crate) struct Edge {
+    pub(crate) source: usize,
+    pub(crate) target: usize,
+    pub(crate) action: String,
+}
+
+impl<State> StateGraph<State>
+where
+    State: Clone + Hash,
+{
+    /// Explores the states reachable from `roots` breadth-first, expanding states fewer than
+    /// `max_depth` steps from a root. Each root is paired with its action indices. States outside
+    /// the model's boundary are omitted, as when checking.
+    pub(crate) fn explore<M>(
+        model: &M,
+        roots: Vec<(State, Vec<usize>)>,
+        max_depth: Option<usize>,
+    ) -> Self
+    where
+        M: Model<State = State>,
+        M::Action: Debug,
+    {
+        let mut graph = StateGraph {
+            nodes: Vec::new(),
+            edges: Vec::new(),
+        };
+        let mut indices = HashMap::new();
+        let mut pending = VecDeque::new();
+        for (state, path) in roots {
+            if !model.within_boundary(&state) {
+                continue;
+            }
+            let fingerprint = fingerprint(&state);
+            if indices.contains_key(&fingerprint) {
+                continue;
+            }
+            indices.insert(fingerprint, graph.nodes.len());
+            pending.push_back(graph.nodes.len());
+            graph.nodes.push(Node {
+                fingerprint,
+                state,
+                path,
+                depth: 0,
+            });
+        }
+
+        let mut actions = Vec::new();
+        while let Some(source) = pending.pop_front() {
+            let depth = graph.nodes[source].depth;
+            if max_depth.is_some_and(|max_depth| depth >= max_depth) {
+                continue;
+            }
+            let state = graph.nodes[source].state.clone();
+            model.actions(&state, &mut actions);
+            for (action_index, action) in actions.drain(..).enumerate() {
+                let label = model.format_action(&action);
+                let Some(next_state) = model.next_state(&state, action) else {
+                    continue;
+                };
+                if !model.within_boundary(&next_state) {
+                    continue;
+                }
+                let fingerprint = fingerprint(&next_state);
+                let target = match indices.get(&fingerprint) {
+                    Some(&target) => target,
+                    None => {
+                        let target = graph.nodes.len();
+                        let mut path = graph.nodes[source].path.clone();
+                        path.push(action_index);
+                        indices.insert(fingerprint, target);
+                        pending.push_back(target);
+                        graph.nodes.push(Node {
+                            fingerprint,
+                            state: next_state,
+                            path,
+                            depth: depth + 1,
+                        });
+                        target
+                    }
+                };
+                graph.edges.push(Edge {
+                    source,
+                    target,
+                    action: label,
+                });
+            }
+        }
+        graph
+    }
+
+    /// Writes the graph in the [DOT](https://graphviz.org/doc/info/lang.html) language of
+    /// Graphviz. Nodes are named by fingerprint and labelled by state, and roots are drawn bold.
+    pub(crate) fn write_dot(&self, writer: &mut dyn Write) -> io::Result<()>
+    where
+        State: Debug,
+    {
+        writeln!(writer, "digraph {{")?;
+        for node in &self.nodes {
+            writeln!(
+                writer,
+                "  s{} [label=\"{}\"{}];",
+                node.fingerprint,
+                escape(&format!("{:?}", node.state)),
+                if node.depth == 0 { ", style=bold" } else { "" },
+            )?;
+        }
+        for edge in &self.edges {
+            writeln!(
+                writer,
+                "  s{} -> s{} [label=\"{}\"];",
+                self.nodes[edge.source].fingerprint,
+                self.nodes[edge.target].fingerprint,
+                escape(&edge.action),
+            )?;
+        }
+        writeln!(writer, "}}")
+    }
+}
+
+/// Searches breadth-first from the initial states for a state satisfying `condition`, returning
+/// the action indices of a shortest path to one (see [`Path::from_action_indices`]) if found
+/// within `max_state_count` unique states, along with the number of unique states visited. States
+/// outside the model's boundary are omitted, as when checking.
+///
+/// [`Path::from_action_indices`]: crate::Path::from_action_indices
+pub(crate) fn find_shortest_path<M>(
+    model: &M,
+    condition: &dyn Fn(&M, &M::State) -> bool,
+    max_state_count: usize,
+) -> (Option<Vec<usize>>, usize)
+where
+    M: Model,
+    M::State: Hash,
+{
+    let mut visited = HashSet::new();
+    // The parent of each state found and the index of the action leading to it, or the index of
+    // the initial state, from which the path to a match is rebuilt.
+    let mut parents: Vec<(Option<usize>, usize)> = Vec::new();
+    let mut pending = VecDeque::new();
+    for (index, state) in model.init_states().into_iter().enumerate() {
+        if model.within_boundary(&state) && visited.insert(fingerprint(&state)) {
+            parents.push((None, index));
+            pending.push_back((state, parents.len() - 1));
+        }
+    }
+
+    let mut actions = Vec::new();
+    while let Some((state, node)) = pending.pop_front() {
+        if condition(model, &state) {
+            let mut path = Vec::new();
+            let mut node = Some(node);
+            while let Some((parent, index)) = node.map(|node| parents[node]) {
+                path.push(index);
+                node = parent;
+            }
+            path.reverse();
+            return (Some(path), visited.len());
+        }
+        if visited.len() >= max_state_count {
+            continue;
+        }
+        model.actions(&state, &mut actions);
+        for (action_index, action) in actions.drain(..).enumerate() {
+            let Some(next_state) = model.next_state(&state, action) else {
+                continue;
+            };
+            if !model.within_boundary(&next_state) || !visited.insert(fingerprint(&next_state)) {
+                continue;
+            }
+            parents.push((Some(node), action_index));
+            pending.push_back((next_state, parents.len() - 1));
+        }
+    }
+    (None, visited.len())
+}
+
+/// Escapes text for a quoted DOT string.
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_util::dgraph::DGraph;
+    use crate::test_util::linear_equation_solver::*;
+    use crate::{Checker, Model, Property};
+
+    #[test]
+    fn explores_within_max_depth() {
+        let model = LinearEquation { a: 2, b: 10, c: 14 };
+        let graph = StateGraph::explore(&model, vec![((0, 0), vec![0])], Some(2));
+        let states: Vec<_> = graph.nodes.iter().map(|n| n.state).collect();
+        assert_eq!(states, vec![(0, 0), (1, 0), (0, 1), (2, 0), (1, 1), (0, 2)]);
+        assert_eq!(graph.nodes[4].path, vec![0, 0, 1]);
+        assert_eq!(graph.nodes[4].depth, 2);
+        // (1, 1) is reached from both (1, 0) and (0, 1).
+        let edges: Vec<_> = graph
+            .edges
+            .iter()
+            .map(|e| (e.source, e.target, e.action.as_str()))
+            .collect();
+        assert_eq!(
+            edges,
+            vec![
+                (0, 1, "IncreaseX"),
+                (0, 2, "IncreaseY"),
+                (1, 3, "IncreaseX"),
+                (1, 4, "IncreaseY"),
+                (2, 4, "IncreaseX"),
+                (2, 5, "IncreaseY"),
+            ]
+        );
+    }
+
+    #[test]
+    fn can_write_dot() {
+        let model =
+            DGraph::with_property(Property::always("true", |_, _| true)).with_path(vec![1, 2, 1]);
+        let graph = StateGraph::explore(&model, vec![(1, vec![0])], None);
+        let mut dot = Vec::new();
+        graph.write_dot(&mut dot).unwrap();
+        let (one, two) = (fingerprint(&1u8), fingerprint(&2u8));
+        assert_eq!(
+            String::from_utf8(dot).unwrap(),
+            format!(
+                "digraph {{\n\
+                 \x20 s{one} [label=\"1\", style=bold];\n\
+                 \x20 s{two} [label=\"2\"];\n\
+                 \x20 s{one} -> s{two} [label=\"2\"];\n\
+                 \x20 s{two} -> s{one} [label=\"1\"];\n\
+                 }}\n"
+            )
+        );
+
+        // Also via a checker, from the initial states.
+        let mut dot = Vec::new();
+        model
+            .checker()
+            .spawn_bfs()
+            .join()
+            .write_dot(&mut dot, Some(0))
+            .unwrap();
+        assert_eq!(
+            String::from_utf8(dot).unwrap(),
+            format!("digraph {{\n  s{one} [label=\"1\", style=bold];\n}}\n")
+        );
+
+        assert_eq!(escape("\"a\\b\"\n"), "\\\"a\\\\b\\\"\\n");
+    }
+
+    #[test]
+    fn can_find_shortest_path() {
+        let model = LinearEquation { a: 2, b: 10, c: 14 };
+        assert_eq!(
+            find_shortest_path(&model, &|_, &(x, y)| x == 2 && y == 1, usize::MAX),
+            (Some(vec![0, 0, 0, 1]), 12)
+        );
+        assert_eq!(
+            find_shortest_path(&model, &|_, &(x, _)| x == 2, 3),
+            (None, 3)
+        );
+
+        let model =
+            DGraph::with_property(Property::always("true", |_, _| true)).with_path(vec![1, 2, 3]);
+        assert_eq!(
+            find_shortest_path(&model, &|_, s| *s == 4, usize::MAX),
+            (None, 3)
+        );
+    }
+}
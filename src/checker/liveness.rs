@@ -0,0 +1,845 @@
+//! Private module for selective re-export.
+
+use crate::checker::ltl::Monitor;
+use crate::{fingerprint, Fingerprint, Model, Predicate, Property};
+use dashmap::DashMap;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::hash::Hash;
+use std::num::NonZeroUsize;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread::JoinHandle;
+
+// The exploring checkers identify `eventually` counterexamples that end in a terminal state, but
+// they cannot tell a cycle from a DAG join, so paths ending in a cycle are ignored. They also
+// deduplicate states regardless of which `eventually` properties were satisfied along the way,
+// which can hide counterexamples that revisit a state. Temporal logic properties are not checked
+// during exploration at all.
+//
+// This module closes those gaps with a sequential pass per unresolved `eventually` or temporal
+// logic property. The property's negation is compiled into a monitor (see ltl.rs), and the pass
+// explores the product of the model with that monitor. A terminal state is treated as repeating
+// forever, so a counterexample is a lasso (a prefix followed by a loop) within the product whose
+// loop visits each of the monitor's acceptance sets. Such loops are found by computing the
+// product's strongly connected components, and a lasso is reported for the accepting component
+// that is closest to an initial state. For an `eventually` property the monitor has a single
+// node, so the product is simply the subgraph of states that do not satisfy the property.
+//
+// Fairness constraints are handled by refining components in the style of Emerson and Lei. A
+// component violates a weak fairness constraint if the constrained actions are enabled in every
+// state of the component but none of its edges takes one, in which case no cycle within the
+// component is fair. A component violates a strong fairness constraint if the constrained actions
+// are enabled in some state but never taken, in which case a fair cycle can only avoid those
+// states, so they are removed and the remainder is decomposed again. A component satisfying every
+// constraint has a fair cycle that visits a witness for each constraint.
+
+pub(crate) type FairnessBits = id_set::IdSet;
+
+/// A fairness constraint over the class of actions for which a predicate holds. See
+/// [`CheckerBuilder::weak_fairness`] and [`CheckerBuilder::strong_fairness`].
+///
+/// [`CheckerBuilder::weak_fairness`]: crate::CheckerBuilder::weak_fairness
+/// [`CheckerBuilder::strong_fairness`]: crate::CheckerBuilder::strong_fairness
+pub(crate) enum Fairness<Action> {
+    /// If the class is enabled continuously, then it is eventually taken.
+    Weak(ActionClass<Action>),
+    /// If the class is enabled infinitely often, then it is taken infinitely often.
+    Strong(ActionClass<Action>),
+}
+
+/// A predicate identifying the actions in the class of a [`Fairness`] constraint.
+pub(crate) type ActionClass<Action> = Arc<dyn Fn(&Action) -> bool + Send + Sync>;
+
+impl<Action> Fairness<Action> {
+    fn includes(&self, action: &Action) -> bool {
+        match self {
+            Fairness::Weak(predicate) | Fairness::Strong(predicate) => predicate(action),
+        }
+    }
+}
+
+/// Returns the indices of the fairness constraints whose class includes an action.
+pub(crate) fn classify<Action>(fairness: &[Fairness<Action>], action: &Action) -> FairnessBits {
+    fairness
+        .iter()
+        .enumerate()
+        .filter(|(_, f)| f.includes(action))
+        .map(|(i, _)| i)
+        .collect()
+}
+
+/// The outcome of checking a set of states that are strongly connected against the fairness
+/// constraints.
+#[derive(Debug, PartialEq)]
+pub(crate) enum Judgement {
+    /// There is a fair cycle through every state.
+    Fair,
+    /// No cycle through these states is fair.
+    Unfair,
+    /// A fair cycle must avoid the states in which these strong fairness constraints are enabled.
+    Restrict(FairnessBits),
+}
+
+/// Judges a set of strongly connected states given the fairness constraints enabled in each of
+/// them and the union of the classes of actions taken along their internal edges.
+pub(crate) fn judge<'a, Action>(
+    fairness: &[Fairness<Action>],
+    enabled: impl Iterator<Item = &'a FairnessBits> + Clone,
+    taken: &FairnessBits,
+) -> Judgement {
+    let mut restricted = FairnessBits::new();
+    for (i, f) in fairness.iter().enumerate() {
+        if taken.contains(i) {
+            continue;
+        }
+        match f {
+            Fairness::Weak(_) => {
+                if enabled.clone().all(|e| e.contains(i)) {
+                    return Judgement::Unfair;
+                }
+            }
+            Fairness::Strong(_) => {
+                if enabled.clone().any(|e| e.contains(i)) {
+                    restricted.insert(i);
+                }
+            }
+        }
+    }
+    if restricted.is_empty() {
+        Judgement::Fair
+    } else {
+        Judgement::Restrict(restricted)
+    }
+}
+
+/// Returns whether an exploring checker should search for cycles once exploration completes,
+/// which is the case if [`CheckerBuilder::detect_cycles`] was called and the model has
+/// `eventually` or temporal logic properties.
+///
+/// # Panics
+///
+/// Panics if the model has temporal logic properties but cycles are not detected, as those
+/// properties are otherwise left unchecked.
+///
+/// [`CheckerBuilder::detect_cycles`]: crate::CheckerBuilder::detect_cycles
+pub(crate) fn is_enabled<M: Model>(detect_cycles: bool, properties: &[Property<M>]) -> bool {
+    if !detect_cycles {
+        if let Some(p) = properties
+            .iter()
+            .find(|p| matches!(p.predicate, Predicate::Ltl(_)))
+        {
+            panic!(
+                "Checking the temporal logic property {:?} requires CheckerBuilder::detect_cycles",
+                p.name
+            );
+        }
+        return false;
+    }
+    properties
+        .iter()
+        .any(|p| matches!(p.predicate, Predicate::Eventually(_) | Predicate::Ltl(_)))
+}
+
+/// Joins the threads of an exploring checker and then searches for counterexamples to the
+/// `eventually` and temporal logic properties for which that checker did not find a discovery.
+/// The position of the state to which each counterexample loops back is recorded in
+/// `loop_starts`, as a path may revisit that state before its loop begins.
+///
+/// `done` is set once the search completes (or is abandoned). `should_stop` is consulted
+/// periodically so that the search honors the same stopping conditions as the exploring checker.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn spawn_after<M>(
+    handles: Vec<JoinHandle<()>>,
+    model: Arc<M>,
+    discoveries: Arc<DashMap<&'static str, Vec<usize>>>,
+    loop_starts: Arc<DashMap<&'static str, Option<usize>>>,
+    target_max_depth: Option<NonZeroUsize>,
+    fairness: Vec<Fairness<M::Action>>,
+    done: Arc<AtomicBool>,
+    should_stop: impl Fn() -> bool + Send + 'static,
+) -> JoinHandle<()>
+where
+    M: Model + Send + Sync + 'static,
+    M::State: Clone + Hash,
+{
+    std::thread::Builder::new()
+        .name("liveness".to_owned())
+        .spawn(move || {
+            for h in handles {
+                if let Err(panic) = h.join() {
+                    done.store(true, Ordering::Relaxed);
+                    std::panic::resume_unwind(panic);
+                }
+            }
+            for property in model.properties() {
+                let Some(monitor) = Monitor::for_property(&property) else {
+                    continue;
+                };
+                if discoveries.contains_key(property.name) {
+                    continue;
+                }
+                if should_stop() {
+                    log::debug!("Liveness checking stopped early.");
+                    break;
+                }
+                log::debug!("Checking liveness. property={:?}", property.name);
+                if let Some((action_indices, loop_start)) = find_counterexample(
+                    &*model,
+                    &monitor,
+                    target_max_depth,
+                    &fairness,
+                    &should_stop,
+                ) {
+                    loop_starts.insert(property.name, loop_start);
+                    discoveries.insert(property.name, action_indices);
+                }
+            }
+            done.store(true, Ordering::Relaxed);
+        })
+        .expect("Failed to spawn a thread")
+}
+
+/// A state of the product of a model and a monitor.
+struct Node {
+    /// The preceding node and the index of the action leading from it (`None` if a terminal state
+    /// is repeated), or the index of the initial state.
+    parent: Result<(usize, Option<usize>), usize>,
+    /// The number of states in the shortest path leading to this node.
+    depth: usize,
+    /// The fairness constraints with an action that is enabled in this state, regardless of
+    /// whether that action leads to another node.
+    enabled: FairnessBits,
+    /// The monitor's acceptance sets that include this node.
+    accepting: FairnessBits,
+    /// The transitions to other nodes.
+    successors: Vec<Edge>,
+}
+
+/// A transition from one node to another.
+struct Edge {
+    /// The index of the action, or `None` if a terminal state is repeated.
+    action_index: Option<usize>,
+    target: usize,
+    /// The fairness constraints whose class includes the action.
+    classes: FairnessBits,
+}
+
+/// Searches for a fair path that the monitor accepts, returning the action indices (the 0th
+/// index is for the init state) of the path along with the position of the state to which it loops
+/// back, if any. A path that does not loop ends in a terminal state.
+pub(crate) fn find_counterexample<M>(
+    model: &M,
+    monitor: &Monitor<M>,
+    target_max_depth: Option<NonZeroUsize>,
+    fairness: &[Fairness<M::Action>],
+    should_stop: &impl Fn() -> bool,
+) -> Option<(Vec<usize>, Option<usize>)>
+where
+    M: Model,
+    M::State: Clone + Hash,
+{
+    let mut nodes: Vec<Node> = Vec::new();
+    let mut node_indices: HashMap<(Fingerprint, usize), usize> = HashMap::new();
+    let mut pending = VecDeque::new();
+    // Returns the index of the node for a state and monitor node, adding the node if necessary.
+    let mut visit = |nodes: &mut Vec<Node>,
+                     pending: &mut VecDeque<_>,
+                     state: &M::State,
+                     fp: Fingerprint,
+                     monitor_node: usize,
+                     parent: Result<(usize, Option<usize>), usize>| {
+        *node_indices.entry((fp, monitor_node)).or_insert_with(|| {
+            let index = nodes.len();
+            nodes.push(Node {
+                parent,
+                depth: match parent {
+                    Ok((parent, Some(_))) => nodes[parent].depth + 1,
+                    Ok((parent, None)) => nodes[parent].depth,
+                    Err(_) => 1,
+                },
+                enabled: FairnessBits::new(),
+                accepting: monitor.nodes[monitor_node].accepting.clone(),
+                successors: Vec::new(),
+            });
+            pending.push_back((index, state.clone(), fp, monitor_node));
+            index
+        })
+    };
+    for (init_index, init_state) in model.init_states().into_iter().enumerate() {
+        if !model.within_boundary(&init_state) {
+            continue;
+        }
+        let atoms = monitor.evaluate(model, &init_state);
+        let fp = fingerprint(&init_state);
+        for &monitor_node in &monitor.initial {
+            if monitor.admits(monitor_node, &atoms) {
+                visit(
+                    &mut nodes,
+                    &mut pending,
+                    &init_state,
+                    fp,
+                    monitor_node,
+                    Err(init_index),
+                );
+            }
+        }
+    }
+
+    // Explore breadth-first so that counterexamples are as short as possible.
+    let mut actions = Vec::new();
+    let mut edges = Vec::new();
+    while let Some((index, state, fp, monitor_node)) = pending.pop_front() {
+        if index % 1024 == 0 && should_stop() {
+            return None;
+        }
+        if let Some(target_max_depth) = target_max_depth {
+            if nodes[index].depth >= target_max_depth.get() {
+                continue;
+            }
+        }
+        let monitor_successors = &monitor.nodes[monitor_node].successors;
+        let mut is_terminal = true;
+        model.actions(&state, &mut actions);
+        for (action_index, action) in actions.drain(..).enumerate() {
+            let classes = classify(fairness, &action);
+            let next_state = match model.next_state(&state, action) {
+                None => continue,
+                Some(next_state) => next_state,
+            };
+            if !model.within_boundary(&next_state) {
+                continue;
+            }
+            is_terminal = false;
+            nodes[index].enabled.inplace_union(&classes);
+            let atoms = monitor.evaluate(model, &next_state);
+            let next_fp = fingerprint(&next_state);
+            for &next_monitor_node in monitor_successors {
+                if !monitor.admits(next_monitor_node, &atoms) {
+                    continue;
+                }
+                let parent = Ok((index, Some(action_index)));
+                edges.push(Edge {
+                    action_index: Some(action_index),
+                    target: visit(
+                        &mut nodes,
+                        &mut pending,
+                        &next_state,
+                        next_fp,
+                        next_monitor_node,
+                        parent,
+                    ),
+                    classes: classes.clone(),
+                });
+            }
+        }
+        if is_terminal {
+            let atoms = monitor.evaluate(model, &state);
+            for &next_monitor_node in monitor_successors {
+                if !monitor.admits(next_monitor_node, &atoms) {
+                    continue;
+                }
+                let parent = Ok((index, None));
+                edges.push(Edge {
+                    action_index: None,
+                    target: visit(
+                        &mut nodes,
+                        &mut pending,
+                        &state,
+                        fp,
+                        next_monitor_node,
+                        parent,
+                    ),
+                    classes: FairnessBits::new(),
+                });
+            }
+        }
+        nodes[index].successors.append(&mut edges);
+    }
+
+    let (entry, cycle) = find_fair_cycle(&nodes, monitor.acceptance_count, fairness)?;
+    let mut action_indices = action_indices_to(&nodes, entry);
+    if cycle.iter().all(Option::is_none) {
+        // Repeats a terminal state.
+        return Some((action_indices, None));
+    }
+    let loop_start = action_indices.len() - 1;
+    action_indices.extend(cycle.into_iter().flatten());
+    Some((action_indices, Some(loop_start)))
+}
+
+/// Indicates whether the monitor accepts a path, which either loops back to the state at
+/// `loop_start` (in which case the last state is a repetition of that state) or repeats its last
+/// state forever.
+pub(crate) fn accepts_path<M: Model>(
+    model: &M,
+    monitor: &Monitor<M>,
+    states: &[M::State],
+    loop_start: Option<usize>,
+) -> bool {
+    let len = match loop_start {
+        Some(_) => states.len() - 1,
+        None => states.len(),
+    };
+    let successor = |position: usize| {
+        if position + 1 < len {
+            position + 1
+        } else {
+            loop_start.unwrap_or(len - 1)
+        }
+    };
+    let atoms: Vec<_> = states[..len]
+        .iter()
+        .map(|s| monitor.evaluate(model, s))
+        .collect();
+
+    let mut nodes: Vec<Node> = Vec::new();
+    let mut node_indices: HashMap<(usize, usize), usize> = HashMap::new();
+    let mut pending = VecDeque::new();
+    let mut visit =
+        |nodes: &mut Vec<Node>, pending: &mut VecDeque<_>, position: usize, monitor_node: usize| {
+            *node_indices
+                .entry((position, monitor_node))
+                .or_insert_with(|| {
+                    nodes.push(Node {
+                        parent: Err(0),
+                        depth: 0,
+                        enabled: FairnessBits::new(),
+                        accepting: monitor.nodes[monitor_node].accepting.clone(),
+                        successors: Vec::new(),
+                    });
+                    pending.push_back((nodes.len() - 1, position, monitor_node));
+                    nodes.len() - 1
+                })
+        };
+    for &monitor_node in &monitor.initial {
+        if monitor.admits(monitor_node, &atoms[0]) {
+            visit(&mut nodes, &mut pending, 0, monitor_node);
+        }
+    }
+    let mut edges = Vec::new();
+    while let Some((index, position, monitor_node)) = pending.pop_front() {
+        let next_position = successor(position);
+        for &next_monitor_node in &monitor.nodes[monitor_node].successors {
+            if monitor.admits(next_monitor_node, &atoms[next_position]) {
+                edges.push(Edge {
+                    action_index: None,
+                    target: visit(&mut nodes, &mut pending, next_position, next_monitor_node),
+                    classes: FairnessBits::new(),
+                });
+            }
+        }
+        nodes[index].successors.append(&mut edges);
+    }
+    find_fair_cycle::<M::Action>(&nodes, monitor.acceptance_count, &[]).is_some()
+}
+
+/// Searches for a fair cycle that visits each acceptance set, returning the node at which the
+/// cycle starts and the action indices of the cycle. Nodes are indexed in breadth-first order, so
+/// the cycle starts at the node closest to an initial state among the fair components.
+fn find_fair_cycle<Action>(
+    nodes: &[Node],
+    acceptance_count: usize,
+    fairness: &[Fairness<Action>],
+) -> Option<(usize, Vec<Option<usize>>)> {
+    let mut fair: Option<(usize, Vec<usize>)> = None;
+    let mut components =
+        strongly_connected_components(nodes, &(0..nodes.len()).collect::<Vec<_>>());
+    while let Some(component) = components.pop() {
+        let entry = *component.iter().min().unwrap();
+        if matches!(&fair, Some((best, _)) if *best < entry) {
+            continue;
+        }
+        let mut accepting = FairnessBits::new();
+        for &i in &component {
+            accepting.inplace_union(&nodes[i].accepting);
+        }
+        if accepting.len() < acceptance_count {
+            continue;
+        }
+        let members: HashSet<usize> = component.iter().copied().collect();
+        let mut taken = FairnessBits::new();
+        for &i in &component {
+            for edge in &nodes[i].successors {
+                if members.contains(&edge.target) {
+                    taken.inplace_union(&edge.classes);
+                }
+            }
+        }
+        match judge(
+            fairness,
+            component.iter().map(|&i| &nodes[i].enabled),
+            &taken,
+        ) {
+            Judgement::Fair => fair = Some((entry, component)),
+            Judgement::Unfair => {}
+            Judgement::Restrict(restricted) => {
+                let remaining: Vec<usize> = component
+                    .into_iter()
+                    .filter(|&i| nodes[i].enabled.is_disjoint(&restricted))
+                    .collect();
+                components.extend(strongly_connected_components(nodes, &remaining));
+            }
+        }
+    }
+    let (entry, mut component) = fair?;
+    component.sort_unstable();
+    let members: HashSet<usize> = component.iter().copied().collect();
+
+    // The cycle starts and ends at the entry, passing through a witness for each acceptance set
+    // and fairness constraint along the way.
+    let mut cycle = Vec::new();
+    let mut current = entry;
+    let visit = |cycle: &mut Vec<Option<usize>>, current: &mut usize, witness: usize| {
+        if *current != witness {
+            cycle.extend(path_within(nodes, &members, *current, witness));
+            *current = witness;
+        }
+    };
+    for i in 0..acceptance_count {
+        let witness = component
+            .iter()
+            .copied()
+            .find(|&j| nodes[j].accepting.contains(i))
+            .unwrap();
+        visit(&mut cycle, &mut current, witness);
+    }
+    for (i, f) in fairness.iter().enumerate() {
+        let edge = component.iter().find_map(|&source| {
+            let edge = nodes[source]
+                .successors
+                .iter()
+                .find(|e| e.classes.contains(i) && members.contains(&e.target))?;
+            Some((source, edge))
+        });
+        if let Some((source, edge)) = edge {
+            visit(&mut cycle, &mut current, source);
+            cycle.push(edge.action_index);
+            current = edge.target;
+        } else if let Fairness::Weak(_) = f {
+            let witness = component
+                .iter()
+                .copied()
+                .find(|&j| !nodes[j].enabled.contains(i))
+                .unwrap();
+            visit(&mut cycle, &mut current, witness);
+        }
+    }
+    if current != entry || cycle.is_empty() {
+        cycle.extend(path_within(nodes, &members, current, entry));
+    }
+    Some((entry, cycle))
+}
+
+/// Returns the action indices of the shortest known path to a node, where the 0th index is for
+/// the init state. Repetitions of a terminal state are omitted.
+fn action_indices_to(nodes: &[Node], mut index: usize) -> Vec<usize> {
+    let mut action_indices = Vec::with_capacity(nodes[index].depth);
+    loop {
+        match nodes[index].parent {
+            Ok((parent, action_index)) => {
+                action_indices.extend(action_index);
+                index = parent;
+            }
+            Err(init_index) => {
+                action_indices.push(init_index);
+                action_indices.reverse();
+                return action_indices;
+            }
+        }
+    }
+}
+
+/// Returns the action indices of the shortest path between two members of a strongly connected
+/// component, remaining within the component. The path from a node to itself is a cycle.
+fn path_within(
+    nodes: &[Node],
+    members: &HashSet<usize>,
+    source: usize,
+    target: usize,
+) -> Vec<Option<usize>> {
+    let mut predecessors: HashMap<usize, (usize, Option<usize>)> = HashMap::new();
+    let mut pending = VecDeque::from([source]);
+    while let Some(index) = pending.pop_front() {
+        for edge in &nodes[index].successors {
+            if !members.contains(&edge.target) || predecessors.contains_key(&edge.target) {
+                continue;
+            }
+            predecessors.insert(edge.target, (index, edge.action_index));
+            if edge.target == target {
+                let mut action_indices = Vec::new();
+                let mut index = target;
+                loop {
+                    let (prev, action_index) = predecessors[&index];
+                    action_indices.push(action_index);
+                    if prev == source {
+                        action_indices.reverse();
+                        return action_indices;
+                    }
+                    index = prev;
+                }
+            }
+            pending.push_back(edge.target);
+        }
+    }
+    unreachable!("members of a strongly connected component must be connected")
+}
+
+/// Returns the strongly connected components of the subgraph induced by `members` that contain
+/// at least one edge (i.e. those with a cycle), using an iterative version of Tarjan's algorithm.
+fn strongly_connected_components(nodes: &[Node], members: &[usize]) -> Vec<Vec<usize>> {
+    const UNVISITED: usize = usize::MAX;
+    let local: HashMap<usize, usize> = members.iter().enumerate().map(|(l, &i)| (i, l)).collect();
+    let mut visit_order = vec![UNVISITED; members.len()];
+    let mut low_links = vec![0; members.len()];
+    let mut on_stack = vec![false; members.len()];
+    let mut stack = Vec::new();
+    let mut next_visit = 0;
+    let mut components = Vec::new();
+
+    for root in 0..members.len() {
+        if visit_order[root] != UNVISITED {
+            continue;
+        }
+        // Each frame tracks a node and the position of the next successor to consider.
+        let mut frames = vec![(root, 0)];
+        visit_order[root] = next_visit;
+        low_links[root] = next_visit;
+        next_visit += 1;
+        stack.push(root);
+        on_stack[root] = true;
+        while let Some(frame) = frames.last_mut() {
+            let l = frame.0;
+            let edges = &nodes[members[l]].successors;
+            let next = edges[frame.1..]
+                .iter()
+                .position(|edge| local.contains_key(&edge.target));
+            if let Some(offset) = next {
+                let next = local[&edges[frame.1 + offset].target];
+                frame.1 += offset + 1;
+                if visit_order[next] == UNVISITED {
+                    visit_order[next] = next_visit;
+                    low_links[next] = next_visit;
+                    next_visit += 1;
+                    stack.push(next);
+                    on_stack[next] = true;
+                    frames.push((next, 0));
+                } else if on_stack[next] {
+                    low_links[l] = low_links[l].min(visit_order[next]);
+                }
+                continue;
+            }
+            frames.pop();
+            if let Some(&(parent, _)) = frames.last() {
+                low_links[parent] = low_links[parent].min(low_links[l]);
+            }
+            if low_links[l] == visit_order[l] {
+                let mut component = Vec::new();
+                loop {
+                    let member = stack.pop().unwrap();
+                    on_stack[member] = false;
+                    component.push(members[member]);
+                    if member == l {
+                        break;
+                    }
+                }
+                let has_self_loop = || {
+                    let index = members[l];
+                    nodes[index]
+                        .successors
+                        .iter()
+                        .any(|edge| edge.target == index)
+                };
+                if component.len() > 1 || has_self_loop() {
+                    components.push(component);
+                }
+            }
+        }
+    }
+    components
+}
+
+#[cfg(test)]
+mod test {
+    use crate::test_util::dgraph::DGraph;
+    use crate::{Checker, Model, Property};
+
+    fn eventually_odd() -> Property<DGraph> {
+        Property::eventually("odd", |_, s| s % 2 == 1)
+    }
+
+    #[test]
+    fn can_discover_lasso() {
+        let path = DGraph::with_property(eventually_odd())
+            .with_path(vec![0, 2, 4, 6, 2])
+            .with_path(vec![0, 2, 4, 5])
+            .check_cycles()
+            .discovery("odd")
+            .unwrap();
+        assert_eq!(path.loop_start(), Some(1));
+        assert_eq!(path.into_states(), vec![0, 2, 4, 6, 2]);
+    }
+
+    #[test]
+    fn can_discover_self_loop() {
+        let path = DGraph::with_property(eventually_odd())
+            .with_path(vec![0, 2, 2])
+            .with_path(vec![0, 2, 3])
+            .check_cycles()
+            .discovery("odd")
+            .unwrap();
+        assert_eq!(path.loop_start(), Some(1));
+        assert_eq!(path.into_states(), vec![0, 2, 2]);
+    }
+
+    #[test]
+    fn ignores_cycles_that_satisfy_property() {
+        DGraph::with_property(eventually_odd())
+            .with_path(vec![0, 2, 3, 2])
+            .check_cycles()
+            .assert_properties();
+        DGraph::with_property(eventually_odd())
+            .with_path(vec![1, 2, 4, 2])
+            .check_cycles()
+            .assert_properties();
+    }
+
+    #[test]
+    fn dfs_can_discover_lasso() {
+        let checker = DGraph::with_property(eventually_odd())
+            .with_path(vec![0, 2, 4, 2])
+            .checker()
+            .detect_cycles()
+            .spawn_dfs()
+            .join();
+        assert!(checker.is_done());
+        assert_eq!(
+            checker.discovery("odd").unwrap().into_states(),
+            vec![0, 2, 4, 2]
+        );
+        checker.assert_discovery("odd", vec![2, 4, 2]);
+    }
+
+    #[test]
+    fn weak_fairness_discards_cycle_starving_continuously_enabled_action() {
+        let graph = DGraph::with_property(eventually_odd())
+            .with_path(vec![0, 2, 2])
+            .with_path(vec![0, 2, 3]);
+        assert!(graph.clone().check_cycles().discovery("odd").is_some());
+        // Classes can be parameterized by runtime values.
+        let exit = 3;
+        let checker = graph
+            .checker()
+            .detect_cycles()
+            .weak_fairness(move |a| *a == exit)
+            .spawn_bfs()
+            .join();
+        assert_eq!(checker.discovery("odd"), None);
+    }
+
+    #[test]
+    fn strong_fairness_discards_cycle_starving_intermittently_enabled_action() {
+        let graph = DGraph::with_property(eventually_odd())
+            .with_path(vec![0, 2, 4, 2])
+            .with_path(vec![0, 2, 3]);
+        let checker = graph
+            .clone()
+            .checker()
+            .detect_cycles()
+            .weak_fairness(|a| *a == 3)
+            .spawn_bfs()
+            .join();
+        assert_eq!(
+            checker.discovery("odd").unwrap().into_states(),
+            vec![0, 2, 4, 2]
+        );
+        let checker = graph
+            .checker()
+            .detect_cycles()
+            .strong_fairness(|a| *a == 3)
+            .spawn_bfs()
+            .join();
+        assert_eq!(checker.discovery("odd"), None);
+    }
+
+    #[test]
+    fn strong_fairness_avoids_states_enabling_starved_action() {
+        let checker = DGraph::with_property(eventually_odd())
+            .with_path(vec![0, 2, 4, 2])
+            .with_path(vec![0, 2, 6, 2])
+            .with_path(vec![0, 2, 4, 1])
+            .checker()
+            .detect_cycles()
+            .strong_fairness(|a| *a == 1)
+            .spawn_bfs()
+            .join();
+        assert_eq!(
+            checker.discovery("odd").unwrap().into_states(),
+            vec![0, 2, 6, 2]
+        );
+    }
+
+    #[test]
+    fn fair_cycle_visits_witnesses() {
+        // Visits a state in which the constrained action is not enabled.
+        let graph = DGraph::with_property(eventually_odd())
+            .with_path(vec![0, 2, 4, 2])
+            .with_path(vec![0, 2, 6, 2])
+            .with_path(vec![0, 2, 1])
+            .with_path(vec![0, 2, 4, 1]);
+        assert_eq!(
+            graph
+                .clone()
+                .check_cycles()
+                .discovery("odd")
+                .unwrap()
+                .into_states(),
+            vec![0, 2, 4, 2]
+        );
+        let path = graph
+            .checker()
+            .detect_cycles()
+            .weak_fairness(|a| *a == 1)
+            .spawn_bfs()
+            .join()
+            .discovery("odd")
+            .unwrap();
+        assert_eq!(path.loop_start(), Some(1));
+        assert_eq!(path.into_states(), vec![0, 2, 6, 2]);
+
+        // Takes the constrained action.
+        let graph = DGraph::with_property(eventually_odd())
+            .with_path(vec![0, 2, 2])
+            .with_path(vec![0, 2, 4, 2]);
+        assert_eq!(
+            graph
+                .clone()
+                .check_cycles()
+                .discovery("odd")
+                .unwrap()
+                .into_states(),
+            vec![0, 2, 2]
+        );
+        let path = graph
+            .checker()
+            .detect_cycles()
+            .weak_fairness(|a| *a == 4)
+            .spawn_dfs()
+            .join()
+            .discovery("odd")
+            .unwrap();
+        assert_eq!(path.loop_start(), Some(1));
+        assert_eq!(path.into_states(), vec![0, 2, 4, 2]);
+    }
+
+    #[test]
+    fn respects_target_max_depth() {
+        let checker = DGraph::with_property(eventually_odd())
+            .with_path(vec![0, 2, 4, 6, 8, 2])
+            .checker()
+            .detect_cycles()
+            .target_max_depth(3)
+            .spawn_bfs()
+            .join();
+        assert_eq!(checker.discovery("odd"), None);
+    }
+}
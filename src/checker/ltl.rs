@@ -0,0 +1,511 @@
+//! Private module for selective re-export.
+
+use crate::{Model, Property};
+use std::collections::{BTreeSet, HashMap};
+use std::sync::Arc;
+
+/// A [linear temporal logic](https://en.wikipedia.org/wiki/Linear_temporal_logic) formula over
+/// predicates on model states, for use with [`Property::ltl`].
+///
+/// Formulas are evaluated against infinite behaviors, so a path ending in a terminal state is
+/// treated as remaining in that state forever. The "next" operator is intentionally omitted, so
+/// formulas cannot distinguish a behavior from one that repeats a state.
+///
+/// # Example
+///
+/// ```
+/// use stateright::{Ltl, Model, Property};
+/// # struct System;
+/// # impl Model for System {
+/// #     type State = (bool, bool);
+/// #     type Action = ();
+/// #     fn init_states(&self) -> Vec<Self::State> { vec![(false, false)] }
+/// #     fn actions(&self, _: &Self::State, _: &mut Vec<Self::Action>) {}
+/// #     fn next_state(&self, _: &Self::State, _: ()) -> Option<Self::State> { None }
+/// # }
+/// let requested = Ltl::state(|_: &System, (requested, _)| *requested);
+/// let replied = Ltl::state(|_: &System, (_, replied)| *replied);
+/// let property = Property::ltl("every request is followed by a reply", requested.leads_to(replied));
+/// ```
+///
+/// [`Property::ltl`]: crate::Property::ltl
+pub struct Ltl<M: Model>(Arc<Formula<M>>);
+
+type Predicate<M> = Arc<dyn Fn(&M, &<M as Model>::State) -> bool + Send + Sync>;
+
+enum Formula<M: Model> {
+    True,
+    False,
+    State(Predicate<M>),
+    Not(Ltl<M>),
+    And(Ltl<M>, Ltl<M>),
+    Or(Ltl<M>, Ltl<M>),
+    Until(Ltl<M>, Ltl<M>),
+    Release(Ltl<M>, Ltl<M>),
+}
+
+impl<M: Model> Ltl<M> {
+    fn new(formula: Formula<M>) -> Self {
+        Ltl(Arc::new(formula))
+    }
+
+    /// A formula that holds if a predicate holds for the current state.
+    pub fn state(predicate: impl Fn(&M, &M::State) -> bool + Send + Sync + 'static) -> Self {
+        Self::new(Formula::State(Arc::new(predicate)))
+    }
+
+    /// A formula that holds if both formulas hold.
+    pub fn and(self, other: Self) -> Self {
+        Self::new(Formula::And(self, other))
+    }
+
+    /// A formula that holds if either formula holds.
+    pub fn or(self, other: Self) -> Self {
+        Self::new(Formula::Or(self, other))
+    }
+
+    /// A formula that holds if `other` holds whenever this formula holds.
+    pub fn implies(self, other: Self) -> Self {
+        (!self).or(other)
+    }
+
+    /// A formula that holds if this formula holds for the rest of the behavior.
+    pub fn always(self) -> Self {
+        Self::new(Formula::Release(Self::new(Formula::False), self))
+    }
+
+    /// A formula that holds if this formula holds at some point in the rest of the behavior.
+    pub fn eventually(self) -> Self {
+        Self::new(Formula::Until(Self::new(Formula::True), self))
+    }
+
+    /// A formula that holds if `other` eventually holds, with this formula holding until then.
+    pub fn until(self, other: Self) -> Self {
+        Self::new(Formula::Until(self, other))
+    }
+
+    /// A formula that holds if `other` holds until and including the point at which this formula
+    /// holds, which need not ever happen.
+    pub fn release(self, other: Self) -> Self {
+        Self::new(Formula::Release(self, other))
+    }
+
+    /// A formula that holds if this formula holds infinitely often.
+    pub fn always_eventually(self) -> Self {
+        self.eventually().always()
+    }
+
+    /// A formula that holds if this formula eventually holds forever.
+    pub fn eventually_always(self) -> Self {
+        self.always().eventually()
+    }
+
+    /// A formula that holds if `other` holds at some point after (or when) this formula holds,
+    /// i.e. "this formula leads to `other`".
+    pub fn leads_to(self, other: Self) -> Self {
+        self.implies(other.eventually()).always()
+    }
+}
+
+impl<M: Model> Clone for Ltl<M> {
+    fn clone(&self) -> Self {
+        Ltl(Arc::clone(&self.0))
+    }
+}
+
+impl<M: Model> std::ops::Not for Ltl<M> {
+    type Output = Self;
+
+    fn not(self) -> Self {
+        Self::new(Formula::Not(self))
+    }
+}
+
+/// A subformula of a formula in negation normal form, referring to other subformulas by index.
+#[derive(Clone, Copy, Eq, Hash, PartialEq)]
+enum Nnf {
+    True,
+    False,
+    /// An atomic proposition (by index) and whether it should hold.
+    Literal(usize, bool),
+    And(usize, usize),
+    Or(usize, usize),
+    Until(usize, usize),
+    Release(usize, usize),
+}
+
+/// A node of a [`Monitor`].
+pub(crate) struct MonitorNode {
+    /// The atomic propositions (by index) that a state read by this node must satisfy, and
+    /// whether each should hold.
+    literals: Vec<(usize, bool)>,
+    /// The nodes that can read the next state.
+    pub(crate) successors: Vec<usize>,
+    /// The acceptance sets that include this node.
+    pub(crate) accepting: super::liveness::FairnessBits,
+}
+
+/// A generalized [Büchi automaton](https://en.wikipedia.org/wiki/B%C3%BCchi_automaton) that
+/// reads states of a model and accepts the behaviors that violate a formula. A run is accepting
+/// if it visits each acceptance set infinitely often.
+///
+/// The automaton is built using the tableau construction of Gerth, Peled, Vardi and Wolper in
+/// "Simple On-the-fly Automatic Verification of Linear Temporal Logic".
+pub(crate) struct Monitor<M: Model> {
+    atoms: Vec<Predicate<M>>,
+    pub(crate) nodes: Vec<MonitorNode>,
+    /// The nodes that can read an initial state.
+    pub(crate) initial: Vec<usize>,
+    pub(crate) acceptance_count: usize,
+}
+
+impl<M: Model> Monitor<M> {
+    /// Builds a monitor that accepts the behaviors violating a formula.
+    pub(crate) fn violations_of(formula: &Ltl<M>) -> Self {
+        let mut builder = NnfBuilder {
+            atoms: Vec::new(),
+            atom_ids: HashMap::new(),
+            subformulas: Vec::new(),
+            subformula_ids: HashMap::new(),
+        };
+        let root = builder.build(formula, true);
+        let subformulas = builder.subformulas;
+        let subformula_ids = builder.subformula_ids;
+
+        // Each tableau node tracks the nodes it can follow (`incoming`), the subformulas it has yet
+        // to process (`new`), those it has processed (`old`), and those that must hold for the
+        // next state (`next`).
+        const INIT: usize = usize::MAX;
+        #[derive(Clone)]
+        struct Tableau {
+            incoming: BTreeSet<usize>,
+            new: BTreeSet<usize>,
+            old: BTreeSet<usize>,
+            next: BTreeSet<usize>,
+        }
+        let mut nodes: Vec<Tableau> = Vec::new();
+        let mut pending = vec![Tableau {
+            incoming: BTreeSet::from([INIT]),
+            new: BTreeSet::from([root]),
+            old: BTreeSet::new(),
+            next: BTreeSet::new(),
+        }];
+        while let Some(mut tableau) = pending.pop() {
+            let Some(id) = tableau.new.pop_first() else {
+                if let Some(node) = nodes
+                    .iter_mut()
+                    .find(|n| n.old == tableau.old && n.next == tableau.next)
+                {
+                    node.incoming.extend(tableau.incoming);
+                } else {
+                    pending.push(Tableau {
+                        incoming: BTreeSet::from([nodes.len()]),
+                        new: tableau.next.clone(),
+                        old: BTreeSet::new(),
+                        next: BTreeSet::new(),
+                    });
+                    nodes.push(tableau);
+                }
+                continue;
+            };
+            if !tableau.old.insert(id) {
+                pending.push(tableau);
+                continue;
+            }
+            let mut extend = |mut tableau: Tableau, new: &[usize], next: Option<usize>| {
+                for id in new {
+                    if !tableau.old.contains(id) {
+                        tableau.new.insert(*id);
+                    }
+                }
+                tableau.next.extend(next);
+                pending.push(tableau);
+            };
+            match subformulas[id] {
+                Nnf::False => {}
+                Nnf::True => extend(tableau, &[], None),
+                Nnf::Literal(atom, holds) => {
+                    let contradiction = subformula_ids.get(&Nnf::Literal(atom, !holds));
+                    if !contradiction.is_some_and(|id| tableau.old.contains(id)) {
+                        extend(tableau, &[], None);
+                    }
+                }
+                Nnf::And(lhs, rhs) => extend(tableau, &[lhs, rhs], None),
+                Nnf::Or(lhs, rhs) => {
+                    extend(tableau.clone(), &[lhs], None);
+                    extend(tableau, &[rhs], None);
+                }
+                Nnf::Until(lhs, rhs) => {
+                    extend(tableau.clone(), &[lhs], Some(id));
+                    extend(tableau, &[rhs], None);
+                }
+                Nnf::Release(lhs, rhs) => {
+                    extend(tableau.clone(), &[rhs], Some(id));
+                    extend(tableau, &[lhs, rhs], None);
+                }
+            }
+        }
+
+        // A run satisfying `lhs U rhs` must not postpone `rhs` forever, so each "until" subformula
+        // defines an acceptance set of the nodes that either do not require it or satisfy `rhs`.
+        let untils: Vec<(usize, usize)> = subformulas
+            .iter()
+            .enumerate()
+            .filter_map(|(id, f)| match f {
+                Nnf::Until(_, rhs) => Some((id, *rhs)),
+                _ => None,
+            })
+            .collect();
+        let monitor_nodes = nodes
+            .iter()
+            .enumerate()
+            .map(|(i, node)| MonitorNode {
+                literals: node
+                    .old
+                    .iter()
+                    .filter_map(|id| match subformulas[*id] {
+                        Nnf::Literal(atom, holds) => Some((atom, holds)),
+                        _ => None,
+                    })
+                    .collect(),
+                successors: (0..nodes.len())
+                    .filter(|&j| nodes[j].incoming.contains(&i))
+                    .collect(),
+                accepting: untils
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, (until, rhs))| !node.old.contains(until) || node.old.contains(rhs))
+                    .map(|(i, _)| i)
+                    .collect(),
+            })
+            .collect();
+        Monitor {
+            atoms: builder.atoms,
+            nodes: monitor_nodes,
+            initial: (0..nodes.len())
+                .filter(|&i| nodes[i].incoming.contains(&INIT))
+                .collect(),
+            acceptance_count: untils.len(),
+        }
+    }
+
+    /// Builds a monitor that accepts the counterexamples of an `eventually` or temporal logic
+    /// property, or returns `None` for other properties.
+    pub(crate) fn for_property(property: &Property<M>) -> Option<Self> {
+        match &property.predicate {
+            crate::Predicate::Eventually(condition) => Some(Self::violations_of(
+                &Ltl::new(Formula::State(Arc::clone(condition))).eventually(),
+            )),
+            crate::Predicate::Ltl(formula) => Some(Self::violations_of(formula)),
+            crate::Predicate::Always(_)
+            | crate::Predicate::Sometimes(_)
+            | crate::Predicate::Transition(_) => None,
+        }
+    }
+
+    /// Evaluates the atomic propositions for a state.
+    pub(crate) fn evaluate(&self, model: &M, state: &M::State) -> Vec<bool> {
+        self.atoms.iter().map(|atom| atom(model, state)).collect()
+    }
+
+    /// Indicates whether a node can read a state, given the state's atomic propositions.
+    pub(crate) fn admits(&self, node: usize, atoms: &[bool]) -> bool {
+        self.nodes[node]
+            .literals
+            .iter()
+            .all(|&(atom, holds)| atoms[atom] == holds)
+    }
+}
+
+/// Converts a formula to negation normal form, in which negation is only applied to atomic
+/// propositions, while identifying equivalent subformulas.
+struct NnfBuilder<M: Model> {
+    atoms: Vec<Predicate<M>>,
+    atom_ids: HashMap<*const Formula<M>, usize>,
+    subformulas: Vec<Nnf>,
+    subformula_ids: HashMap<Nnf, usize>,
+}
+
+impl<M: Model> NnfBuilder<M> {
+    fn build(&mut self, formula: &Ltl<M>, negate: bool) -> usize {
+        let nnf = match &*formula.0 {
+            Formula::True if negate => Nnf::False,
+            Formula::True => Nnf::True,
+            Formula::False if negate => Nnf::True,
+            Formula::False => Nnf::False,
+            Formula::State(predicate) => {
+                let next_id = self.atoms.len();
+                let atom = *self
+                    .atom_ids
+                    .entry(Arc::as_ptr(&formula.0))
+                    .or_insert(next_id);
+                if atom == next_id {
+                    self.atoms.push(Arc::clone(predicate));
+                }
+                Nnf::Literal(atom, !negate)
+            }
+            Formula::Not(f) => return self.build(f, !negate),
+            Formula::And(lhs, rhs) | Formula::Or(lhs, rhs) => {
+                let (lhs, rhs) = (self.build(lhs, negate), self.build(rhs, negate));
+                if matches!(&*formula.0, Formula::And(..)) != negate {
+                    Nnf::And(lhs, rhs)
+                } else {
+                    Nnf::Or(lhs, rhs)
+                }
+            }
+            Formula::Until(lhs, rhs) | Formula::Release(lhs, rhs) => {
+                let (lhs, rhs) = (self.build(lhs, negate), self.build(rhs, negate));
+                if matches!(&*formula.0, Formula::Until(..)) != negate {
+                    Nnf::Until(lhs, rhs)
+                } else {
+                    Nnf::Release(lhs, rhs)
+                }
+            }
+        };
+        let next_id = self.subformulas.len();
+        let id = *self.subformula_ids.entry(nnf).or_insert(next_id);
+        if id == next_id {
+            self.subformulas.push(nnf);
+        }
+        id
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_util::dgraph::DGraph;
+    use crate::{Checker, Model};
+
+    fn is(n: u8) -> Ltl<DGraph> {
+        match n {
+            1 => Ltl::state(|_, s| *s == 1),
+            2 => Ltl::state(|_, s| *s == 2),
+            4 => Ltl::state(|_, s| *s == 4),
+            _ => unreachable!(),
+        }
+    }
+
+    fn is_even() -> Ltl<DGraph> {
+        Ltl::state(|_, s| s % 2 == 0)
+    }
+
+    #[test]
+    fn monitor_for_eventually_has_single_node() {
+        let monitor = Monitor::violations_of(&is(1).eventually());
+        assert_eq!(monitor.nodes.len(), 1);
+        assert_eq!(monitor.initial, vec![0]);
+        assert_eq!(monitor.nodes[0].successors, vec![0]);
+        assert_eq!(monitor.acceptance_count, 0);
+    }
+
+    #[test]
+    fn can_check_leads_to() {
+        let property = || Property::ltl("requests get replies", is(1).leads_to(is(2)));
+        DGraph::with_property(property())
+            .with_path(vec![0, 1, 2, 0])
+            .check_cycles()
+            .assert_properties();
+
+        let checker = DGraph::with_property(property())
+            .with_path(vec![0, 1, 2, 0])
+            .with_path(vec![0, 1, 3, 3])
+            .check_cycles();
+        let path = checker.discovery("requests get replies").unwrap();
+        assert_eq!(path.loop_start(), Some(2));
+        assert_eq!(path.into_states(), vec![0, 1, 3, 3]);
+        checker.assert_discovery("requests get replies", vec![1, 3, 3]);
+    }
+
+    #[test]
+    fn can_check_always_eventually() {
+        let property = || Property::ltl("infinitely often", is(1).always_eventually());
+        DGraph::with_property(property())
+            .with_path(vec![0, 1, 0])
+            .check_cycles()
+            .assert_properties();
+
+        let path = DGraph::with_property(property())
+            .with_path(vec![0, 1, 0])
+            .with_path(vec![0, 2, 2])
+            .checker()
+            .detect_cycles()
+            .spawn_dfs()
+            .join()
+            .discovery("infinitely often")
+            .unwrap();
+        assert_eq!(path.into_states(), vec![0, 2, 2]);
+    }
+
+    #[test]
+    fn can_check_until() {
+        let property = || Property::ltl("even until 4", is_even().until(is(4)));
+        DGraph::with_property(property())
+            .with_path(vec![0, 2, 4, 5])
+            .check_cycles()
+            .assert_properties();
+
+        // The right-hand side must eventually hold.
+        let path = DGraph::with_property(property())
+            .with_path(vec![0, 2, 4])
+            .with_path(vec![0, 2, 2])
+            .check_cycles()
+            .discovery("even until 4")
+            .unwrap();
+        assert_eq!(path.loop_start(), Some(1));
+        assert_eq!(path.into_states(), vec![0, 2, 2]);
+
+        // The left-hand side must hold until then. A terminal state repeats forever.
+        let path = DGraph::with_property(property())
+            .with_path(vec![0, 2, 4])
+            .with_path(vec![0, 3])
+            .check_cycles()
+            .discovery("even until 4")
+            .unwrap();
+        assert_eq!(path.loop_start(), None);
+        assert_eq!(path.into_states(), vec![0, 3]);
+    }
+
+    #[test]
+    fn reports_loop_start_of_product() {
+        // The monitor only begins accepting after visiting 2, so the loop begins at the second
+        // visit to 1 rather than the first.
+        let checker = DGraph::with_property(Property::ltl("2 leads to 4", is(2).leads_to(is(4))))
+            .with_path(vec![0, 1, 2, 1])
+            .check_cycles();
+        let path = checker.discovery("2 leads to 4").unwrap();
+        assert_eq!(path.loop_start(), Some(3));
+        assert_eq!(path.into_states(), vec![0, 1, 2, 1, 2, 1]);
+    }
+
+    #[test]
+    fn is_expected_eventually() {
+        let property = Property::ltl("infinitely often", is(1).always_eventually());
+        assert_eq!(property.name, "infinitely often");
+        assert_eq!(property.expectation(), crate::Expectation::Eventually);
+        assert!(property.discovery_is_failure());
+    }
+
+    #[test]
+    #[should_panic(expected = "requires CheckerBuilder::detect_cycles")]
+    fn requires_detecting_cycles() {
+        let _ = DGraph::with_property(Property::ltl("2 leads to 4", is(2).leads_to(is(4))))
+            .with_path(vec![0, 1, 2, 1])
+            .check();
+    }
+
+    #[test]
+    fn simulation_can_check_ltl() {
+        let checker =
+            DGraph::with_property(Property::ltl("infinitely often", is(1).always_eventually()))
+                .with_path(vec![0, 2, 2])
+                .checker()
+                .target_state_count(100)
+                .spawn_simulation(0, crate::UniformChooser)
+                .join();
+        assert_eq!(
+            checker.discovery("infinitely often").unwrap().into_states(),
+            vec![0, 2, 2]
+        );
+    }
+}
@@ -1,35 +1,29 @@
 //! Private module for selective re-export.
 
-use crate::checker::{Checker, EventuallyBits, Expectation, Path};
+use crate::checker::disk::{FingerprintSet, ScratchDir};
+use crate::checker::{check_transitions, Checker, EventuallyBits, Path, Predicate};
 use crate::job_market::JobBroker;
-use crate::{
-    fingerprint, CheckerBuilder, CheckerVisitor, ControlFlow, Fingerprint, Model, Property,
-};
-use dashmap::mapref::entry::Entry;
+use crate::{fingerprint, CheckerBuilder, CheckerVisitor, ControlFlow, Fingerprint, Model};
 use dashmap::DashMap;
-use nohash_hasher::NoHashHasher;
 use std::collections::{HashMap, VecDeque};
-use std::hash::{BuildHasherDefault, Hash};
+use std::hash::Hash;
 use std::num::NonZeroUsize;
 use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
 use std::thread::JoinHandle;
 use std::time::SystemTime;
 
-// While this file is currently quite similar to dfs.rs, a refactoring to lift shared
-// behavior is being postponed until DPOR is implemented.
-
 pub(crate) struct OnDemandChecker<M: Model> {
     // Immutable state.
     model: Arc<M>,
     handles: Vec<std::thread::JoinHandle<()>>,
+    shrink_discoveries: bool,
 
     // Mutable state.
     job_broker: JobBroker<Job<M::State>>,
     state_count: Arc<AtomicUsize>,
     max_depth: Arc<AtomicUsize>,
-    generated:
-        Arc<DashMap<Fingerprint, Option<Fingerprint>, BuildHasherDefault<NoHashHasher<u64>>>>,
+    generated: Arc<FingerprintSet>,
     // In the original fingerprint-based Path construction, the value type of `discovery` was a
     // single fingerprint since the path could be reconstructed in reverse using the parent
     // relationships in `generated`. However, with action indices encoding, this is not possible,
@@ -50,6 +44,7 @@ where
         M::Action: Clone + PartialEq,
     {
         let model = Arc::new(options.model);
+        let shrink_discoveries = options.shrink_discoveries;
         let target_state_count = options.target_state_count;
         let thread_count = options.thread_count;
         let visitor = Arc::new(options.visitor);
@@ -66,21 +61,18 @@ where
             .collect();
         let state_count = Arc::new(AtomicUsize::new(init_states.len()));
         let max_depth = Arc::new(AtomicUsize::new(0));
+        let scratch_dir = options.disk.as_ref().map(|disk| ScratchDir::new(&disk.dir));
         let generated = Arc::new({
-            let generated = DashMap::default();
+            let generated = FingerprintSet::new(scratch_dir.as_ref().zip(options.disk.as_ref()));
             for s in &init_states {
-                generated.insert(fingerprint(s), None);
+                generated.insert(fingerprint(s));
             }
             generated
         });
         let ebits = {
             let mut ebits = EventuallyBits::new();
             for (i, p) in model.properties().iter().enumerate() {
-                if let Property {
-                    expectation: Expectation::Eventually,
-                    ..
-                } = p
-                {
+                if let Predicate::Eventually(_) = p.predicate {
                     ebits.insert(i);
                 }
             }
@@ -142,6 +134,18 @@ where
                                 );
                             }
 
+                            if !wait_for_fingerprints {
+                                // Pausing resumes waiting for someone to ask us to do work.
+                                for control_flow in controlflow_receiver.try_iter() {
+                                    match control_flow {
+                                        ControlFlow::Pause => wait_for_fingerprints = true,
+                                        ControlFlow::RunToCompletion => {
+                                            wait_for_fingerprints = false
+                                        }
+                                        ControlFlow::CheckFingerprint(_) => {}
+                                    }
+                                }
+                            }
                             if wait_for_fingerprints {
                                 // Step 0: wait for someone to ask us to do work
                                 loop {
@@ -174,6 +178,7 @@ where
                                                 wait_for_fingerprints = false;
                                                 break;
                                             }
+                                            ControlFlow::Pause => {}
                                         }
                                     } else {
                                         // no commands left so we can finish
@@ -238,6 +243,7 @@ where
         OnDemandChecker {
             model,
             handles,
+            shrink_discoveries,
             job_broker,
             state_count,
             max_depth,
@@ -251,11 +257,7 @@ where
     fn check_block(
         model: &M,
         state_count: &AtomicUsize,
-        generated: &DashMap<
-            Fingerprint,
-            Option<Fingerprint>,
-            BuildHasherDefault<NoHashHasher<u64>>,
-        >,
+        generated: &FingerprintSet,
         pending: &mut VecDeque<Job<M::State>>,
         discoveries: &DashMap<&'static str, Vec<usize>>,
         visitor: &Option<Box<dyn CheckerVisitor<M> + Send + Sync>>,
@@ -266,6 +268,9 @@ where
         M::Action: Clone + PartialEq,
     {
         let properties = model.properties();
+        let has_transitions = properties
+            .iter()
+            .any(|p| matches!(p.predicate, Predicate::Transition(_)));
 
         let mut current_max_depth = global_max_depth.load(Ordering::Relaxed);
         let mut actions = Vec::new();
@@ -300,17 +305,13 @@ where
             let mut is_awaiting_discoveries = false;
             for (i, property) in properties.iter().enumerate() {
                 if discoveries.contains_key(property.name)
-                    && (property.expectation == Expectation::Eventually)
+                    && matches!(property.predicate, Predicate::Eventually(_))
                     && !ebits.contains(i)
                 {
                     continue;
                 }
-                match property {
-                    Property {
-                        expectation: Expectation::Always,
-                        condition: always,
-                        ..
-                    } => {
+                match &property.predicate {
+                    Predicate::Always(always) => {
                         if !always(model, &state) {
                             // Races other threads, but that's fine.
                             discoveries.insert(property.name, action_path.clone());
@@ -318,11 +319,7 @@ where
                             is_awaiting_discoveries = true;
                         }
                     }
-                    Property {
-                        expectation: Expectation::Sometimes,
-                        condition: sometimes,
-                        ..
-                    } => {
+                    Predicate::Sometimes(sometimes) => {
                         if sometimes(model, &state) {
                             // Races other threads, but that's fine.
                             discoveries.insert(property.name, action_path.clone());
@@ -330,11 +327,7 @@ where
                             is_awaiting_discoveries = true;
                         }
                     }
-                    Property {
-                        expectation: Expectation::Eventually,
-                        condition: eventually,
-                        ..
-                    } => {
+                    Predicate::Eventually(eventually) => {
                         // The checker early exits after finding discoveries for every property,
                         // and "eventually" property discoveries are only identified at terminal
                         // states, so if we are here it means we are still awaiting a corresponding
@@ -345,6 +338,15 @@ where
                             ebits.remove(i);
                         }
                     }
+                    Predicate::Transition(_) => {
+                        // Transitions are checked as steps are taken.
+                        if !discoveries.contains_key(property.name) {
+                            is_awaiting_discoveries = true;
+                        }
+                    }
+                    Predicate::Ltl(_) => {
+                        // Temporal logic properties are not checked by this checker.
+                    }
                 }
             }
             if !is_awaiting_discoveries {
@@ -355,6 +357,7 @@ where
             let mut is_terminal = true;
             model.actions(&state, &mut actions);
             for (action_idx, action) in actions.drain(..).enumerate() {
+                let transition_action = has_transitions.then(|| action.clone());
                 let next_state = model.next_state(&state, action);
                 if next_state.is_none() {
                     continue;
@@ -370,6 +373,20 @@ where
                 }
                 state_count.fetch_add(1, Ordering::Relaxed);
 
+                if let Some(action) = &transition_action {
+                    check_transitions(
+                        model,
+                        &properties,
+                        discoveries,
+                        &state,
+                        action,
+                        &next_state,
+                        &action_path,
+                        action_idx,
+                        false,
+                    );
+                }
+
                 // Skip if already generated.
                 //
                 // FIXME: we should really include ebits in the fingerprint here --
@@ -378,9 +395,7 @@ where
                 // property held on the path leading to the first visit as meaning
                 // that it holds in the path leading to the second visit -- another
                 // possible false-negative.
-                if let Entry::Vacant(next_entry) = generated.entry(next_fp) {
-                    next_entry.insert(Some(state_fp));
-                } else {
+                if !generated.insert(next_fp) {
                     // FIXME: arriving at an already-known state may be a loop (in which case it
                     // could, in a fancier implementation, be considered a terminal state for
                     // purposes of eventually-property checking) but it might also be a join in
@@ -435,6 +450,10 @@ where
         let _ = self.control_flow.send(ControlFlow::RunToCompletion);
     }
 
+    fn pause(&self) {
+        let _ = self.control_flow.send(ControlFlow::Pause);
+    }
+
     fn state_count(&self) -> usize {
         self.state_count.load(Ordering::Relaxed)
     }
@@ -467,6 +486,10 @@ where
         std::mem::take(&mut self.handles)
     }
 
+    fn shrinks_discoveries(&self) -> bool {
+        self.shrink_discoveries
+    }
+
     fn is_done(&self) -> bool {
         self.job_broker.is_closed() || self.discoveries.len() == self.model.properties().len()
     }
@@ -538,4 +561,29 @@ mod test {
             vec![Guess::IncreaseY; 27],
         );
     }
+
+    #[test]
+    fn can_pause_and_resume() {
+        use std::time::Duration;
+
+        // Visiting is slowed so that checking does not complete before pausing.
+        let checker = LinearEquation { a: 2, b: 4, c: 7 }
+            .checker()
+            .visitor(|_: Path<_, _>| std::thread::sleep(Duration::from_micros(50)))
+            .spawn_on_demand();
+        checker.run_to_completion();
+        std::thread::sleep(Duration::from_millis(10));
+        checker.pause();
+        // The block being checked when pausing is completed.
+        std::thread::sleep(Duration::from_millis(500));
+        let paused_count = checker.state_count();
+        std::thread::sleep(Duration::from_millis(200));
+        assert_eq!(checker.state_count(), paused_count);
+        assert!(!checker.is_done());
+
+        checker.run_to_completion();
+        while checker.state_count() == paused_count {
+            std::thread::sleep(Duration::from_millis(10));
+        }
+    }
 }
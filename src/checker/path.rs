@@ -1,6 +1,6 @@
 //! Private module for selective re-export.
 
-use crate::Model;
+use crate::{Model, Predicate};
 use std::collections::VecDeque;
 use std::fmt::{Debug, Display, Formatter};
 use std::hash::Hash;
@@ -10,10 +10,16 @@ use std::hash::Hash;
 /// You can convert to a `Vec<_>` with [`path.into_vec()`]. If you only need the actions, then use
 /// [`path.into_actions()`].
 ///
+/// A path can also be "lasso" shaped, which is how an infinite behavior that never satisfies an
+/// [`eventually`] property is reported: the last action of such a path leads back to an earlier
+/// state, and [`path.loop_start()`] indicates the index of that state.
+///
+/// [`eventually`]: crate::Property::eventually
 /// [`path.into_vec()`]: Path::into_vec
 /// [`path.into_actions()`]: Path::into_actions
+/// [`path.loop_start()`]: Path::loop_start
 #[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
-pub struct Path<State, Action>(Vec<(State, Option<Action>)>);
+pub struct Path<State, Action>(Vec<(State, Option<Action>)>, Option<usize>);
 
 impl<State, Action> Path<State, Action> {
     /// Constructs a path from a model and a sequence of action indices (the 0th index is for the
@@ -84,7 +90,7 @@ Available action indices (none of which match): {:?}"#,
             }
         }
         output.push((last_state, None));
-        Path(output)
+        Path(output, None)
     }
 
     /// Constructs a path from a model, initial state, and a sequence of actions. Panics for inputs
@@ -114,7 +120,102 @@ Available action indices (none of which match): {:?}"#,
         }
         output.push((prev_state, None));
 
-        Some(Path(output))
+        Some(Path(output, None))
+    }
+
+    /// Searches for a shorter path that is still a discovery for the named property, by removing
+    /// increasingly small runs of steps and replaying the remaining actions (as with
+    /// [`Path::from_actions`]). Replays end as soon as the property's discovery condition holds
+    /// and must stay within the [model boundary].
+    ///
+    /// Only "always" and "sometimes" discoveries are shrunk. Other paths, such as lasso shaped
+    /// counterexamples for "eventually" properties, are returned unchanged. The result is not
+    /// necessarily the shortest discovery, but no single step can be removed from it.
+    ///
+    /// [model boundary]: crate::Model::within_boundary
+    pub fn shrink<M>(&self, model: &M, name: &'static str) -> Self
+    where
+        M: Model<State = State, Action = Action>,
+        State: Clone + PartialEq,
+        Action: Clone + PartialEq,
+    {
+        let property = model.property(name);
+        let is_shrinkable = matches!(
+            property.predicate,
+            Predicate::Always(_) | Predicate::Sometimes(_) | Predicate::Transition(_)
+        );
+        if !is_shrinkable || self.1.is_some() {
+            return self.clone();
+        }
+        let is_discovery = |last_state: Option<(&State, &Action)>, state: &State| -> bool {
+            match &property.predicate {
+                Predicate::Always(always) => !always(model, state),
+                Predicate::Sometimes(sometimes) => sometimes(model, state),
+                Predicate::Transition(transition) => {
+                    last_state.is_some_and(|(last_state, action)| {
+                        !transition(model, last_state, action, state)
+                    })
+                }
+                Predicate::Eventually(_) | Predicate::Ltl(_) => false,
+            }
+        };
+
+        // Returns the steps of the shortest prefix of a replay that is a discovery, if any.
+        let init_state = &self.0[0].0;
+        let replay = |actions: &[Action]| -> Option<Vec<(State, Option<Action>)>> {
+            let mut steps = Vec::with_capacity(actions.len() + 1);
+            let mut state = init_state.clone();
+            if is_discovery(None, &state) {
+                steps.push((state, None));
+                return Some(steps);
+            }
+            for action in actions {
+                let (action, next_state) = model
+                    .next_steps(&state)
+                    .into_iter()
+                    .find(|(a, _)| a == action)?;
+                if !model.within_boundary(&next_state) {
+                    return None;
+                }
+                let is_done = is_discovery(Some((&state, &action)), &next_state);
+                steps.push((state, Some(action)));
+                state = next_state;
+                if is_done {
+                    steps.push((state, None));
+                    return Some(steps);
+                }
+            }
+            None
+        };
+
+        let Some(mut steps) = replay(&self.clone().into_actions()) else {
+            return self.clone(); // Not reproducible, e.g. if the discovery was for another path.
+        };
+        let mut run_len = (steps.len() / 2).max(1);
+        loop {
+            let mut removed_any = false;
+            let mut i = 0;
+            while i + 1 < steps.len() {
+                let end = (i + run_len).min(steps.len() - 1);
+                let actions: Vec<Action> = steps[..i]
+                    .iter()
+                    .chain(&steps[end..])
+                    .filter_map(|(_, a)| a.clone())
+                    .collect();
+                match replay(&actions) {
+                    Some(shorter) => {
+                        steps = shorter;
+                        removed_any = true;
+                    }
+                    None => i = end,
+                }
+            }
+            if run_len > 1 {
+                run_len /= 2;
+            } else if !removed_any {
+                return Path(steps, None);
+            }
+        }
     }
 
     /// Determines the final state associated with a particular action indices path.
@@ -142,6 +243,19 @@ Available action indices (none of which match): {:?}"#,
         &self.0.last().unwrap().0
     }
 
+    /// Indicates the index of the state to which the last action of a "lasso" shaped path loops
+    /// back, or [`None`] if the path does not end in a cycle. The states from that index onward
+    /// repeat forever.
+    pub fn loop_start(&self) -> Option<usize> {
+        self.1
+    }
+
+    /// Marks the path as "lasso" shaped, looping back to the state at index `loop_start`, which is
+    /// only done for counterexamples to liveness properties that end in a cycle.
+    pub(crate) fn with_loop_start(self, loop_start: Option<usize>) -> Self {
+        Path(self.0, loop_start)
+    }
+
     /// Extracts the states.
     pub fn into_states(self) -> Vec<State> {
         self.0.into_iter().map(|(s, _a)| s).collect()
@@ -207,6 +321,9 @@ where
                 writeln!(f, "- {action:?}")?;
             }
         }
+        if let Some(loop_start) = self.1 {
+            writeln!(f, "Loops back to state {loop_start}.")?;
+        }
         Ok(())
     }
 }
@@ -241,4 +358,47 @@ mod test {
         });
         assert!(err_result.is_err());
     }
+
+    #[test]
+    fn can_mark_loop_back_to_an_earlier_state() {
+        let model: fn(Option<&_>, &mut Vec<_>) = |prev_state, next_states| match prev_state {
+            None => next_states.push(0),
+            Some(0) => next_states.push(1),
+            Some(_) => next_states.push(0),
+        };
+        let path = Path::from_action_indices(&model, VecDeque::from_iter(vec![0, 0, 0]));
+        assert_eq!(path.loop_start(), None);
+        let path = path.with_loop_start(Some(0));
+        assert_eq!(path.loop_start(), Some(0));
+        assert_eq!(path.clone().into_states(), vec![0, 1, 0]);
+        assert_eq!(
+            format!("{path}"),
+            "Path[2]:\n- 1\n- 0\nLoops back to state 0.\n"
+        );
+        assert_eq!(
+            Path::from_actions(&model, 0, &[1, 0]).map(|p| p.with_loop_start(Some(0))),
+            Some(path)
+        );
+    }
+
+    #[test]
+    fn shrinks_discoveries_by_removing_steps() {
+        use crate::test_util::dgraph::DGraph;
+        use crate::Property;
+
+        // 0 → 1 → 2 → 3 → 4 → 9 with shortcuts 1 → 3 → 9, and a detour 2 → 5 → 2.
+        let model = DGraph::with_properties(vec![
+            Property::always("not 9", |_, s| *s != 9),
+            Property::sometimes("5", |_, s| *s == 5),
+            Property::eventually("9", |_, s| *s == 9),
+        ])
+        .with_path(vec![0, 1, 2, 5, 2, 3, 4, 9])
+        .with_path(vec![1, 3, 9]);
+        let path = Path::from_actions(&model, 0, &[1, 2, 5, 2, 3, 4, 9]).unwrap();
+        assert_eq!(path.shrink(&model, "not 9").into_states(), vec![0, 1, 3, 9]);
+        // Replays end once the discovery condition holds.
+        assert_eq!(path.shrink(&model, "5").into_states(), vec![0, 1, 2, 5]);
+        // Other discoveries are unchanged.
+        assert_eq!(path.shrink(&model, "9"), path);
+    }
 }
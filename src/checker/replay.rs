@@ -0,0 +1,299 @@
+//! Private module for selective re-export.
+
+use crate::checker::Path;
+use crate::{Checker, CheckerBuilder, Model};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter, Write};
+use std::thread::JoinHandle;
+
+/// The contents of a file written by [`Checker::save_discoveries`].
+#[derive(Debug, Deserialize, PartialEq, Serialize)]
+pub(crate) struct SavedDiscoveries {
+    pub(crate) state_count: usize,
+    pub(crate) unique_state_count: usize,
+    pub(crate) max_depth: usize,
+    /// Keyed by property name.
+    pub(crate) discoveries: BTreeMap<String, SavedDiscovery>,
+}
+
+#[derive(Debug, Deserialize, PartialEq, Serialize)]
+pub(crate) struct SavedDiscovery {
+    /// Encoded via [`Path::encode`].
+    pub(crate) path: String,
+    #[serde(default)]
+    pub(crate) loop_start: Option<usize>,
+    #[serde(default)]
+    pub(crate) seed: Option<u64>,
+}
+
+impl SavedDiscoveries {
+    pub(crate) fn of<M, C>(checker: &C) -> Self
+    where
+        M: Model,
+        M::State: Clone + PartialEq,
+        M::Action: Clone + PartialEq,
+        C: Checker<M> + ?Sized,
+    {
+        let discoveries = checker
+            .discoveries()
+            .into_iter()
+            .map(|(name, path)| {
+                let discovery = SavedDiscovery {
+                    path: path.encode(checker.model()),
+                    loop_start: path.loop_start(),
+                    seed: checker.discovery_seed(name),
+                };
+                (name.to_string(), discovery)
+            })
+            .collect();
+        SavedDiscoveries {
+            state_count: checker.state_count(),
+            unique_state_count: checker.unique_state_count(),
+            max_depth: checker.max_depth(),
+            discoveries,
+        }
+    }
+
+    pub(crate) fn save(&self, path: &std::path::Path) -> io::Result<()> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        serde_json::to_writer_pretty(&mut writer, self)?;
+        writeln!(writer)?;
+        writer.flush()
+    }
+
+    pub(crate) fn load(path: &std::path::Path) -> io::Result<Self> {
+        let reader = BufReader::new(File::open(path)?);
+        Ok(serde_json::from_reader(reader)?)
+    }
+}
+
+/// A checker that has already finished, whose discoveries were saved by another checker. See
+/// [`CheckerBuilder::load_discoveries`].
+pub(crate) struct ReplayChecker<M: Model> {
+    model: M,
+    state_count: usize,
+    unique_state_count: usize,
+    max_depth: usize,
+    discoveries: HashMap<&'static str, Path<M::State, M::Action>>,
+    seeds: HashMap<&'static str, u64>,
+    shrink_discoveries: bool,
+}
+
+impl<M> ReplayChecker<M>
+where
+    M: Model,
+    M::State: Clone + PartialEq,
+    M::Action: Clone + PartialEq,
+{
+    /// Replays saved discoveries against the model, failing with [`io::ErrorKind::InvalidData`]
+    /// if a discovery does not fit the model, such as one saved for a different model.
+    pub(crate) fn load(options: CheckerBuilder<M>, path: &std::path::Path) -> io::Result<Self> {
+        let saved = SavedDiscoveries::load(path)?;
+        let model = options.model;
+        let shrink_discoveries = options.shrink_discoveries;
+        let properties = model.properties();
+        let mut discoveries = HashMap::new();
+        let mut seeds = HashMap::new();
+        for (name, discovery) in saved.discoveries {
+            let Some(property) = properties.iter().find(|p| p.name == name) else {
+                return Err(invalid_data(format!("Unknown property {name:?}")));
+            };
+            let indices: VecDeque<usize> = discovery
+                .path
+                .split('/')
+                .map(str::parse)
+                .collect::<Result<_, _>>()
+                .map_err(|_| {
+                    invalid_data(format!(
+                        "Unable to parse path {:?} of {name:?}",
+                        discovery.path
+                    ))
+                })?;
+            let state_count = indices.len();
+            if Path::final_state(&model, indices.clone()).is_none()
+                || discovery.loop_start.is_some_and(|i| i >= state_count)
+            {
+                return Err(invalid_data(format!(
+                    "The model has no path {:?} for {name:?}",
+                    discovery.path
+                )));
+            }
+            let path =
+                Path::from_action_indices(&model, indices).with_loop_start(discovery.loop_start);
+            discoveries.insert(property.name, path);
+            if let Some(seed) = discovery.seed {
+                seeds.insert(property.name, seed);
+            }
+        }
+        Ok(ReplayChecker {
+            model,
+            state_count: saved.state_count,
+            unique_state_count: saved.unique_state_count,
+            max_depth: saved.max_depth,
+            discoveries,
+            seeds,
+            shrink_discoveries,
+        })
+    }
+}
+
+fn invalid_data(message: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+impl<M: Model> Checker<M> for ReplayChecker<M> {
+    fn model(&self) -> &M {
+        &self.model
+    }
+
+    fn state_count(&self) -> usize {
+        self.state_count
+    }
+
+    fn unique_state_count(&self) -> usize {
+        self.unique_state_count
+    }
+
+    fn max_depth(&self) -> usize {
+        self.max_depth
+    }
+
+    fn discoveries(&self) -> HashMap<&'static str, Path<M::State, M::Action>>
+    where
+        M::State: Clone + PartialEq,
+        M::Action: Clone + PartialEq,
+    {
+        self.discoveries.clone()
+    }
+
+    fn handles(&mut self) -> Vec<JoinHandle<()>> {
+        Vec::new()
+    }
+
+    fn is_done(&self) -> bool {
+        true
+    }
+
+    fn discovery_seed(&self, name: &'static str) -> Option<u64> {
+        self.seeds.get(name).copied()
+    }
+
+    fn shrinks_discoveries(&self) -> bool {
+        self.shrink_discoveries
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_util::dgraph::DGraph;
+    use crate::test_util::linear_equation_solver::LinearEquation;
+    use crate::Property;
+
+    fn temp_path(name: &str) -> std::path::PathBuf {
+        std::env::temp_dir().join(format!("stateright-{}-{name}", std::process::id()))
+    }
+
+    fn model() -> DGraph {
+        DGraph::with_properties(vec![
+            Property::eventually("odd", |_, s| s % 2 == 1),
+            Property::always("small", |_, s| *s < 6),
+            Property::sometimes("four", |_, s| *s == 4),
+        ])
+        .with_path(vec![0, 2, 4, 6, 2])
+        .with_path(vec![0, 2, 4, 5])
+    }
+
+    #[test]
+    fn can_save_and_load_discoveries() {
+        let path = temp_path("can_save_and_load_discoveries.json");
+        let checker = model().check_cycles();
+        checker.save_discoveries(&path).unwrap();
+
+        let saved = SavedDiscoveries::load(&path).unwrap();
+        assert_eq!(saved.state_count, checker.state_count());
+        assert_eq!(saved.unique_state_count, checker.unique_state_count());
+        assert_eq!(saved.max_depth, checker.max_depth());
+        assert_eq!(
+            saved.discoveries["odd"],
+            SavedDiscovery {
+                path: "0/0/0/1/0".to_string(),
+                loop_start: Some(1),
+                seed: None,
+            }
+        );
+
+        let loaded = model().checker().load_discoveries(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert!(loaded.is_done());
+        assert_eq!(loaded.state_count(), checker.state_count());
+        assert_eq!(loaded.unique_state_count(), checker.unique_state_count());
+        assert_eq!(loaded.max_depth(), checker.max_depth());
+        assert_eq!(loaded.discoveries(), checker.discoveries());
+        assert_eq!(loaded.discovery("odd").unwrap().loop_start(), Some(1));
+    }
+
+    #[test]
+    fn can_save_simulation_seeds() {
+        let path = temp_path("can_save_simulation_seeds.json");
+        let checker = LinearEquation { a: 2, b: 10, c: 14 }
+            .checker()
+            .spawn_simulation(0, crate::UniformChooser)
+            .join();
+        checker.save_discoveries(&path).unwrap();
+        let loaded = LinearEquation { a: 2, b: 10, c: 14 }
+            .checker()
+            .load_discoveries(&path)
+            .unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(loaded.discoveries(), checker.discoveries());
+        assert_eq!(
+            loaded.discovery_seed("solvable"),
+            checker.discovery_seed("solvable")
+        );
+        assert!(loaded.discovery_seed("solvable").is_some());
+    }
+
+    #[test]
+    fn rejects_discoveries_of_another_model() {
+        let path = temp_path("rejects_discoveries_of_another_model.json");
+        let load = |saved: &SavedDiscoveries| {
+            saved.save(&path).unwrap();
+            let result = model().checker().load_discoveries(&path);
+            std::fs::remove_file(&path).unwrap();
+            result.map(|_| ()).unwrap_err()
+        };
+        let saved = |name: &str, path: &str, loop_start| SavedDiscoveries {
+            state_count: 1,
+            unique_state_count: 1,
+            max_depth: 1,
+            discoveries: BTreeMap::from([(
+                name.to_string(),
+                SavedDiscovery {
+                    path: path.to_string(),
+                    loop_start,
+                    seed: None,
+                },
+            )]),
+        };
+
+        let err = load(&saved("even", "0", None));
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        assert_eq!(err.to_string(), "Unknown property \"even\"");
+        let err = load(&saved("odd", "0/x", None));
+        assert_eq!(err.to_string(), "Unable to parse path \"0/x\" of \"odd\"");
+        let err = load(&saved("odd", "0/1", None));
+        assert_eq!(err.to_string(), "The model has no path \"0/1\" for \"odd\"");
+        let err = load(&saved("odd", "0/0", Some(2)));
+        assert_eq!(err.to_string(), "The model has no path \"0/0\" for \"odd\"");
+
+        let err = model()
+            .checker()
+            .load_discoveries(temp_path("missing.json"))
+            .map(|_| ())
+            .unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::NotFound);
+    }
+}
@@ -13,6 +13,9 @@ use std::sync::Arc;
 /// This is used for symmetry reduction when a [`Model::State`] implements [`Representative`]. See
 /// the latter docs for an example.
 ///
+/// With the `derive` cargo feature, `#[derive(Rewrite)]` implements this trait for structs and
+/// enums by rewriting each field. Fields annotated with `#[rewrite(skip)]` are cloned instead.
+///
 /// [`Model::State`]: crate::Model::State
 /// [`Representative`]: crate::Representative
 pub trait Rewrite<R> {
@@ -0,0 +1,80 @@
+//! Private module for selective re-export.
+
+/// A pseudorandom number generator whose output for a given seed will not change across versions
+/// of Stateright or its dependencies, so a seed recorded for a simulation run replays the same
+/// run after an upgrade (see [`CheckerBuilder::spawn_simulation`]).
+///
+/// The generator is SplitMix64 (Steele, Lea, and Flood, "Fast Splittable Pseudorandom Number
+/// Generators", 2014), and [`StableRng::below`] uses Lemire's method of multiplying by the bound
+/// and rejecting biased results ("Fast Random Integer Generation in an Interval", 2019). These
+/// are part of the API, so changing either would be a breaking change.
+///
+/// [`CheckerBuilder::spawn_simulation`]: crate::CheckerBuilder::spawn_simulation
+#[derive(Clone, Debug)]
+pub struct StableRng {
+    state: u64,
+}
+
+impl StableRng {
+    /// Creates a generator from a seed.
+    pub fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    /// Returns the next number, uniformly distributed over all `u64` values.
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Returns a number that is uniformly distributed in `0..bound`, such as an index into a
+    /// slice of length `bound`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `bound` is zero.
+    pub fn below(&mut self, bound: usize) -> usize {
+        assert!(bound > 0, "Cannot choose from an empty range");
+        let bound = bound as u64;
+        // Products whose low bits fall below this threshold would favor some results.
+        let threshold = bound.wrapping_neg() % bound;
+        loop {
+            let product = u128::from(self.next_u64()) * u128::from(bound);
+            if product as u64 >= threshold {
+                return (product >> 64) as usize;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn output_is_stable() {
+        // Reference values for SplitMix64.
+        let mut rng = StableRng::new(0);
+        assert_eq!(rng.next_u64(), 0xE220_A839_7B1D_CDAF);
+        assert_eq!(rng.next_u64(), 0x6E78_9E6A_A1B9_65F4);
+        assert_eq!(rng.next_u64(), 0x06C4_5D18_8009_454F);
+
+        let mut rng = StableRng::new(42);
+        let choices: Vec<_> = (0..8).map(|_| rng.below(10)).collect();
+        assert_eq!(choices, vec![7, 1, 2, 3, 0, 8, 2, 8]);
+    }
+
+    #[test]
+    fn chooses_within_bound() {
+        let mut rng = StableRng::new(1);
+        let mut counts = [0; 3];
+        for _ in 0..3_000 {
+            counts[rng.below(3)] += 1;
+        }
+        assert!(counts.iter().all(|&c| c > 900), "{counts:?}");
+        assert_eq!(rng.below(1), 0);
+    }
+}
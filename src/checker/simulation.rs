@@ -1,13 +1,16 @@
 //! Private module for selective re-export.
 
-use crate::checker::{Checker, Expectation, Path};
-use crate::{fingerprint, CheckerBuilder, CheckerVisitor, Model, Property};
+use crate::checker::liveness::{self, Fairness, FairnessBits, Judgement};
+use crate::checker::ltl::Monitor;
+use crate::checker::{Checker, Path, Predicate, StableRng, Symmetry};
+use crate::{fingerprint, CheckerBuilder, CheckerVisitor, Model};
 use dashmap::DashMap;
-use rand::rngs::StdRng;
-use rand::Rng;
-use rand::SeedableRng;
-use std::collections::{HashMap, HashSet, VecDeque};
+use parking_lot::Mutex;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::fmt::Debug;
 use std::hash::Hash;
+use std::mem::{discriminant, Discriminant};
 use std::num::NonZeroUsize;
 use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 use std::sync::Arc;
@@ -44,8 +47,7 @@ pub struct UniformChooser;
 
 /// A chooser that makes uniform choices.
 pub struct UniformChooserState {
-    // FIXME: use a reproducible rng, one that will not change over versions.
-    rng: StdRng,
+    rng: StableRng,
 }
 
 impl<M> Chooser<M> for UniformChooser
@@ -56,7 +58,7 @@ where
 
     fn new_state(&self, seed: u64) -> Self::State {
         UniformChooserState {
-            rng: StdRng::seed_from_u64(seed),
+            rng: StableRng::new(seed),
         }
     }
 
@@ -65,7 +67,7 @@ where
         state: &mut Self::State,
         initial_states: &[<M as Model>::State],
     ) -> usize {
-        state.rng.random_range(0..initial_states.len())
+        state.rng.below(initial_states.len())
     }
 
     fn choose_action(
@@ -74,7 +76,89 @@ where
         _current_state: &<M as Model>::State,
         actions: &[<M as Model>::Action],
     ) -> usize {
-        state.rng.random_range(0..actions.len())
+        state.rng.below(actions.len())
+    }
+}
+
+/// How a simulation run ended, as counted by [`SimulationStats::terminations`].
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd, Serialize)]
+pub enum RunTermination {
+    /// The run reached a state without a next state.
+    Deadlock,
+    /// The run left the [boundary](Model::within_boundary) of the model.
+    Boundary,
+    /// The run returned to a state that it had already visited.
+    Cycle,
+    /// The run reached the depth set by [`CheckerBuilder::target_max_depth`].
+    TargetMaxDepth,
+    /// Every property already had a discovery, so there was nothing left to check.
+    Discoveries,
+}
+
+/// Statistics about the runs of a simulation checker, available via
+/// [`Checker::simulation_stats`].
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize)]
+pub struct SimulationStats {
+    /// The number of runs that have ended.
+    pub runs: usize,
+    /// The number of runs that ended for each reason.
+    pub terminations: BTreeMap<RunTermination, usize>,
+    /// The number of runs that ended at each depth, which is the number of actions taken.
+    pub depths: BTreeMap<usize, usize>,
+    /// The number of times that each kind of action was taken. Actions are grouped by enum
+    /// variant (such as [`ActorModelAction::Deliver`]), which is named after its [`Debug`]
+    /// representation, while other types of actions are grouped under the name of their type.
+    ///
+    /// [`ActorModelAction::Deliver`]: crate::actor::ActorModelAction::Deliver
+    pub actions: BTreeMap<String, usize>,
+}
+
+/// Counts of the actions taken, with an example of each kind for naming it.
+type ActionCounts<Action> = HashMap<Discriminant<Action>, (Action, usize)>;
+
+/// Statistics that threads add to at the end of each run.
+struct RunStats<Action> {
+    stats: SimulationStats,
+    actions: ActionCounts<Action>,
+}
+
+impl<Action> RunStats<Action> {
+    fn record(
+        &mut self,
+        termination: RunTermination,
+        depth: usize,
+        actions: &mut ActionCounts<Action>,
+    ) {
+        self.stats.runs += 1;
+        *self.stats.terminations.entry(termination).or_default() += 1;
+        *self.stats.depths.entry(depth).or_default() += 1;
+        for (kind, (action, count)) in actions.drain() {
+            self.actions.entry(kind).or_insert((action, 0)).1 += count;
+        }
+    }
+
+    fn to_stats(&self) -> SimulationStats
+    where
+        Action: Debug,
+    {
+        let mut stats = self.stats.clone();
+        for (action, count) in self.actions.values() {
+            *stats.actions.entry(action_kind(action)).or_default() += count;
+        }
+        stats
+    }
+}
+
+/// Names the kind of an action after the variant at the start of its [`Debug`] representation.
+fn action_kind<Action: Debug>(action: &Action) -> String {
+    let debug = format!("{action:?}");
+    let variant = debug
+        .split([' ', '(', '{'])
+        .next()
+        .filter(|v| v.starts_with(|c: char| c.is_alphabetic() || c == '_'));
+    match variant {
+        Some(variant) => variant.to_string(),
+        None => std::any::type_name::<Action>().to_string(),
     }
 }
 
@@ -82,11 +166,15 @@ pub(crate) struct SimulationChecker<M: Model> {
     // Immutable state.
     model: Arc<M>,
     handles: Vec<std::thread::JoinHandle<()>>,
+    shrink_discoveries: bool,
 
     // Mutable state.
     state_count: Arc<AtomicUsize>,
     max_depth: Arc<AtomicUsize>,
     discoveries: Arc<DashMap<&'static str, Vec<usize>>>,
+    loop_starts: Arc<DashMap<&'static str, usize>>,
+    seeds: Arc<DashMap<&'static str, u64>>,
+    stats: Arc<Mutex<RunStats<M::Action>>>,
 }
 
 impl<M> SimulationChecker<M>
@@ -98,23 +186,41 @@ where
     ///
     /// `seed` is the seed for the random selection of actions between states.
     /// It is passed straight through to the first trace on the first thread to allow for
-    /// reproducibility. For other threads and traces it is regenerated using a [`StdRng`].
+    /// reproducibility. For other threads and traces it is regenerated using a [`StableRng`], and
+    /// the seed of the trace that found each discovery is recorded so that it can be replayed.
     pub(crate) fn spawn<C: Chooser<M>>(options: CheckerBuilder<M>, seed: u64, chooser: C) -> Self
     where
         M::State: Clone + PartialEq,
-        M::Action: Clone + PartialEq,
+        M::Action: Clone + PartialEq + Send,
     {
         let model = Arc::new(options.model);
+        let shrink_discoveries = options.shrink_discoveries;
         let symmetry = options.symmetry;
         let target_state_count = options.target_state_count;
         let target_max_depth = options.target_max_depth;
         let visitor = Arc::new(options.visitor);
         let finish_when = Arc::new(options.finish_when);
         let properties = Arc::new(model.properties());
+        let fairness = Arc::new(options.fairness);
+        let monitors: Arc<Vec<_>> = Arc::new(
+            properties
+                .iter()
+                .map(|p| match p.predicate {
+                    Predicate::Ltl(_) => Monitor::for_property(p),
+                    _ => None,
+                })
+                .collect(),
+        );
 
         let state_count = Arc::new(AtomicUsize::new(0));
         let max_depth = Arc::new(AtomicUsize::new(0));
         let discoveries = Arc::new(DashMap::default());
+        let loop_starts = Arc::new(DashMap::default());
+        let seeds = Arc::new(DashMap::default());
+        let stats = Arc::new(Mutex::new(RunStats {
+            stats: SimulationStats::default(),
+            actions: HashMap::new(),
+        }));
         let mut handles = Vec::new();
 
         let mut thread_seed = seed;
@@ -144,36 +250,51 @@ where
             let visitor = Arc::clone(&visitor);
             let finish_when = Arc::clone(&finish_when);
             let properties = Arc::clone(&properties);
+            let fairness = Arc::clone(&fairness);
+            let monitors = Arc::clone(&monitors);
             let state_count = Arc::clone(&state_count);
             let max_depth = Arc::clone(&max_depth);
             let discoveries = Arc::clone(&discoveries);
+            let loop_starts = Arc::clone(&loop_starts);
+            let seeds = Arc::clone(&seeds);
+            let stats = Arc::clone(&stats);
             let shutdown = Arc::clone(&shutdown);
             let chooser = chooser.clone();
+            let symmetry = symmetry.clone();
             handles.push(
                 std::thread::Builder::new()
                     .name(format!("checker-{t}"))
                     .spawn(move || {
                         let mut seed = thread_seed;
                         log::debug!("{t}: Thread started with seed={seed}.");
-                        // FIXME: use a reproducible rng, one that will not change over versions.
-                        let mut rng = StdRng::seed_from_u64(seed);
+                        let mut rng = StableRng::new(seed);
+                        let mut action_path = Vec::new();
+                        let mut action_counts = HashMap::new();
                         loop {
                             if shutdown.load(Ordering::Relaxed) {
                                 log::debug!("{t}: Got shutdown signal.");
                                 break;
                             }
 
-                            Self::check_trace_from_initial::<C>(
+                            action_path.clear();
+                            let (termination, depth) = Self::check_trace_from_initial::<C>(
                                 &model,
                                 seed,
                                 &chooser,
+                                &mut action_path,
+                                &mut action_counts,
                                 &state_count,
                                 &discoveries,
+                                &loop_starts,
+                                &seeds,
                                 &visitor,
                                 target_max_depth,
                                 &max_depth,
-                                symmetry,
+                                symmetry.as_ref(),
+                                &fairness,
+                                &monitors,
                             );
+                            stats.lock().record(termination, depth, &mut action_counts);
 
                             // Check whether we have found everything.
                             // All threads should reach this check and have the same result,
@@ -194,7 +315,7 @@ where
                                 }
                             }
 
-                            seed = rng.random();
+                            seed = rng.next_u64();
                             log::trace!("{t}: Generated new thread seed={seed}");
                         }
                     })
@@ -205,33 +326,72 @@ where
         SimulationChecker {
             model,
             handles,
+            shrink_discoveries,
             state_count,
             max_depth,
             discoveries,
+            loop_starts,
+            seeds,
+            stats,
+        }
+    }
+
+    /// Records a discovery along with the seed of the run that found it and, for a counterexample
+    /// to a liveness property, the index of the state to which its path loops back if it ends in
+    /// a cycle.
+    #[allow(clippy::too_many_arguments)]
+    fn record_discovery(
+        discoveries: &DashMap<&'static str, Vec<usize>>,
+        loop_starts: &DashMap<&'static str, usize>,
+        seeds: &DashMap<&'static str, u64>,
+        name: &'static str,
+        action_path: &[usize],
+        loop_start: Option<usize>,
+        seed: u64,
+    ) {
+        // Holding the discovery while recording its seed and loop start prevents another thread
+        // from replacing the discovery in between.
+        let discovery = discoveries.entry(name);
+        seeds.insert(name, seed);
+        if let Some(loop_start) = loop_start {
+            loop_starts.insert(name, loop_start);
+        } else {
+            loop_starts.remove(name);
         }
+        discovery.insert(action_path.to_vec());
     }
 
+    /// Checks a run, returning how it ended and its depth.
     #[allow(clippy::too_many_arguments)]
     #[allow(clippy::type_complexity)]
     fn check_trace_from_initial<C: Chooser<M>>(
         model: &M,
         seed: u64,
         chooser: &C,
+        action_path: &mut Vec<usize>,
+        action_counts: &mut ActionCounts<M::Action>,
         state_count: &AtomicUsize,
         discoveries: &DashMap<&'static str, Vec<usize>>,
+        loop_starts: &DashMap<&'static str, usize>,
+        seeds: &DashMap<&'static str, u64>,
         visitor: &Option<Box<dyn CheckerVisitor<M> + Send + Sync>>,
         target_max_depth: Option<NonZeroUsize>,
         global_max_depth: &AtomicUsize,
-        symmetry: Option<fn(&M::State) -> M::State>,
-    ) where
+        symmetry: Option<&Symmetry<M>>,
+        fairness: &[Fairness<M::Action>],
+        monitors: &[Option<Monitor<M>>],
+    ) -> (RunTermination, usize)
+    where
         M::State: Clone + PartialEq,
         M::Action: Clone + PartialEq,
     {
         let properties = model.properties();
+        let has_transitions = properties
+            .iter()
+            .any(|p| matches!(p.predicate, Predicate::Transition(_)));
 
         let mut chooser_state = chooser.new_state(seed);
 
-        let mut action_path = Vec::new();
         let mut state = {
             let mut initial_states = model.init_states();
             let index = chooser.choose_initial_state(&mut chooser_state, &initial_states);
@@ -240,24 +400,36 @@ where
         };
 
         let mut current_max_depth = global_max_depth.load(Ordering::Relaxed);
+        // The number of actions taken in this run.
+        let mut depth = 0;
         // The set of actions.
         let mut actions = Vec::new();
-        // The fingerprints we've seen in this run, for preventing cycles.
-        let mut generated = HashSet::new();
+        // The fingerprints we've seen in this run, for preventing cycles, along with the index of
+        // each in the path.
+        let mut generated = HashMap::new();
+        // The index to which the path loops back if the run ends in a cycle. Not tracked with
+        // symmetry reduction, as the run may only loop back to an equivalent state.
+        let mut path_loop_start = None;
+        // The fingerprint of each state in this run, along with the fairness constraints enabled
+        // in that state and those whose class includes the action taken. Only tracked if there
+        // are fairness constraints.
+        let mut fairness_trace: Vec<(_, FairnessBits, FairnessBits)> = Vec::new();
+        // The fingerprint of each state in this run, only tracked if there are temporal logic
+        // properties, along with how the run ends if it is a terminal state (`Some(None)`) or a
+        // loop back to an earlier state (`Some(Some(index))`).
+        let has_monitors = monitors.iter().any(Option::is_some);
+        let mut states = Vec::new();
+        let mut lasso = None;
         let mut ebits = {
             let mut ebits = EventuallyBits::new();
             for (i, p) in model.properties().iter().enumerate() {
-                if let Property {
-                    expectation: Expectation::Eventually,
-                    ..
-                } = p
-                {
+                if let Predicate::Eventually(_) = p.predicate {
                     ebits.insert(i);
                 }
             }
             ebits
         };
-        'outer: loop {
+        let termination = 'outer: loop {
             if action_path.len() - 1 > current_max_depth {
                 let _ = global_max_depth.compare_exchange(
                     current_max_depth,
@@ -275,26 +447,54 @@ where
                     );
                     // return not break here as we do not know if this is terminal.
                     log::trace!("Reached max depth");
-                    return;
+                    return (RunTermination::TargetMaxDepth, depth);
                 }
             }
 
             // Skip if outside boundary.
             if !model.within_boundary(&state) {
                 log::trace!("Found state outside of boundary");
-                break;
+                break RunTermination::Boundary;
             }
 
             // check that we haven't already seen this state
-            let inserted = if let Some(representative) = symmetry {
-                generated.insert(fingerprint(&representative(&state)))
+            let fp = if let Some(representative) = symmetry {
+                fingerprint(&representative(&state))
             } else {
-                generated.insert(fingerprint(&state))
+                fingerprint(&state)
             };
-            if !inserted {
+            if let Some(&index) = generated.get(&fp) {
                 // found a loop
                 log::trace!("Found a loop");
-                break;
+                if !fairness.is_empty() {
+                    let start = fairness_trace
+                        .iter()
+                        .position(|(f, _, _)| *f == fp)
+                        .unwrap();
+                    let cycle = &fairness_trace[start..];
+                    let mut taken = FairnessBits::new();
+                    for (_, _, classes) in cycle {
+                        taken.inplace_union(classes);
+                    }
+                    let enabled = cycle.iter().map(|(_, enabled, _)| enabled);
+                    if liveness::judge(fairness, enabled, &taken) != Judgement::Fair {
+                        // do not check eventually properties as the loop is not fair
+                        log::trace!("Loop is not fair");
+                        return (RunTermination::Cycle, depth);
+                    }
+                }
+                if has_monitors {
+                    lasso = Some(states.iter().position(|(f, _)| *f == fp));
+                    states.push((fp, state));
+                }
+                if symmetry.is_none() {
+                    path_loop_start = Some(index);
+                }
+                break RunTermination::Cycle;
+            }
+            generated.insert(fp, action_path.len() - 1);
+            if has_monitors {
+                states.push((fp, state.clone()));
             }
 
             state_count.fetch_add(1, Ordering::Relaxed);
@@ -310,41 +510,45 @@ where
             let mut is_awaiting_discoveries = false;
             for (i, property) in properties.iter().enumerate() {
                 if discoveries.contains_key(property.name)
-                    && (property.expectation == Expectation::Eventually)
+                    && matches!(property.predicate, Predicate::Eventually(_))
                     && !ebits.contains(i)
                 {
                     continue;
                 }
-                match property {
-                    Property {
-                        expectation: Expectation::Always,
-                        condition: always,
-                        ..
-                    } => {
+                match &property.predicate {
+                    Predicate::Always(always) => {
                         if !always(model, &state) {
                             // Races other threads, but that's fine.
-                            discoveries.insert(property.name, action_path.clone());
+                            Self::record_discovery(
+                                discoveries,
+                                loop_starts,
+                                seeds,
+                                property.name,
+                                action_path,
+                                None,
+                                seed,
+                            );
                         } else {
                             is_awaiting_discoveries = true;
                         }
                     }
-                    Property {
-                        expectation: Expectation::Sometimes,
-                        condition: sometimes,
-                        ..
-                    } => {
+                    Predicate::Sometimes(sometimes) => {
                         if sometimes(model, &state) {
                             // Races other threads, but that's fine.
-                            discoveries.insert(property.name, action_path.clone());
+                            Self::record_discovery(
+                                discoveries,
+                                loop_starts,
+                                seeds,
+                                property.name,
+                                action_path,
+                                None,
+                                seed,
+                            );
                         } else {
                             is_awaiting_discoveries = true;
                         }
                     }
-                    Property {
-                        expectation: Expectation::Eventually,
-                        condition: eventually,
-                        ..
-                    } => {
+                    Predicate::Eventually(eventually) => {
                         // The checker early exits after finding discoveries for every property,
                         // and "eventually" property discoveries are only identified at terminal
                         // states, so if we are here it means we are still awaiting a corresponding
@@ -355,15 +559,36 @@ where
                             ebits.remove(i);
                         }
                     }
+                    Predicate::Transition(_) | Predicate::Ltl(_) => {
+                        // Transitions are checked as steps are taken, and temporal logic
+                        // properties are checked once the path ends.
+                        if !discoveries.contains_key(property.name) {
+                            is_awaiting_discoveries = true;
+                        }
+                    }
                 }
             }
             if !is_awaiting_discoveries {
                 log::trace!("Found all discoveries");
-                break;
+                break RunTermination::Discoveries;
             }
 
             // generate the possible next actions
             model.actions(&state, &mut actions);
+            if !fairness.is_empty() {
+                let mut enabled = FairnessBits::new();
+                for action in &actions {
+                    let classes = liveness::classify(fairness, action);
+                    if classes.iter().any(|i| !enabled.contains(i))
+                        && model
+                            .next_state(&state, action.clone())
+                            .is_some_and(|s| model.within_boundary(&s))
+                    {
+                        enabled.inplace_union(&classes);
+                    }
+                }
+                fairness_trace.push((fp, enabled, FairnessBits::new()));
+            }
 
             // generate the next state, repeatedly choosing an action until we get one or there are
             // no actions left to choose.
@@ -372,12 +597,17 @@ where
                     // no actions to choose from
                     // break from the outer loop so that we still check eventually properties
                     log::trace!("No actions to choose from");
-                    break 'outer;
+                    lasso = Some(None);
+                    break 'outer RunTermination::Deadlock;
                 }
 
                 // now pick one
                 let index = chooser.choose_action(&mut chooser_state, &state, &actions);
                 let action = actions.swap_remove(index);
+                let classes = liveness::classify(fairness, &action);
+                let transition_action = has_transitions.then(|| action.clone());
+                let kind = discriminant(&action);
+                let example = (!action_counts.contains_key(&kind)).then(|| action.clone());
 
                 // take the chosen action
                 match model.next_state(&state, action) {
@@ -387,7 +617,42 @@ where
                         log::trace!("No next state");
                     }
                     Some(next_state) => {
+                        if let Some(action) = &transition_action {
+                            if model.within_boundary(&next_state) {
+                                for property in &properties {
+                                    let Predicate::Transition(transition) = &property.predicate
+                                    else {
+                                        continue;
+                                    };
+                                    if !discoveries.contains_key(property.name)
+                                        && !transition(model, &state, action, &next_state)
+                                    {
+                                        let mut transition_path = action_path.clone();
+                                        transition_path.push(index);
+                                        // Races other threads, but that's fine.
+                                        Self::record_discovery(
+                                            discoveries,
+                                            loop_starts,
+                                            seeds,
+                                            property.name,
+                                            &transition_path,
+                                            None,
+                                            seed,
+                                        );
+                                    }
+                                }
+                            }
+                        }
                         action_path.push(index);
+                        depth += 1;
+                        // An example is only cloned for the first action of each kind.
+                        action_counts
+                            .entry(kind)
+                            .or_insert_with(|| (example.unwrap(), 0))
+                            .1 += 1;
+                        if let Some((_, _, taken)) = fairness_trace.last_mut() {
+                            *taken = classes;
+                        }
                         // now clear the actions for the next round
                         actions.clear();
                         state = next_state;
@@ -395,14 +660,46 @@ where
                     }
                 };
             }
-        }
+        };
         // check the eventually properties
         for (i, property) in properties.iter().enumerate() {
             if ebits.contains(i) {
                 // Races other threads, but that's fine.
-                discoveries.insert(property.name, action_path.clone());
+                Self::record_discovery(
+                    discoveries,
+                    loop_starts,
+                    seeds,
+                    property.name,
+                    action_path,
+                    path_loop_start,
+                    seed,
+                );
+            }
+        }
+        // check the temporal logic properties
+        if let Some(loop_start) = lasso {
+            let states: Vec<_> = states.into_iter().map(|(_, s)| s).collect();
+            for (property, monitor) in properties.iter().zip(monitors) {
+                let Some(monitor) = monitor else {
+                    continue;
+                };
+                if !discoveries.contains_key(property.name)
+                    && liveness::accepts_path(model, monitor, &states, loop_start)
+                {
+                    // Races other threads, but that's fine.
+                    Self::record_discovery(
+                        discoveries,
+                        loop_starts,
+                        seeds,
+                        property.name,
+                        action_path,
+                        path_loop_start,
+                        seed,
+                    );
+                }
             }
         }
+        (termination, depth)
     }
 }
 
@@ -437,18 +734,34 @@ where
         self.discoveries
             .iter()
             .map(|mapref| {
-                (
-                    <&'static str>::clone(mapref.key()),
-                    Path::from_action_indices(self.model(), VecDeque::from(mapref.value().clone())),
-                )
+                let name = <&'static str>::clone(mapref.key());
+                let path =
+                    Path::from_action_indices(self.model(), VecDeque::from(mapref.value().clone()));
+                let loop_start = self.loop_starts.get(name).map(|r| *r);
+                (name, path.with_loop_start(loop_start))
             })
             .collect()
     }
 
+    fn discovery_seed(&self, name: &'static str) -> Option<u64> {
+        self.seeds.get(name).map(|seed| *seed)
+    }
+
+    fn simulation_stats(&self) -> Option<SimulationStats>
+    where
+        M::Action: Debug,
+    {
+        Some(self.stats.lock().to_stats())
+    }
+
     fn handles(&mut self) -> Vec<JoinHandle<()>> {
         std::mem::take(&mut self.handles)
     }
 
+    fn shrinks_discoveries(&self) -> bool {
+        self.shrink_discoveries
+    }
+
     fn is_done(&self) -> bool {
         self.handles.iter().all(|h| h.is_finished())
     }
@@ -457,6 +770,7 @@ where
 #[cfg(test)]
 mod test {
     use super::*;
+    use crate::report::WriteReporter;
     use crate::test_util::linear_equation_solver::*;
 
     #[test]
@@ -472,4 +786,119 @@ mod test {
             vec![Guess::IncreaseX, Guess::IncreaseY, Guess::IncreaseX],
         );
     }
+
+    #[test]
+    fn records_seeds_that_replay_discoveries() {
+        // Most traces end before reaching a solution, so it is typically not found by the first.
+        let model = || LinearEquation { a: 2, b: 10, c: 14 };
+        let checker = model()
+            .checker()
+            .target_max_depth(4)
+            .threads(4)
+            .spawn_simulation(0, UniformChooser)
+            .join();
+        let seed = checker.discovery_seed("solvable").unwrap();
+        let replayed = model()
+            .checker()
+            .target_max_depth(4)
+            .spawn_simulation(seed, UniformChooser)
+            .join();
+        assert_eq!(
+            replayed.discovery("solvable").unwrap().into_actions(),
+            checker.discovery("solvable").unwrap().into_actions()
+        );
+        assert_eq!(replayed.discovery_seed("solvable"), Some(seed));
+
+        let mut written = Vec::new();
+        replayed.report(&mut WriteReporter::new(&mut written));
+        let written = String::from_utf8(written).unwrap();
+        assert!(written.contains(&format!("Simulation seed: {seed}\n")));
+    }
+
+    #[test]
+    fn collects_run_statistics() {
+        // Unsolvable, so every run continues until reaching the target depth.
+        let checker = LinearEquation { a: 2, b: 4, c: 7 }
+            .checker()
+            .target_max_depth(4)
+            .target_state_count(1_000)
+            .spawn_simulation(0, UniformChooser)
+            .join();
+        let stats = checker.simulation_stats().unwrap();
+        assert!(stats.runs > 0);
+        assert_eq!(stats.terminations.values().sum::<usize>(), stats.runs);
+        assert_eq!(stats.depths.values().sum::<usize>(), stats.runs);
+        assert_eq!(
+            stats.terminations.keys().collect::<Vec<_>>(),
+            vec![&RunTermination::TargetMaxDepth]
+        );
+        assert_eq!(stats.depths.keys().collect::<Vec<_>>(), vec![&4]);
+        assert_eq!(
+            stats.actions.keys().collect::<Vec<_>>(),
+            vec!["IncreaseX", "IncreaseY"]
+        );
+        assert_eq!(stats.actions.values().sum::<usize>(), 4 * stats.runs);
+
+        let mut written = Vec::new();
+        checker.report(&mut WriteReporter::new(&mut written));
+        let written = String::from_utf8(written).unwrap();
+        assert!(written.contains(&format!(
+            "Simulation runs: {}\nRun terminations: TargetMaxDepth={}\nRun depths: 4={}\n",
+            stats.runs, stats.runs, stats.runs
+        )));
+
+        let checker = LinearEquation { a: 2, b: 10, c: 14 }
+            .checker()
+            .spawn_simulation(0, UniformChooser)
+            .join();
+        let stats = checker.simulation_stats().unwrap();
+        assert_eq!(
+            stats.terminations,
+            BTreeMap::from([(RunTermination::Discoveries, 1)])
+        );
+    }
+
+    #[test]
+    fn names_action_kinds_after_variants() {
+        use crate::actor::{ActorModelAction, Envelope, Id};
+        let deliver: ActorModelAction<u8, (), ()> = ActorModelAction::Deliver {
+            src: Id::from(0),
+            dst: Id::from(1),
+            msg: 2,
+        };
+        assert_eq!(action_kind(&deliver), "Deliver");
+        let drop: ActorModelAction<u8, (), ()> = ActorModelAction::Drop(Envelope {
+            src: Id::from(0),
+            dst: Id::from(1),
+            msg: 2,
+        });
+        assert_eq!(action_kind(&drop), "Drop");
+        assert_eq!(action_kind(&Guess::IncreaseX), "IncreaseX");
+        assert_eq!(action_kind(&7_u8), "u8");
+        assert_eq!(action_kind(&"x"), "&str");
+    }
+
+    #[test]
+    fn discards_unfair_loops() {
+        use crate::test_util::dgraph::DGraph;
+        let graph = DGraph::with_property(crate::Property::eventually("odd", |_, s| s % 2 == 1))
+            .with_path(vec![0, 2, 2])
+            .with_path(vec![0, 2, 3]);
+        let checker = graph
+            .clone()
+            .checker()
+            .target_state_count(100)
+            .spawn_simulation(0, UniformChooser)
+            .join();
+        let path = checker.discovery("odd").unwrap();
+        assert_eq!(path.loop_start(), Some(1));
+        assert_eq!(path.into_states(), vec![0, 2, 2]);
+        let checker = graph
+            .checker()
+            .weak_fairness(|a| *a == 3)
+            .target_state_count(100)
+            .spawn_simulation(0, UniformChooser)
+            .join();
+        assert_eq!(checker.discovery("odd"), None);
+    }
 }
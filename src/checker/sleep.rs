@@ -0,0 +1,286 @@
+//! Private module for selective re-export.
+
+use crate::Fingerprint;
+use nohash_hasher::NoHashHasher;
+use parking_lot::Mutex;
+use std::collections::HashMap;
+use std::hash::BuildHasherDefault;
+
+// Partial-order reduction via sleep sets (Godefroid, "Partial-Order Methods for the Verification
+// of Concurrent Systems"). Two steps of distinct processes (see `Model::step_process`) from the
+// same state commute, so once the first is explored, the state reached by the second need not
+// take the first: doing so would only reach a state that is also reached by taking the second
+// after the first. Each pending state therefore carries the steps that it can skip, which it
+// passes on to a successor if they also commute with the step to that successor.
+//
+// A state reached again can only be ignored if it was explored with a subset of the current
+// sleep set. Otherwise the steps that it skipped may be needed, so it is explored again with the
+// intersection of both sleep sets. Hence the sleep set of each explored state is recorded.
+//
+// Sleep sets prune transitions rather than states, so every reachable state is still visited
+// (and checked against every property). The savings come from not computing successors (and
+// fingerprints) that are already known, which dominates for systems of many independent actors.
+
+/// Steps that a pending state can skip, each with the process that takes it.
+pub(crate) struct SleepSet<Action> {
+    asleep: Vec<(Action, usize)>,
+    /// For a state that is explored again, the steps that it skipped before, which are the only
+    /// ones to take now.
+    awakened: Option<Vec<Action>>,
+}
+
+impl<Action> Default for SleepSet<Action> {
+    fn default() -> Self {
+        SleepSet {
+            asleep: Vec::new(),
+            awakened: None,
+        }
+    }
+}
+
+impl<Action: Clone + PartialEq> SleepSet<Action> {
+    /// Whether a step can be skipped.
+    pub(crate) fn skips(&self, action: &Action) -> bool {
+        self.asleep.iter().any(|(a, _)| a == action)
+            || self.awakened.as_ref().is_some_and(|a| !a.contains(action))
+    }
+
+    /// The sleep set of the state reached by a step of `process`, given the steps already taken
+    /// from the current state.
+    pub(crate) fn after(&self, taken: &[(Action, usize)], process: usize) -> Self {
+        SleepSet {
+            asleep: self
+                .asleep
+                .iter()
+                .chain(taken)
+                .filter(|(_, p)| *p != process)
+                .cloned()
+                .collect(),
+            awakened: None,
+        }
+    }
+}
+
+/// The sleep sets with which generated states were explored, keyed by fingerprint. Only nonempty
+/// sleep sets are recorded.
+pub(crate) struct SleepSets<Action> {
+    #[allow(clippy::type_complexity)]
+    shards: Vec<
+        Mutex<HashMap<Fingerprint, Vec<(Action, usize)>, BuildHasherDefault<NoHashHasher<u64>>>>,
+    >,
+}
+
+impl<Action: Clone + PartialEq> SleepSets<Action> {
+    pub(crate) fn new() -> Self {
+        SleepSets {
+            shards: (0..64).map(|_| Mutex::default()).collect(),
+        }
+    }
+
+    /// Returns the sleep set with which to explore a state that is reached with `sleep_set`, or
+    /// `None` if it need not be explored (again). `generate` records the fingerprint as
+    /// generated, returning whether it was not already.
+    pub(crate) fn visit(
+        &self,
+        fingerprint: Fingerprint,
+        sleep_set: SleepSet<Action>,
+        generate: impl FnOnce() -> bool,
+    ) -> Option<SleepSet<Action>> {
+        let mut shard = self.shards[fingerprint.get() as usize % self.shards.len()].lock();
+        if generate() {
+            if !sleep_set.asleep.is_empty() {
+                shard.insert(fingerprint, sleep_set.asleep.clone());
+            }
+            return Some(sleep_set);
+        }
+        let explored = shard.get_mut(&fingerprint)?;
+        let is_asleep = |action: &Action| sleep_set.asleep.iter().any(|(a, _)| a == action);
+        let awakened: Vec<_> = explored
+            .iter()
+            .filter(|(a, _)| !is_asleep(a))
+            .map(|(a, _)| a.clone())
+            .collect();
+        if awakened.is_empty() {
+            return None;
+        }
+        explored.retain(|(a, _)| is_asleep(a));
+        let asleep = explored.clone();
+        if asleep.is_empty() {
+            shard.remove(&fingerprint);
+        }
+        Some(SleepSet {
+            asleep,
+            awakened: Some(awakened),
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::actor::{model_timeout, Actor, ActorModel, Id, LossyNetwork, Network, Out};
+    use crate::{Checker, Expectation, Model, Property};
+    use std::borrow::Cow;
+
+    /// Each actor counts to a limit, sending each count to the next actor, which remembers the
+    /// largest count received.
+    #[derive(Clone)]
+    struct Relay {
+        peer: Id,
+        limit: u8,
+    }
+
+    impl Actor for Relay {
+        type Msg = u8;
+        type State = (u8, u8);
+        type Timer = ();
+        type Random = ();
+        type Storage = ();
+
+        fn on_start(&self, _: Id, _: &Option<Self::Storage>, o: &mut Out<Self>) -> Self::State {
+            o.set_timer((), model_timeout());
+            (0, 0)
+        }
+
+        fn on_msg(
+            &self,
+            _: Id,
+            state: &mut Cow<Self::State>,
+            _: Id,
+            msg: Self::Msg,
+            _: &mut Out<Self>,
+        ) {
+            if msg > state.1 {
+                state.to_mut().1 = msg;
+            }
+        }
+
+        fn on_timeout(&self, _: Id, state: &mut Cow<Self::State>, _: &(), o: &mut Out<Self>) {
+            if state.0 < self.limit {
+                state.to_mut().0 += 1;
+                o.send(self.peer, state.0);
+                o.set_timer((), model_timeout());
+            }
+        }
+    }
+
+    fn relay_model(network: Network<u8>, lossy: LossyNetwork) -> ActorModel<Relay, (), Vec<u8>> {
+        ActorModel::new((), Vec::new())
+            .actors((0..3).map(|i| Relay {
+                peer: Id::from((i + 1) % 3),
+                limit: 2,
+            }))
+            .init_network(network)
+            .lossy_network(lossy)
+            .record_msg_out(|_, history, env| {
+                // Only messages from the first actor are recorded, so only its steps that send
+                // do not commute.
+                (env.src == Id::from(0)).then(|| {
+                    let mut history = history.clone();
+                    history.push(*env.msg);
+                    history
+                })
+            })
+            .property(Expectation::Always, "received at most limit", |_, state| {
+                state.actor_states.iter().all(|s| s.1 <= 2)
+            })
+            .property(Expectation::Always, "first is ahead", |_, state| {
+                state.actor_states[0].0 >= state.actor_states[1].0
+            })
+            .property(Expectation::Sometimes, "all received", |_, state| {
+                state.actor_states.iter().all(|s| s.1 == 2)
+            })
+    }
+
+    #[test]
+    fn visits_every_state_with_fewer_transitions() {
+        for (network, lossy) in [
+            (Network::new_unordered_nonduplicating([]), LossyNetwork::No),
+            (Network::new_unordered_duplicating([]), LossyNetwork::No),
+            (Network::new_ordered([]), LossyNetwork::Yes),
+        ] {
+            let model = || relay_model(network.clone(), lossy);
+            let checkers = |reduce: bool| -> Vec<Box<dyn Checker<_>>> {
+                let builder = || {
+                    let builder = model().checker();
+                    if reduce {
+                        builder.partial_order_reduction()
+                    } else {
+                        builder
+                    }
+                };
+                vec![
+                    Box::new(builder().spawn_bfs().join()),
+                    Box::new(builder().threads(4).spawn_bfs().join()),
+                    Box::new(builder().spawn_dfs().join()),
+                    Box::new(builder().threads(4).spawn_dfs().join()),
+                ]
+            };
+            let full = model().checker().spawn_bfs().join();
+            for checker in checkers(true) {
+                assert_eq!(checker.unique_state_count(), full.unique_state_count());
+                assert!(checker.state_count() < full.state_count());
+                for property in model().properties() {
+                    assert_eq!(
+                        checker.discovery(property.name).is_some(),
+                        full.discovery(property.name).is_some(),
+                        "{}",
+                        property.name
+                    );
+                }
+            }
+            for checker in checkers(false) {
+                assert_eq!(checker.state_count(), full.state_count());
+            }
+        }
+    }
+
+    #[test]
+    fn respects_model_boundary() {
+        // Two independent counters with a hole in the boundary, so [1, 1] is only reachable via
+        // [0, 1] even though incrementing the counters commutes.
+        struct Counters;
+        impl Model for Counters {
+            type State = [u8; 2];
+            type Action = usize;
+            fn init_states(&self) -> Vec<Self::State> {
+                vec![[0, 0]]
+            }
+            fn actions(&self, _: &Self::State, actions: &mut Vec<Self::Action>) {
+                actions.extend([0, 1]);
+            }
+            fn next_state(&self, last_state: &Self::State, i: usize) -> Option<Self::State> {
+                let mut state = *last_state;
+                state[i] += 1;
+                Some(state)
+            }
+            fn step_process(&self, _: &Self::State, &i: &usize, _: &Self::State) -> Option<usize> {
+                Some(i)
+            }
+            fn within_boundary(&self, state: &Self::State) -> bool {
+                state != &[1, 0] && state[0] + state[1] <= 4 && state[0] <= 3
+            }
+            fn properties(&self) -> Vec<Property<Self>> {
+                vec![
+                    Property::always("within boundary", |model: &Self, s| {
+                        model.within_boundary(s)
+                    }),
+                    Property::sometimes("corner", |_, s| s == &[3, 1]),
+                ]
+            }
+        }
+        let checker = Counters
+            .checker()
+            .partial_order_reduction()
+            .spawn_bfs()
+            .join();
+        assert_eq!(checker.unique_state_count(), 11);
+        checker.assert_any_discovery("corner");
+        let checker = Counters
+            .checker()
+            .partial_order_reduction()
+            .spawn_dfs()
+            .join();
+        assert_eq!(checker.unique_state_count(), 11);
+        checker.assert_any_discovery("corner");
+    }
+}
@@ -29,11 +29,11 @@ impl HasDiscoveries {
             HasDiscoveries::Any => !discoveries.is_empty(),
             HasDiscoveries::AnyFailures => properties
                 .iter()
-                .filter(|prop| prop.expectation.discovery_is_failure())
+                .filter(|prop| prop.discovery_is_failure())
                 .any(|prop| discoveries.contains(prop.name)),
             HasDiscoveries::AllFailures => properties
                 .iter()
-                .filter(|prop| prop.expectation.discovery_is_failure())
+                .filter(|prop| prop.discovery_is_failure())
                 .all(|prop| discoveries.contains(prop.name)),
             HasDiscoveries::AllOf(props) => props.iter().all(|prop| discoveries.contains(prop)),
             HasDiscoveries::AnyOf(props) => props.iter().any(|prop| discoveries.contains(prop)),
@@ -1,4 +1,12 @@
+use crate::checker::Spill;
 use parking_lot::{Condvar, Mutex};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::io::{self, BufReader, BufWriter, Read, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{channel, Receiver};
+use std::time::{Duration, Instant};
 use std::{collections::VecDeque, sync::Arc, thread::sleep, time::SystemTime};
 
 /// A market for synchronising the sharing of jobs.
@@ -30,6 +38,10 @@ impl<Job> Drop for JobBroker<Job> {
         );
         market.open = false;
         market.job_batches.clear();
+        market.job_count = 0;
+        if let Some(spill) = &mut market.spill {
+            spill.clear();
+        }
         market.open_count = market.open_count.saturating_sub(1);
         self.has_new_jobs.notify_all();
     }
@@ -44,6 +56,29 @@ struct JobMarket<Job> {
     open_count: usize,
     /// Jobs available.
     job_batches: Vec<VecDeque<Job>>,
+    /// Number of jobs in `job_batches`.
+    job_count: usize,
+    /// Jobs available on disk, if jobs are spilled once too many are held in memory.
+    spill: Option<Spill<Job>>,
+    /// How jobs are periodically checkpointed, if they are.
+    checkpoint: Option<Checkpoint<Job>>,
+    /// How jobs are shared with the markets of other processes, if they are.
+    network: Option<Network<Job>>,
+}
+
+/// The jobs of a checkpoint, in the order that they should be restored, and the jobs spilled to
+/// disk.
+pub(crate) type CheckpointJobs<'a, Job> = (Vec<&'a VecDeque<Job>>, Option<&'a Spill<Job>>);
+
+struct Checkpoint<Job> {
+    interval: Duration,
+    next_at: Instant,
+    /// Whether threads are awaiting a checkpoint, which is written once every working thread
+    /// has parked its jobs.
+    due: bool,
+    parked: Vec<Option<VecDeque<Job>>>,
+    #[allow(clippy::type_complexity)]
+    write: Box<dyn FnMut(CheckpointJobs<Job>) + Send>,
 }
 
 impl<Job> JobBroker<Job>
@@ -59,6 +94,10 @@ where
                 thread_count,
                 open_count: thread_count,
                 job_batches: Vec::new(),
+                job_count: 0,
+                spill: None,
+                checkpoint: None,
+                network: None,
             })),
         };
         if let Some(closing_time) = close_at {
@@ -89,7 +128,14 @@ impl<Job> JobBroker<Job> {
             return VecDeque::new();
         }
         loop {
-            if let Some(jobs) = market.job_batches.pop() {
+            let jobs = match market.job_batches.pop() {
+                Some(jobs) => {
+                    market.job_count -= jobs.len();
+                    Some(jobs)
+                }
+                None => market.spill.as_mut().and_then(Spill::read),
+            };
+            if let Some(jobs) = jobs {
                 log::trace!(
                     "{}: Got jobs. Working.",
                     std::thread::current().name().unwrap_or_default()
@@ -98,7 +144,9 @@ impl<Job> JobBroker<Job> {
             } else {
                 // Otherwise more work may become available.
                 market.open_count = market.open_count.saturating_sub(1);
-                if market.open_count == 0 {
+                if market.write_checkpoint_if_ready() {
+                    self.has_new_jobs.notify_all();
+                } else if market.open_count == 0 {
                     // we are the last running thread, notify all others and return so we can
                     // shut down properly
                     log::trace!(
@@ -120,13 +168,132 @@ impl<Job> JobBroker<Job> {
         }
     }
 
+    /// Writes jobs to disk rather than holding them in memory once the market holds more than the
+    /// spill's limit.
+    pub fn spill(&mut self, spill: Spill<Job>) {
+        self.market.lock().spill = Some(spill);
+    }
+
+    /// Periodically calls `write` with every job once the threads working on jobs have called
+    /// [`JobBroker::checkpoint_if_due`].
+    pub fn checkpoint(
+        &mut self,
+        interval: Duration,
+        write: impl FnMut(CheckpointJobs<Job>) + Send + 'static,
+    ) {
+        self.market.lock().checkpoint = Some(Checkpoint {
+            interval,
+            next_at: Instant::now() + interval,
+            due: false,
+            parked: Vec::new(),
+            write: Box::new(write),
+        });
+    }
+
+    /// Blocks until a checkpoint is written if one is due, including the specified jobs in the
+    /// checkpoint. Does nothing otherwise.
+    pub fn checkpoint_if_due(&mut self, jobs: &mut VecDeque<Job>) {
+        let mut market = self.market.lock();
+        if !market.open {
+            return;
+        }
+        let Some(checkpoint) = &mut market.checkpoint else {
+            return;
+        };
+        if !checkpoint.due {
+            if Instant::now() < checkpoint.next_at {
+                return;
+            }
+            checkpoint.due = true;
+        }
+        let index = checkpoint.parked.len();
+        checkpoint.parked.push(Some(std::mem::take(jobs)));
+        log::trace!(
+            "{}: Parked for checkpoint.",
+            std::thread::current().name().unwrap_or_default()
+        );
+        if market.write_checkpoint_if_ready() {
+            self.has_new_jobs.notify_all();
+        }
+        while market.open && market.checkpoint.as_ref().is_some_and(|c| c.due) {
+            self.has_new_jobs.wait(&mut market);
+        }
+        let checkpoint = market.checkpoint.as_mut().unwrap();
+        *jobs = checkpoint.parked[index].take().unwrap();
+        if checkpoint.parked.iter().all(Option::is_none) {
+            checkpoint.parked.clear();
+        }
+    }
+
+    /// Shares jobs with the markets of other processes via `network`. See [`JobBroker::send`] and
+    /// [`JobBroker::exchange`].
+    pub fn network(&mut self, network: Network<Job>) {
+        self.market.lock().network = Some(network);
+    }
+
+    /// Sends jobs to the market of another process, which receives them during its next
+    /// [`JobBroker::exchange`]. Jobs are buffered until then.
+    pub fn send(&mut self, process: usize, jobs: &[Job]) -> io::Result<()> {
+        let mut market = self.market.lock();
+        let network = market
+            .network
+            .as_mut()
+            .expect("The market is not connected to other processes");
+        network.send(process, jobs)
+    }
+
+    /// Sends `summary` to every other process following the jobs sent since the previous
+    /// exchange, then blocks until the same is received from every other process. Returns the
+    /// jobs received and the summaries of the other processes in order of their indices.
+    pub fn exchange<Summary>(&mut self, summary: &Summary) -> io::Result<(Vec<Job>, Vec<Summary>)>
+    where
+        Summary: Serialize + DeserializeOwned,
+    {
+        // The market is not locked while awaiting the other processes.
+        let mut network = self
+            .market
+            .lock()
+            .network
+            .take()
+            .expect("The market is not connected to other processes");
+        let result = network.exchange(summary);
+        self.market.lock().network = Some(network);
+        result
+    }
+
+    /// Reopens a market that closed once its jobs were done, so that threads can work on more
+    /// jobs.
+    pub fn reopen(&mut self) {
+        let mut market = self.market.lock();
+        market.open = true;
+        market.open_count = market.thread_count;
+    }
+
+    /// Restores jobs from a checkpoint, bypassing any spill.
+    pub fn restore(&mut self, batches: Vec<VecDeque<Job>>) {
+        let mut market = self.market.lock();
+        // An empty batch would signal that there are no more jobs when popped.
+        for jobs in batches.into_iter().filter(|jobs| !jobs.is_empty()) {
+            market.job_count += jobs.len();
+            market.job_batches.push(jobs);
+        }
+        self.has_new_jobs.notify_all();
+    }
+
     /// Push a new set of job batches into the market.
     pub fn push(&mut self, jobs: VecDeque<Job>) {
         let mut market = self.market.lock();
         if !market.open {
             return;
         }
-        market.job_batches.push(jobs);
+        let job_count = market.job_count + jobs.len();
+        match &mut market.spill {
+            Some(spill) if job_count > spill.memory_limit => spill.write(jobs),
+            _ => {
+                market.job_count = job_count;
+                market.job_batches.push(jobs);
+            }
+        }
         log::trace!(
             "{}: Pushing jobs. running={}",
             std::thread::current().name().unwrap_or_default(),
@@ -161,6 +328,7 @@ impl<Job> JobBroker<Job> {
             if to_share.is_empty() {
                 continue;
             }
+            market.job_count += to_share.len();
             market.job_batches.push(to_share);
             self.has_new_jobs.notify_one();
         }
@@ -169,6 +337,360 @@ impl<Job> JobBroker<Job> {
     /// See whether the market is closed.
     pub fn is_closed(&self) -> bool {
         let market = self.market.lock();
-        !market.open && market.job_batches.is_empty() && market.open_count == 0
+        !market.open
+            && market.job_batches.is_empty()
+            && market.spill.as_ref().is_none_or(Spill::is_empty)
+            && market.open_count == 0
+    }
+}
+
+impl<Job> JobMarket<Job> {
+    /// Writes a checkpoint if one is due and every working thread has parked its jobs, returning
+    /// whether it did.
+    fn write_checkpoint_if_ready(&mut self) -> bool {
+        // A closed market may have discarded jobs.
+        if !self.open {
+            return false;
+        }
+        let Some(checkpoint) = &mut self.checkpoint else {
+            return false;
+        };
+        let parked_count = checkpoint.parked.iter().filter(|p| p.is_some()).count();
+        if !checkpoint.due || parked_count < self.open_count {
+            return false;
+        }
+        log::debug!("Writing checkpoint.");
+        let mut batches: Vec<_> = self.job_batches.iter().collect();
+        batches.extend(checkpoint.parked.iter().flatten());
+        (checkpoint.write)((batches, self.spill.as_ref()));
+        checkpoint.due = false;
+        checkpoint.next_at = Instant::now() + checkpoint.interval;
+        true
+    }
+}
+
+/// Each connection between processes starts with this followed by the index of the process that
+/// opened it, which distinguishes the other processes from stray connections.
+const HANDSHAKE: &[u8] = b"stateright";
+
+/// The most jobs sent to another process per frame.
+const JOBS_PER_FRAME: usize = 4096;
+
+/// The most bytes of jobs or of a summary in one frame, which bounds what a process allocates for
+/// a frame that it receives. Frames of jobs are ended once they reach half of this.
+const MAX_FRAME_LEN: usize = 1 << 30;
+
+/// Connections to the markets of the other processes of a distributed check, which extend the
+/// sharing of jobs beyond one process. Every process connects to every other, and frames from each
+/// process are received in the order sent. Jobs are encoded as for a [`Spill`].
+pub(crate) struct Network<Job> {
+    index: usize,
+    outgoing: Vec<Option<BufWriter<TcpStream>>>,
+    incoming: Receiver<(usize, io::Result<Frame>)>,
+    /// Frames received from a process before they were awaited.
+    stashed: Vec<VecDeque<io::Result<Frame>>>,
+    /// Stops the listener once the network is dropped.
+    closed: Arc<AtomicBool>,
+    #[allow(clippy::type_complexity)]
+    encode: Box<dyn Fn(&Job, &mut Vec<u8>) + Send>,
+    #[allow(clippy::type_complexity)]
+    decode: Box<dyn Fn(&mut &[u8]) -> io::Result<Job> + Send>,
+}
+
+impl<Job> Drop for Network<Job> {
+    fn drop(&mut self) {
+        self.closed.store(true, Ordering::Relaxed);
+    }
+}
+
+/// What one process sends another: encoded jobs, or a summary that ends a
+/// [`JobBroker::exchange`].
+enum Frame {
+    Jobs(usize, Vec<u8>),
+    Summary(Vec<u8>),
+}
+
+impl<Job> Network<Job> {
+    /// Listens at the address of process `index` and connects to the other processes, waiting up
+    /// to `connect_timeout` for them to start listening. Connections are only accepted from the
+    /// hosts of the other processes, unless their addresses are unspecified.
+    ///
+    /// Connections are not authenticated beyond their host, so the addresses must not be
+    /// reachable by untrusted hosts. A process that connects more than once, such as when another
+    /// connection from its host presents its index, fails the check rather than have either
+    /// connection be trusted.
+    pub fn connect(
+        index: usize,
+        addresses: &[SocketAddr],
+        connect_timeout: Duration,
+        encode: impl Fn(&Job, &mut Vec<u8>) + Send + 'static,
+        decode: impl Fn(&mut &[u8]) -> io::Result<Job> + Send + 'static,
+    ) -> io::Result<Self> {
+        let listener = TcpListener::bind(addresses[index])?;
+        // Polled so that the listener stops once the network is dropped.
+        listener.set_nonblocking(true)?;
+        let (sender, incoming) = channel();
+        let closed = Arc::new(AtomicBool::new(false));
+        {
+            let addresses = addresses.to_vec();
+            let closed = Arc::clone(&closed);
+            std::thread::Builder::new()
+                .name("network".to_owned())
+                .spawn(move || {
+                    let connected = Arc::new(Mutex::new(vec![false; addresses.len()]));
+                    connected.lock()[index] = true;
+                    while !closed.load(Ordering::Relaxed) {
+                        let stream = match listener.accept() {
+                            Ok((stream, _)) => stream,
+                            Err(err) if err.kind() == io::ErrorKind::WouldBlock => {
+                                sleep(Duration::from_millis(100));
+                                continue;
+                            }
+                            Err(err) => {
+                                log::debug!("Failed to accept a connection. err={err}");
+                                continue;
+                            }
+                        };
+                        let addresses = addresses.clone();
+                        let connected = Arc::clone(&connected);
+                        let sender = sender.clone();
+                        // Handshakes are read separately, so a silent connection cannot delay
+                        // the others.
+                        std::thread::spawn(move || {
+                            let peer = match Self::handshake(&stream, &addresses, connect_timeout) {
+                                Ok(peer) => peer,
+                                Err(err) => {
+                                    log::warn!(
+                                        "Ignoring a connection from {:?}. err={err}",
+                                        stream.peer_addr()
+                                    );
+                                    return;
+                                }
+                            };
+                            if std::mem::replace(&mut connected.lock()[peer], true) {
+                                log::warn!(
+                                    "Process {peer} connected again from {:?}.",
+                                    stream.peer_addr()
+                                );
+                                let _ = sender.send((
+                                    peer,
+                                    Err(io::Error::new(
+                                        io::ErrorKind::InvalidData,
+                                        format!("Process {peer} connected more than once"),
+                                    )),
+                                ));
+                                return;
+                            }
+                            let mut reader = BufReader::new(stream);
+                            loop {
+                                // Fails once the connection is closed.
+                                let frame = Self::read_frame(&mut reader);
+                                let is_closed = frame.is_err();
+                                if sender.send((peer, frame)).is_err() || is_closed {
+                                    return;
+                                }
+                            }
+                        });
+                    }
+                })?;
+        }
+
+        let deadline = Instant::now() + connect_timeout;
+        let mut outgoing = Vec::new();
+        for (peer, address) in addresses.iter().enumerate() {
+            if peer == index {
+                outgoing.push(None);
+                continue;
+            }
+            let stream = loop {
+                match TcpStream::connect(address) {
+                    Ok(stream) => break stream,
+                    Err(err) if Instant::now() < deadline => {
+                        log::debug!("Awaiting process {peer} at {address}. err={err}");
+                        sleep(Duration::from_millis(100));
+                    }
+                    Err(err) => return Err(err),
+                }
+            };
+            stream.set_nodelay(true)?;
+            let mut writer = BufWriter::new(stream);
+            writer.write_all(HANDSHAKE)?;
+            writer.write_all(&(index as u64).to_le_bytes())?;
+            writer.flush()?;
+            outgoing.push(Some(writer));
+        }
+        Ok(Network {
+            index,
+            outgoing,
+            incoming,
+            stashed: addresses.iter().map(|_| VecDeque::new()).collect(),
+            closed,
+            encode: Box::new(encode),
+            decode: Box::new(decode),
+        })
+    }
+
+    /// Reads the index of the process that opened a connection, which must be another process.
+    fn handshake(
+        mut stream: &TcpStream,
+        addresses: &[SocketAddr],
+        timeout: Duration,
+    ) -> io::Result<usize> {
+        let invalid = |message: String| io::Error::new(io::ErrorKind::InvalidData, message);
+        stream.set_nonblocking(false)?;
+        stream.set_read_timeout(Some(timeout))?;
+        let mut handshake = [0; HANDSHAKE.len() + 8];
+        stream.read_exact(&mut handshake)?;
+        stream.set_read_timeout(None)?;
+        let (prefix, peer) = handshake.split_at(HANDSHAKE.len());
+        if prefix != HANDSHAKE {
+            return Err(invalid("Not a process of the check".to_owned()));
+        }
+        let peer = u64::from_le_bytes(peer.try_into().unwrap());
+        let Some(peer) = usize::try_from(peer)
+            .ok()
+            .filter(|&peer| peer < addresses.len())
+        else {
+            return Err(invalid(format!("Unexpected process {peer}")));
+        };
+        let expected = addresses[peer].ip();
+        let actual = stream.peer_addr()?.ip();
+        if !expected.is_unspecified() && expected != actual {
+            return Err(invalid(format!(
+                "Process {peer} is at {expected}, not {actual}"
+            )));
+        }
+        Ok(peer)
+    }
+
+    fn send(&mut self, peer: usize, jobs: &[Job]) -> io::Result<()> {
+        let writer = self.outgoing[peer]
+            .as_mut()
+            .expect("A process cannot send to itself");
+        let mut count = 0;
+        let mut bytes = Vec::new();
+        for job in jobs {
+            (self.encode)(job, &mut bytes);
+            count += 1;
+            if count == JOBS_PER_FRAME || bytes.len() >= MAX_FRAME_LEN / 2 {
+                Self::write_frame(writer, &Frame::Jobs(count, std::mem::take(&mut bytes)))?;
+                count = 0;
+            }
+        }
+        if count > 0 {
+            Self::write_frame(writer, &Frame::Jobs(count, bytes))?;
+        }
+        Ok(())
+    }
+
+    fn exchange<Summary>(&mut self, summary: &Summary) -> io::Result<(Vec<Job>, Vec<Summary>)>
+    where
+        Summary: Serialize + DeserializeOwned,
+    {
+        let summary = Frame::Summary(serde_json::to_vec(summary)?);
+        for writer in self.outgoing.iter_mut().flatten() {
+            Self::write_frame(writer, &summary)?;
+            writer.flush()?;
+        }
+        let mut jobs = Vec::new();
+        let mut summaries = Vec::new();
+        let index = self.index;
+        for peer in (0..self.outgoing.len()).filter(|&peer| peer != index) {
+            loop {
+                match self.recv(peer)? {
+                    Frame::Jobs(count, bytes) => {
+                        let mut bytes = bytes.as_slice();
+                        for _ in 0..count {
+                            jobs.push((self.decode)(&mut bytes)?);
+                        }
+                        if !bytes.is_empty() {
+                            return Err(io::Error::new(
+                                io::ErrorKind::InvalidData,
+                                format!("Process {peer} sent more bytes than jobs"),
+                            ));
+                        }
+                    }
+                    Frame::Summary(bytes) => {
+                        summaries.push(serde_json::from_slice(&bytes)?);
+                        break;
+                    }
+                }
+            }
+        }
+        Ok((jobs, summaries))
+    }
+
+    /// Blocks until the next frame from a process arrives.
+    fn recv(&mut self, peer: usize) -> io::Result<Frame> {
+        loop {
+            if let Some(frame) = self.stashed[peer].pop_front() {
+                return frame;
+            }
+            let (from, frame) = self
+                .incoming
+                .recv()
+                .map_err(|_| io::Error::from(io::ErrorKind::BrokenPipe))?;
+            if from == peer {
+                return frame;
+            }
+            self.stashed[from].push_back(frame);
+        }
+    }
+
+    fn write_frame(writer: &mut impl Write, frame: &Frame) -> io::Result<()> {
+        let (count, bytes) = match frame {
+            Frame::Jobs(count, bytes) => (*count as u64, bytes),
+            // Every frame of jobs has at least one job.
+            Frame::Summary(bytes) => (0, bytes),
+        };
+        if bytes.len() > MAX_FRAME_LEN {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("A frame of {} bytes is too large to send", bytes.len()),
+            ));
+        }
+        writer.write_all(&count.to_le_bytes())?;
+        writer.write_all(&(bytes.len() as u64).to_le_bytes())?;
+        writer.write_all(bytes)
+    }
+
+    fn read_frame(reader: &mut impl Read) -> io::Result<Frame> {
+        let mut header = [0; 16];
+        reader.read_exact(&mut header)?;
+        let count = u64::from_le_bytes(header[..8].try_into().unwrap());
+        let len = u64::from_le_bytes(header[8..].try_into().unwrap());
+        if count > JOBS_PER_FRAME as u64 || len > MAX_FRAME_LEN as u64 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("A frame of {count} jobs in {len} bytes is too large"),
+            ));
+        }
+        let (count, len) = (count as usize, len as usize);
+        let mut bytes = vec![0; len];
+        reader.read_exact(&mut bytes)?;
+        Ok(match count {
+            0 => Frame::Summary(bytes),
+            count => Frame::Jobs(count, bytes),
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn rejects_oversized_frames() {
+        let read = |count: u64, len: u64| {
+            let mut bytes = count.to_le_bytes().to_vec();
+            bytes.extend(len.to_le_bytes());
+            bytes.extend([0; 4]);
+            Network::<()>::read_frame(&mut bytes.as_slice())
+        };
+        assert!(matches!(read(1, 4), Ok(Frame::Jobs(1, bytes)) if bytes.len() == 4));
+        assert!(matches!(read(0, 4), Ok(Frame::Summary(_))));
+        assert!(read(0, 5).is_err());
+        assert!(read(JOBS_PER_FRAME as u64 + 1, 4).is_err());
+        assert!(read(1, u64::MAX).is_err());
     }
 }
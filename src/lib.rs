@@ -139,6 +139,7 @@ mod job_market;
 pub mod report;
 use std::fmt::Debug;
 use std::hash::{Hash, Hasher};
+use std::sync::Arc;
 
 #[cfg(test)]
 mod test_util;
@@ -234,6 +235,12 @@ pub trait Model: Sized {
         Vec::new()
     }
 
+    /// Generates named queries for browsing this model with Explorer, which can search for a
+    /// shortest path to a state matching each. Unlike properties, queries are not checked.
+    fn queries(&self) -> Vec<Query<Self>> {
+        Vec::new()
+    }
+
     /// Looks up a property by name. Panics if the property does not exist.
     fn property(&self, name: &'static str) -> Property<Self> {
         if let Some(p) = self.properties().into_iter().find(|p| p.name == name) {
@@ -249,6 +256,22 @@ pub trait Model: Sized {
         true
     }
 
+    /// Identifies the process (e.g. the actor) that takes a step, enabling partial-order
+    /// reduction when requested via [`CheckerBuilder::partial_order_reduction`].
+    ///
+    /// Steps of distinct processes from the same state must be independent: after taking either,
+    /// the other must remain a step of the same process, and taking both in either order must
+    /// reach the same state. [`None`] indicates that the step may interfere with any other, which
+    /// is the default.
+    fn step_process(
+        &self,
+        _last_state: &Self::State,
+        _action: &Self::Action,
+        _next_state: &Self::State,
+    ) -> Option<usize> {
+        None
+    }
+
     /// Instantiates a [`CheckerBuilder`] for this model.
     fn checker(self) -> CheckerBuilder<Self>
     where
@@ -264,57 +287,211 @@ pub trait Model: Sized {
 /// model checker would find a counterexample) or "a proposal is *eventually* accepted" (for
 /// which the model checker would find a counterexample path leading from the initial state
 /// through to a terminal state).
+///
+/// Conditions can be closures, so a property can be parameterized by values only known at
+/// runtime, such as a node count read from the command line. Properties are created via
+/// constructors such as [`Property::always`], and the kind of property is available via
+/// [`Property::expectation`].
 pub struct Property<M: Model> {
-    pub expectation: Expectation,
     pub name: &'static str,
-    pub condition: fn(&M, &M::State) -> bool,
+    pub(crate) predicate: Predicate<M>,
+}
+
+/// What a [`Property`] checks, which determines what the model checker searches for.
+pub(crate) enum Predicate<M: Model> {
+    /// A condition that holds for every reachable state.
+    Always(Condition<M>),
+    /// A condition that eventually holds along every behavior.
+    Eventually(Condition<M>),
+    /// A condition that holds for some reachable state.
+    Sometimes(Condition<M>),
+    /// A condition that holds for every step, given the state before the step, the action taken,
+    /// and the resulting state.
+    Transition(
+        #[allow(clippy::type_complexity)]
+        Arc<dyn Fn(&M, &M::State, &M::Action, &M::State) -> bool + Send + Sync>,
+    ),
+    /// A temporal logic formula that holds for every behavior.
+    Ltl(Ltl<M>),
 }
+
+pub(crate) type Condition<M> = Arc<dyn Fn(&M, &<M as Model>::State) -> bool + Send + Sync>;
+
+impl<M: Model> Clone for Predicate<M> {
+    fn clone(&self) -> Self {
+        match self {
+            Predicate::Always(condition) => Predicate::Always(Arc::clone(condition)),
+            Predicate::Eventually(condition) => Predicate::Eventually(Arc::clone(condition)),
+            Predicate::Sometimes(condition) => Predicate::Sometimes(Arc::clone(condition)),
+            Predicate::Transition(transition) => Predicate::Transition(Arc::clone(transition)),
+            Predicate::Ltl(formula) => Predicate::Ltl(formula.clone()),
+        }
+    }
+}
+
 impl<M: Model> Property<M> {
     /// An invariant that defines a [safety
     /// property](https://en.wikipedia.org/wiki/Safety_property). The model checker will try to
     /// discover a counterexample.
-    pub fn always(name: &'static str, condition: fn(&M, &M::State) -> bool) -> Property<M> {
+    pub fn always(
+        name: &'static str,
+        condition: impl Fn(&M, &M::State) -> bool + Send + Sync + 'static,
+    ) -> Property<M> {
         Property {
-            expectation: Expectation::Always,
             name,
-            condition,
+            predicate: Predicate::Always(Arc::new(condition)),
         }
     }
 
     /// An invariant that defines a [liveness
     /// property](https://en.wikipedia.org/wiki/Liveness). The model checker will try to
-    /// discover a counterexample path leading from the initial state through to a
-    /// terminal state.
+    /// discover a counterexample path leading from the initial state through to either a
+    /// terminal state or a cycle, neither of which satisfy the condition.
     ///
-    /// Note that in this implementation `eventually` properties only work correctly on acyclic
-    /// paths (those that end in either states with no successors or checking boundaries). A path
-    /// ending in a cycle is not viewed as _terminating_ in that cycle, as the checker does not
-    /// differentiate cycles from DAG joins, and so an `eventually` property that has not been met
-    /// by the cycle-closing edge will be ignored -- a false negative.
-    pub fn eventually(name: &'static str, condition: fn(&M, &M::State) -> bool) -> Property<M> {
+    /// A counterexample ending in a cycle is reported as a "lasso" shaped [`Path`] whose last
+    /// action loops back to an earlier state (see [`Path::loop_start`]). The BFS and DFS checkers
+    /// only identify such cycles if [`CheckerBuilder::detect_cycles`] is called, and otherwise
+    /// only report counterexamples ending in a terminal state. Cycles that are unfair with
+    /// respect to [`CheckerBuilder::weak_fairness`] or [`CheckerBuilder::strong_fairness`] are
+    /// not reported.
+    pub fn eventually(
+        name: &'static str,
+        condition: impl Fn(&M, &M::State) -> bool + Send + Sync + 'static,
+    ) -> Property<M> {
         Property {
-            expectation: Expectation::Eventually,
             name,
-            condition,
+            predicate: Predicate::Eventually(Arc::new(condition)),
         }
     }
 
     /// Something that should be possible in the model. The model checker will try to discover an
     /// example.
-    pub fn sometimes(name: &'static str, condition: fn(&M, &M::State) -> bool) -> Property<M> {
+    pub fn sometimes(
+        name: &'static str,
+        condition: impl Fn(&M, &M::State) -> bool + Send + Sync + 'static,
+    ) -> Property<M> {
+        Property {
+            name,
+            predicate: Predicate::Sometimes(Arc::new(condition)),
+        }
+    }
+
+    /// An invariant over each step of the model, which sees the state before the step, the
+    /// action taken, and the resulting state. This is useful for properties such as "a term never
+    /// decreases" that would otherwise require recording history in the state. The model checker
+    /// will try to discover a counterexample path ending at a violating step.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use stateright::{Model, Property};
+    /// # struct Counter;
+    /// # impl Model for Counter {
+    /// #     type State = u8;
+    /// #     type Action = ();
+    /// #     fn init_states(&self) -> Vec<Self::State> { vec![0] }
+    /// #     fn actions(&self, _: &Self::State, _: &mut Vec<Self::Action>) {}
+    /// #     fn next_state(&self, _: &Self::State, _: ()) -> Option<Self::State> { None }
+    /// # }
+    /// let property = Property::<Counter>::always_transition("never decreases", |_, last, _, next| {
+    ///     last <= next
+    /// });
+    /// ```
+    pub fn always_transition(
+        name: &'static str,
+        transition: impl Fn(&M, &M::State, &M::Action, &M::State) -> bool + Send + Sync + 'static,
+    ) -> Property<M> {
         Property {
-            expectation: Expectation::Sometimes,
             name,
-            condition,
+            predicate: Predicate::Transition(Arc::new(transition)),
         }
     }
+
+    /// A [linear temporal logic](https://en.wikipedia.org/wiki/Linear_temporal_logic) formula,
+    /// such as "every request *leads to* a reply", that should hold for every behavior. The model
+    /// checker will try to discover a counterexample path, which either ends in a terminal state
+    /// (treated as repeating forever) or a cycle.
+    ///
+    /// The formula's negation is compiled into a monitor that runs alongside the model. The BFS
+    /// and DFS checkers search for counterexamples once exploration completes, which requires
+    /// [`CheckerBuilder::detect_cycles`], and the simulation checker checks each simulated path
+    /// that ends in a terminal state or a cycle. Temporal logic properties are not checked by the
+    /// on-demand checker.
+    pub fn ltl(name: &'static str, formula: Ltl<M>) -> Property<M> {
+        Property {
+            name,
+            predicate: Predicate::Ltl(formula),
+        }
+    }
+
+    /// Indicates whether the property is always, eventually, or sometimes true. A [transition
+    /// property](Property::always_transition) is always true, and a [temporal logic
+    /// property](Property::ltl) is treated as eventually true, as its counterexamples are
+    /// behaviors rather than individual states.
+    pub fn expectation(&self) -> Expectation {
+        match self.predicate {
+            Predicate::Always(_) | Predicate::Transition(_) => Expectation::Always,
+            Predicate::Eventually(_) | Predicate::Ltl(_) => Expectation::Eventually,
+            Predicate::Sometimes(_) => Expectation::Sometimes,
+        }
+    }
+
+    /// Indicates whether a discovery for this property is a counterexample rather than an
+    /// example, which is the case for every kind of property other than `sometimes`.
+    pub(crate) fn discovery_is_failure(&self) -> bool {
+        self.expectation().discovery_is_failure()
+    }
 }
 impl<M: Model> Clone for Property<M> {
     fn clone(&self) -> Self {
         Property {
-            expectation: self.expectation.clone(),
             name: self.name,
-            condition: self.condition,
+            predicate: self.predicate.clone(),
+        }
+    }
+}
+
+/// A named predicate, such as "a leader was elected twice in one term", identifying states of
+/// interest when browsing a model. Explorer lists the queries returned by [`Model::queries`] and
+/// can search breadth-first for a shortest path to a state satisfying each, which helps with
+/// reaching a particular scenario without clicking through every step by hand.
+///
+/// # Example
+///
+/// ```
+/// use stateright::{Model, Query};
+/// # struct Counter;
+/// # impl Model for Counter {
+/// #     type State = u8;
+/// #     type Action = ();
+/// #     fn init_states(&self) -> Vec<Self::State> { vec![0] }
+/// #     fn actions(&self, _: &Self::State, _: &mut Vec<Self::Action>) {}
+/// #     fn next_state(&self, _: &Self::State, _: ()) -> Option<Self::State> { None }
+/// # }
+/// let query = Query::<Counter>::new("reaches ten", |_, state| *state == 10);
+/// ```
+pub struct Query<M: Model> {
+    pub name: &'static str,
+    #[allow(clippy::type_complexity)]
+    pub condition: Arc<dyn Fn(&M, &M::State) -> bool + Send + Sync>,
+}
+impl<M: Model> Query<M> {
+    /// A query for states satisfying a condition.
+    pub fn new(
+        name: &'static str,
+        condition: impl Fn(&M, &M::State) -> bool + Send + Sync + 'static,
+    ) -> Query<M> {
+        Query {
+            name,
+            condition: Arc::new(condition),
+        }
+    }
+}
+impl<M: Model> Clone for Query<M> {
+    fn clone(&self) -> Self {
+        Query {
+            name: self.name,
+            condition: Arc::clone(&self.condition),
         }
     }
 }
@@ -4,7 +4,7 @@ use std::hash::Hash;
 use std::io::Write;
 use std::time::Duration;
 
-use crate::{DiscoveryClassification, Model, Path};
+use crate::{CompletedDepth, DiscoveryClassification, Model, Path, SimulationStats};
 
 /// The data sent during a report event.
 pub struct ReportData {
@@ -21,6 +21,7 @@ pub struct ReportData {
 }
 
 /// A discovery found during the checking.
+#[non_exhaustive]
 pub struct ReportDiscovery<M>
 where
     M: Model,
@@ -29,6 +30,28 @@ where
     pub path: Path<M::State, M::Action>,
     /// The classification of the path.
     pub classification: DiscoveryClassification,
+    /// The seed of the simulation run that found the discovery, if any (see
+    /// [`Checker::discovery_seed`]).
+    ///
+    /// [`Checker::discovery_seed`]: crate::Checker::discovery_seed
+    pub seed: Option<u64>,
+    /// The number of actions of the path that was found, if `path` was shrunk from it (see
+    /// [`CheckerBuilder::shrink_discoveries`]). The `seed` reproduces the path that was found.
+    ///
+    /// [`CheckerBuilder::shrink_discoveries`]: crate::CheckerBuilder::shrink_discoveries
+    pub shrunk_from: Option<usize>,
+}
+
+impl<M: Model> ReportDiscovery<M> {
+    /// A discovery that was not found by a simulation run.
+    pub fn new(path: Path<M::State, M::Action>, classification: DiscoveryClassification) -> Self {
+        ReportDiscovery {
+            path,
+            classification,
+            seed: None,
+            shrunk_from: None,
+        }
+    }
 }
 
 /// A reporter for progress during the model checking.
@@ -45,6 +68,19 @@ pub trait Reporter<M: Model> {
         M::Action: Debug + PartialEq,
         M::State: Debug + Hash + PartialEq;
 
+    /// Report a depth completed by a checker that deepens iteratively.
+    fn report_completed_depth(&mut self, _depth: CompletedDepth) {}
+
+    /// Report the estimated probability that a state was omitted (see
+    /// [`Checker::omission_probability`]) at the end of the checking run, for a checker that can
+    /// omit states.
+    ///
+    /// [`Checker::omission_probability`]: crate::Checker::omission_probability
+    fn report_omission_probability(&mut self, _probability: f64) {}
+
+    /// Report the statistics of a simulation checker at the end of the checking run.
+    fn report_simulation_stats(&mut self, _stats: SimulationStats) {}
+
     fn delay(&self) -> std::time::Duration {
         std::time::Duration::from_millis(1_000)
     }
@@ -84,6 +120,34 @@ where
         }
     }
 
+    fn report_completed_depth(&mut self, depth: CompletedDepth) {
+        let _ = writeln!(
+            self.writer,
+            "Completed depth {}. states={}, unique={}, discoveries={:?}",
+            depth.depth, depth.state_count, depth.unique_state_count, depth.discoveries,
+        );
+    }
+
+    fn report_omission_probability(&mut self, probability: f64) {
+        let _ = writeln!(self.writer, "Omission probability: {probability:.1e}");
+    }
+
+    fn report_simulation_stats(&mut self, stats: SimulationStats) {
+        fn counts<K: std::fmt::Display>(counts: BTreeMap<K, usize>) -> String {
+            let counts: Vec<_> = counts.iter().map(|(k, n)| format!("{k}={n}")).collect();
+            counts.join(", ")
+        }
+        let terminations = stats
+            .terminations
+            .into_iter()
+            .map(|(termination, n)| (format!("{termination:?}"), n))
+            .collect();
+        let _ = writeln!(self.writer, "Simulation runs: {}", stats.runs);
+        let _ = writeln!(self.writer, "Run terminations: {}", counts(terminations));
+        let _ = writeln!(self.writer, "Run depths: {}", counts(stats.depths));
+        let _ = writeln!(self.writer, "Action counts: {}", counts(stats.actions));
+    }
+
     fn report_discoveries(
         &mut self,
         model: &M,
@@ -103,6 +167,12 @@ where
                 "Action index path: {}",
                 discovery.path.encode(model)
             );
+            if let Some(actions) = discovery.shrunk_from {
+                let _ = writeln!(self.writer, "Shrunk from a path of {actions} actions.");
+            }
+            if let Some(seed) = discovery.seed {
+                let _ = writeln!(self.writer, "Simulation seed: {seed}");
+            }
         }
     }
 }
@@ -93,6 +93,10 @@ pub mod dgraph {
         pub fn check(self) -> impl Checker<Self> {
             self.checker().spawn_bfs().join()
         }
+
+        pub fn check_cycles(self) -> impl Checker<Self> {
+            self.checker().detect_cycles().spawn_bfs().join()
+        }
     }
 
     impl Model for DGraph {
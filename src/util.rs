@@ -151,6 +151,9 @@ impl<V: Hash, S> Hash for HashableHashSet<V, S> {
                 inner_hasher.finish()
             }));
             buffer.sort_unstable();
+            // The length prefix ensures that sequences of sets (e.g. `[{x}, {}]` and `[{}, {x}]`)
+            // hash differently, as with the standard library's collections.
+            hasher.write_usize(buffer.len());
             for v in &*buffer {
                 hasher.write_u64(*v);
             }
@@ -266,6 +269,16 @@ mod hashable_hash_set_test {
         });
         fingerprint(&set); // No assertion as this test is just checking for a panic.
     }
+
+    #[test]
+    fn different_hash_if_elements_move_between_sets() {
+        let mut set = HashableHashSet::new();
+        set.insert(());
+        let fp1 = fingerprint(&vec![set.clone(), HashableHashSet::new()]);
+        let fp2 = fingerprint(&vec![HashableHashSet::new(), set]);
+
+        assert_ne!(fp1, fp2);
+    }
 }
 
 /// A [`HashMap`] wrapper that implements [`Hash`] by sorting pre-hashed entries and feeding those back
@@ -366,6 +379,8 @@ impl<K: Hash, V: Hash, S> Hash for HashableHashMap<K, V, S> {
                 inner_hasher.finish()
             }));
             buffer.sort_unstable();
+            // See the corresponding comment for `HashableHashSet`.
+            state.write_usize(buffer.len());
             for hash in &*buffer {
                 state.write_u64(*hash);
             }
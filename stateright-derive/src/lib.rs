@@ -0,0 +1,135 @@
+//! Derive macros for [Stateright](https://docs.rs/stateright). Rather than depending on this
+//! crate directly, enable the `derive` feature of the `stateright` crate, which re-exports these
+//! macros.
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{format_ident, quote};
+use syn::{parse_macro_input, parse_quote, Data, DeriveInput, Error, Field, Fields};
+
+/// Derives `stateright::Rewrite<Id>` for a struct or enum by rewriting each field, where `Id` is
+/// `stateright::actor::Id`. Type parameters must also implement `Rewrite<Id>`.
+///
+/// Fields annotated with `#[rewrite(skip)]` are cloned rather than rewritten, which is useful for
+/// fields that cannot contain the rewritten type but do not implement `Rewrite` themselves.
+///
+/// ```
+/// use stateright::actor::Id;
+/// use stateright::Rewrite;
+///
+/// #[derive(Clone, Rewrite)]
+/// struct ProcessState {
+///     parent: Id,
+///     children: Vec<Id>,
+///     #[rewrite(skip)]
+///     label: std::path::PathBuf,
+/// }
+/// ```
+#[proc_macro_derive(Rewrite, attributes(rewrite))]
+pub fn derive_rewrite(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand_rewrite(input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+fn expand_rewrite(input: DeriveInput) -> syn::Result<TokenStream2> {
+    let name = &input.ident;
+    let body = match &input.data {
+        Data::Struct(data) => {
+            let (pattern, construction) = rewrite_fields(quote!(Self), &data.fields)?;
+            quote! {
+                let #pattern = self;
+                #construction
+            }
+        }
+        Data::Enum(data) => {
+            let mut arms = Vec::new();
+            for variant in &data.variants {
+                let ident = &variant.ident;
+                let (pattern, construction) =
+                    rewrite_fields(quote!(Self::#ident), &variant.fields)?;
+                arms.push(quote!(#pattern => #construction,));
+            }
+            quote! {
+                match self {
+                    #(#arms)*
+                }
+            }
+        }
+        Data::Union(data) => {
+            return Err(Error::new_spanned(
+                data.union_token,
+                "`Rewrite` cannot be derived for unions",
+            ));
+        }
+    };
+
+    let mut generics = input.generics.clone();
+    for param in generics.type_params_mut() {
+        param
+            .bounds
+            .push(parse_quote!(::stateright::Rewrite<::stateright::actor::Id>));
+    }
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    Ok(quote! {
+        #[automatically_derived]
+        impl #impl_generics ::stateright::Rewrite<::stateright::actor::Id>
+            for #name #ty_generics #where_clause
+        {
+            #[allow(unused_variables)]
+            fn rewrite<__S>(
+                &self,
+                __plan: &::stateright::RewritePlan<::stateright::actor::Id, __S>,
+            ) -> Self {
+                #body
+            }
+        }
+    })
+}
+
+/// Returns a pattern that binds the fields of `path` and an expression that constructs `path`
+/// from the rewritten bindings. Bindings have generated names so that fields cannot shadow the
+/// plan.
+fn rewrite_fields(
+    path: TokenStream2,
+    fields: &Fields,
+) -> syn::Result<(TokenStream2, TokenStream2)> {
+    let mut names = Vec::new();
+    let mut bindings = Vec::new();
+    let mut values = Vec::new();
+    for (i, field) in fields.iter().enumerate() {
+        let binding = format_ident!("__field_{}", i);
+        values.push(if is_skipped(field)? {
+            quote!(::std::clone::Clone::clone(#binding))
+        } else {
+            quote!(::stateright::Rewrite::rewrite(#binding, __plan))
+        });
+        names.extend(field.ident.clone());
+        bindings.push(binding);
+    }
+    Ok(match fields {
+        Fields::Named(_) => (
+            quote!(#path { #(#names: #bindings),* }),
+            quote!(#path { #(#names: #values),* }),
+        ),
+        Fields::Unnamed(_) => (quote!(#path(#(#bindings),*)), quote!(#path(#(#values),*))),
+        Fields::Unit => (path.clone(), path),
+    })
+}
+
+/// Whether a field is annotated with `#[rewrite(skip)]`.
+fn is_skipped(field: &Field) -> syn::Result<bool> {
+    let mut skip = false;
+    for attr in field.attrs.iter().filter(|a| a.path().is_ident("rewrite")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("skip") {
+                skip = true;
+                Ok(())
+            } else {
+                Err(meta.error("unsupported `rewrite` attribute, expected `skip`"))
+            }
+        })?;
+    }
+    Ok(skip)
+}
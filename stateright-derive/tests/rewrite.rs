@@ -0,0 +1,115 @@
+use stateright::actor::Id;
+use stateright::{Rewrite, RewritePlan};
+use std::collections::BTreeSet;
+
+#[derive(Clone, Debug, PartialEq, Rewrite)]
+struct ProcessState<T> {
+    parent: Id,
+    children: BTreeSet<Id>,
+    value: T,
+    #[rewrite(skip)]
+    label: NotRewritable,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+struct NotRewritable(&'static str);
+
+#[derive(Clone, Debug, PartialEq, Rewrite)]
+struct Pair(Id, #[rewrite(skip)] u8);
+
+#[derive(Clone, Debug, PartialEq, Rewrite)]
+struct Empty;
+
+/// Fields may share names with the bindings of the generated code.
+#[derive(Clone, Debug, PartialEq, Rewrite)]
+struct Shadowing {
+    plan: Id,
+    __plan: Id,
+    other: Id,
+}
+
+#[derive(Clone, Debug, PartialEq, Rewrite)]
+enum ShadowingMsg {
+    Plan { plan: Id },
+}
+
+#[derive(Clone, Debug, PartialEq, Rewrite)]
+enum Msg {
+    Ping,
+    Forward(Id, Vec<Msg>),
+    Ack {
+        from: Id,
+        #[rewrite(skip)]
+        note: NotRewritable,
+    },
+}
+
+/// Swaps `Id(0)` and `Id(2)`.
+fn plan() -> RewritePlan<Id, stateright::util::DenseNatMap<Id, Id>> {
+    RewritePlan::from_values_to_sort(&vec![2, 1, 0])
+}
+
+#[test]
+fn rewrites_struct_fields() {
+    let state = ProcessState {
+        parent: Id::from(0),
+        children: Id::vec_from([1, 2]).into_iter().collect(),
+        value: vec![Id::from(2)],
+        label: NotRewritable("Id(0)"),
+    };
+    assert_eq!(
+        state.rewrite(&plan()),
+        ProcessState {
+            parent: Id::from(2),
+            children: Id::vec_from([0, 1]).into_iter().collect(),
+            value: vec![Id::from(0)],
+            label: NotRewritable("Id(0)"),
+        }
+    );
+    assert_eq!(Pair(Id::from(2), 2).rewrite(&plan()), Pair(Id::from(0), 2));
+    assert_eq!(Empty.rewrite(&plan()), Empty);
+    assert_eq!(
+        Shadowing {
+            plan: Id::from(0),
+            __plan: Id::from(1),
+            other: Id::from(2),
+        }
+        .rewrite(&plan()),
+        Shadowing {
+            plan: Id::from(2),
+            __plan: Id::from(1),
+            other: Id::from(0),
+        }
+    );
+    assert_eq!(
+        ShadowingMsg::Plan { plan: Id::from(0) }.rewrite(&plan()),
+        ShadowingMsg::Plan { plan: Id::from(2) }
+    );
+}
+
+#[test]
+fn rewrites_enum_variants() {
+    assert_eq!(Msg::Ping.rewrite(&plan()), Msg::Ping);
+    assert_eq!(
+        Msg::Forward(
+            Id::from(0),
+            vec![Msg::Forward(Id::from(1), vec![Msg::Ping])]
+        )
+        .rewrite(&plan()),
+        Msg::Forward(
+            Id::from(2),
+            vec![Msg::Forward(Id::from(1), vec![Msg::Ping])]
+        ),
+    );
+    assert_eq!(
+        Msg::Ack {
+            from: Id::from(2),
+            note: NotRewritable("skipped"),
+        }
+        .rewrite(&plan()),
+        Msg::Ack {
+            from: Id::from(0),
+            note: NotRewritable("skipped"),
+        },
+    );
+}
@@ -1,5 +1,5 @@
 /// Represents the checker status. Reloads periodically until checking completes.
-function Status({done, state_count, unique_state_count, max_depth, model, properties, recent_path}) {
+function Status({done, state_count, unique_state_count, max_depth, model, properties, recent_path, read_only, can_run, can_shut_down, diffs}) {
     let status = this;
 
     status.stateCount = state_count.toLocaleString();
@@ -16,6 +16,10 @@ function Status({done, state_count, unique_state_count, max_depth, model, proper
         return getProperty(p, done)
     });
     status.recentPath = recent_path;
+    status.readOnly = read_only;
+    status.canRun = can_run;
+    status.canShutDown = can_shut_down;
+    status.diffs = diffs;
 }
 
 /// Placeholder status.
@@ -27,6 +31,10 @@ Status.LOADING = new Status({
     model: 'loading...',
     properties: [],
     recent_path: 'loading...',
+    read_only: true,
+    can_run: false,
+    can_shut_down: false,
+    diffs: false,
 });
 
 function getProperty(p, done) {
@@ -44,6 +52,7 @@ function getProperty(p, done) {
                     case 'Sometimes':
                         return '✅ Example found: ';
                     case 'Eventually':
+                    case 'Ltl':
                         return '⚠️ Counterexample found: ';
                     default:
                         throw new Error(`Invalid expectation ${expectation}.`);
@@ -60,6 +69,8 @@ function getProperty(p, done) {
                         return '⚠️ Example not found: ';
                     case 'Eventually':
                         return '✅ Liveness holds: ';
+                    case 'Ltl':
+                        return '✅ Property holds: ';
                     default:
                         throw new Error(`Invalid expectation ${expectation}.`);
                 }
@@ -95,6 +106,7 @@ function getPropertyForState(p, path) {
                     case 'Sometimes':
                         return ['✅', ' Example found: '];
                     case 'Eventually':
+                    case 'Ltl':
                         return ['⚠️', ' Counterexample found: '];
                     default:
                         throw new Error(`Invalid expectation ${expectation}.`);
@@ -108,6 +120,8 @@ function getPropertyForState(p, path) {
                     return ['⚠️', ' Example not found: '];
                 case 'Eventually':
                     return ['✅', ' Liveness holds: '];
+                case 'Ltl':
+                    return ['✅', ' Property holds: '];
                 default:
                     throw new Error(`Invalid expectation ${expectation}.`);
             }
@@ -125,13 +139,14 @@ function getPropertyForState(p, path) {
 
 
 /// Represents a model step. Only loads next steps on demand.
-function Step({action, outcome, state, actionIndex, properties, prevStep, svg}) {
+function Step({action, outcome, state, actionIndex, properties, prevStep, svg, diff}) {
     let step = this;
 
     step.action = action || `Init ${actionIndex !== undefined ? actionIndex : 'unknown'}`;
     step.outcome = outcome;
     step.state = state;
     step.svg = svg;
+    step.diff = diff;
     step.actionIndex = actionIndex;
     step.prevStep = prevStep;
 
@@ -179,6 +194,7 @@ function Step({action, outcome, state, actionIndex, properties, prevStep, svg})
                     outcome: nextStep.outcome,
                     state: nextStep.state,
                     svg: nextStep.svg,
+                    diff: nextStep.diff,
                     actionIndex: nextStep.actionIndex,
                     properties: nextStep.properties,
                     prevStep: step,
@@ -204,6 +220,115 @@ Step.PRE_INIT = new Step({
     prevStep: null,
 });
 
+/// Renders the neighbourhood of a state as SVG, with a column of nodes per depth. Each node links
+/// to its state and shows the state when hovered, and each edge is labelled by its action.
+function renderGraph({nodes, edges}) {
+    const escape = (text) => String(text)
+        .replace(/&/g, '&amp;')
+        .replace(/</g, '&lt;')
+        .replace(/>/g, '&gt;')
+        .replace(/"/g, '&quot;')
+        .replace(/'/g, '&#39;');
+    const truncate = (text, length) => text.length <= length
+        ? text
+        : text.substring(0, length - 3) + '...';
+    const columnWidth = 220;
+    const rowHeight = 60;
+    const radius = 16;
+    const top = 40; // Room for edges that bend above the first row.
+
+    let positions = {};
+    let rows = [];
+    for (let node of nodes) {
+        let row = rows[node.depth] || 0;
+        rows[node.depth] = row + 1;
+        positions[node.fingerprint] = {
+            x: radius + 10 + node.depth * columnWidth,
+            y: top + radius + row * rowHeight,
+        };
+    }
+    let width = rows.length * columnWidth;
+    let height = top + Math.max(...rows, 1) * rowHeight;
+
+    let svg = `<svg version='1.1' baseProfile='full' width='${width}' height='${height}' xmlns='http://www.w3.org/2000/svg'>`;
+    for (let {source, target, action} of edges) {
+        let from = positions[source];
+        let to = positions[target];
+        let label = escape(truncate(action, 24));
+        if (source === target) {
+            svg += `<path d='M ${from.x} ${from.y - radius} a ${radius} ${radius} 0 1 1 ${radius} ${radius}' class='svg-graph-edge'><title>${escape(action)}</title></path>`;
+            svg += `<text x='${from.x + radius}' y='${from.y - radius - 10}' class='svg-graph-label'>${label}</text>`;
+            continue;
+        }
+        // Edges bend so that those between the same pair of columns can be told apart.
+        let bend = (to.y - from.y) / 4 + (to.x <= from.x ? rowHeight / 2 : 0);
+        let midX = (from.x + to.x) / 2;
+        let midY = (from.y + to.y) / 2 - bend;
+        svg += `<path d='M ${from.x} ${from.y} Q ${midX} ${midY} ${to.x} ${to.y}' class='svg-graph-edge'><title>${escape(action)}</title></path>`;
+        svg += `<text x='${midX}' y='${(from.y + to.y + midY * 2) / 4 - 4}' text-anchor='middle' class='svg-graph-label'>${label}</text>`;
+    }
+    for (let node of nodes) {
+        let {x, y} = positions[node.fingerprint];
+        svg += `<a href='#/steps/${node.path}'>`;
+        svg += `<circle cx='${x}' cy='${y}' r='${radius}' class='svg-graph-node${node.depth === 0 ? ' is-root' : ''}'><title>${escape(node.state)}</title></circle>`;
+        svg += `<text x='${x}' y='${y + radius + 12}' text-anchor='middle' class='svg-graph-node-label'>${escape(node.fingerprint.substring(0, 6))}</text>`;
+        svg += '</a>';
+    }
+    return svg + '</svg>';
+}
+
+/// Renders the changes from one state to the next as an HTML table with a row per changed value,
+/// showing the value before and after side by side. Runs of unchanged values, such as the states
+/// of actors that did not take the step, are collapsed into a single row.
+function renderDiff(diff) {
+    const escape = (text) => String(text)
+        .replace(/&/g, '&amp;')
+        .replace(/</g, '&lt;')
+        .replace(/>/g, '&gt;');
+    if (diff.kind === 'unchanged') {
+        return 'No changes.';
+    }
+
+    let rows = '';
+    const row = (kind, depth, label, before, after) => {
+        rows += `<tr class='is-${kind}'>`
+            + `<td style='padding-left: ${depth}em'>${escape(label)}</td>`
+            + `<td>${escape(before)}</td><td>${escape(after)}</td></tr>`;
+    };
+    const walk = (diff, depth, label) => {
+        switch (diff.kind) {
+            case 'changed': row('changed', depth, label, diff.before, diff.after); break;
+            case 'added': row('added', depth, label, '', diff.after); break;
+            case 'removed': row('removed', depth, label, diff.before, ''); break;
+            case 'entries': {
+                if (label) {
+                    row('entries', depth, label, '', '');
+                    ++depth;
+                }
+                let unchanged = 0;
+                const collapse = () => {
+                    if (unchanged > 0) {
+                        row('unchanged', depth, `${unchanged} unchanged`, '', '');
+                        unchanged = 0;
+                    }
+                };
+                for (let {key, diff: entryDiff} of diff.entries) {
+                    if (entryDiff.kind === 'unchanged') {
+                        ++unchanged;
+                        continue;
+                    }
+                    collapse();
+                    walk(entryDiff, depth, key === null ? '' : key);
+                }
+                collapse();
+                break;
+            }
+        }
+    };
+    walk(diff, 0, '');
+    return `<table class='diff-table'><tr><th></th><th>Before</th><th>After</th></tr>${rows}</table>`;
+}
+
 /// Manages app state.
 function App() {
     let app = this;
@@ -236,10 +361,57 @@ function App() {
         }
     };
     app.status = ko.observable(Status.LOADING);
+    app.previewStep = ko.observable(null);
+    app.diffStep = ko.pureComputed(() => app.previewStep() || app.selectedStep());
+    app.diffHtml = ko.pureComputed(() => {
+        let {diff} = app.diffStep();
+        return diff ? renderDiff(diff) : 'No previous state.';
+    });
+    app.showGraph = ko.observable(false);
+    app.graphDepth = ko.observable(2);
+    app.graphSvg = ko.observable('');
+    ko.computed(async () => {
+        if (!app.showGraph()) {
+            return;
+        }
+        let url = `/.graph${app.selectedStep().path}?depth=${app.graphDepth()}`;
+        console.log('Fetching graph.', {url});
+        try {
+            let response = await fetch(url);
+            app.graphSvg(renderGraph(await response.json()));
+        } catch (err) {
+            console.log('Failed to fetch graph.', {url, err});
+        }
+    });
+
+    app.queries = ko.observableArray([]);
+    app.queryMessage = ko.observable('');
+    app.runQuery = async (name) => {
+        let url = `/.queries/${encodeURIComponent(name)}`;
+        app.queryMessage(`Searching for "${name}"...`);
+        try {
+            let {path, state_count} = await (await fetch(url)).json();
+            if (path) {
+                app.queryMessage('');
+                window.location = '#/steps/' + path;
+            } else {
+                app.queryMessage(`No match for "${name}" within ${state_count} states.`);
+            }
+        } catch (err) {
+            console.log('Failed to run query.', {url, err});
+            app.queryMessage(`Failed to run "${name}".`);
+        }
+    };
 
     window.onhashchange = prepareView;
     window.onhashchange();
     refreshStatus();
+    fetchQueries();
+
+    async function fetchQueries() {
+        let response = await fetch('/.queries');
+        app.queries(await response.json());
+    }
 
     async function refreshStatus() {
         console.log('Refreshing status.');
@@ -307,5 +479,14 @@ async function runToCompletion() {
     console.log(response);
 }
 
+async function shutDown() {
+    if (!window.confirm('Shut down Explorer? The page will stop responding.')) {
+        return;
+    }
+    console.log("shutting down");
+    let response = await fetch('/.shutdown', {method: 'POST'});
+    console.log(response);
+}
+
 window.app = new App();
 ko.applyBindings(window.app);